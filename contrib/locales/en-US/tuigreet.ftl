@@ -3,24 +3,40 @@ title_command = Change session command
 title_power = Power options
 title_session = Change session
 title_users = Select a user
+title_users_loading = Select a user (loading...)
+title_vts = Switch to a running session
+title_layouts = Keyboard layout
 
 action_reset = Reset
 action_command = Change command
 action_session = Choose session
 action_power = Power
+action_vts = Switch VT
+action_layouts = Layout
 
 date = %a, %d %h %Y - %H:%M
+system_info = {$hostname} - {$system} {$release} {$machine}
 
 select_user = Press Enter to select a user or start typing...
 username = Username:
+username_empty = Username cannot be empty
+username_forbidden = Usernames cannot contain "{$character}"
 wait = Please wait...
 failed = Authentication failed, please try again.
 
 new_command = New command:
 
+line_commands = Type /session, /power or /command instead of a username to choose a session, a power option or a command.
+line_choice = Number:
+
 shutdown = Shut down
 reboot = Reboot
 
+vt_switch = Switch to tty{$vt} ({$username})
+vt_new_session = Start a new session
+vt_switch_failed = Could not switch VT
+layout_failed = Could not load the keyboard layout
+
 command_missing = No command configured
 command_exited = Command exited with
 command_failed = Command failed
@@ -28,3 +44,18 @@ command_failed = Command failed
 status_command = CMD
 status_session = SESS
 status_caps = CAPS LOCK
+status_num = NUM LOCK
+status_scroll = SCROLL LOCK
+status_layout = KBD
+status_battery = BAT
+status_uptime = UP
+
+battery_charging = charging
+battery_full = full
+battery_ac = on AC
+
+status_network = NET
+network_up = up
+network_down = down
+network_offline = offline
+network_no_route = no route
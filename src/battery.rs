@@ -0,0 +1,177 @@
+use std::{fs, path::Path};
+
+const POWER_SUPPLY_PATH: &str = "/sys/class/power_supply";
+
+// Charge state of a battery, as reported by its `status` attribute.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BatteryStatus {
+  Charging,
+  Discharging,
+  NotCharging,
+  Full,
+  Unknown,
+}
+
+impl BatteryStatus {
+  fn parse(status: &str) -> BatteryStatus {
+    match status {
+      "Charging" => BatteryStatus::Charging,
+      "Discharging" => BatteryStatus::Discharging,
+      "Not charging" => BatteryStatus::NotCharging,
+      "Full" => BatteryStatus::Full,
+      _ => BatteryStatus::Unknown,
+    }
+  }
+}
+
+// State of the batteries and AC adapters of the machine.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PowerSupply {
+  // Average charge of the batteries, in percent.
+  pub capacity:  u8,
+  // Status of the first battery that is not idle, if any.
+  pub status:    BatteryStatus,
+  // Whether an AC adapter is plugged in.
+  pub ac_online: bool,
+}
+
+impl PowerSupply {
+  // Reads the power supplies from sysfs, returning `None` on machines without
+  // a battery.
+  pub fn read() -> Option<PowerSupply> {
+    PowerSupply::read_from(Path::new(POWER_SUPPLY_PATH))
+  }
+
+  // Reads the power supplies listed in the given class directory.
+  pub fn read_from(root: &Path) -> Option<PowerSupply> {
+    let mut entries: Vec<_> = fs::read_dir(root).ok()?.flatten().collect();
+    entries.sort_by_key(|entry| entry.file_name());
+
+    let mut capacities = Vec::new();
+    let mut statuses = Vec::new();
+    let mut ac_online = false;
+
+    for entry in entries {
+      let path = entry.path();
+      let attribute = |name: &str| {
+        fs::read_to_string(path.join(name))
+          .ok()
+          .map(|value| value.trim().to_string())
+      };
+
+      match attribute("type").as_deref() {
+        Some("Battery") => {
+          // Peripherals such as mice report their battery under the same
+          // class, but are not powering the machine.
+          if attribute("scope").as_deref() == Some("Device") {
+            continue;
+          }
+
+          if let Some(capacity) =
+            attribute("capacity").and_then(|value| value.parse::<u8>().ok())
+          {
+            capacities.push(capacity.min(100));
+            statuses.push(BatteryStatus::parse(
+              attribute("status").as_deref().unwrap_or_default(),
+            ));
+          }
+        },
+        Some("Mains") => {
+          ac_online |= attribute("online").as_deref() == Some("1")
+        },
+        _ => {},
+      }
+    }
+
+    if capacities.is_empty() {
+      return None;
+    }
+
+    let capacity = capacities.iter().map(|&c| u32::from(c)).sum::<u32>()
+      / capacities.len() as u32;
+
+    let status = statuses
+      .iter()
+      .copied()
+      .find(|status| {
+        matches!(status, BatteryStatus::Charging | BatteryStatus::Discharging)
+      })
+      .unwrap_or(statuses[0]);
+
+    Some(PowerSupply {
+      capacity: capacity as u8,
+      status,
+      ac_online,
+    })
+  }
+
+  // Whether the battery is draining under the given charge, in percent.
+  pub fn is_low(&self, threshold: u8) -> bool {
+    !self.ac_online
+      && self.status != BatteryStatus::Charging
+      && self.capacity < threshold
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{fs, path::Path};
+
+  use tempfile::tempdir;
+
+  use super::{BatteryStatus, PowerSupply};
+
+  fn supply(root: &Path, name: &str, attributes: &[(&str, &str)]) {
+    let dir = root.join(name);
+    fs::create_dir(&dir).unwrap();
+
+    for (attribute, value) in attributes {
+      fs::write(dir.join(attribute), format!("{value}\n")).unwrap();
+    }
+  }
+
+  #[test]
+  fn batteries_and_ac() {
+    let dir = tempdir().unwrap();
+    let root = dir.path();
+
+    assert_eq!(PowerSupply::read_from(root), None);
+
+    supply(root, "AC", &[("type", "Mains"), ("online", "0")]);
+    supply(root, "hidpp_battery_0", &[
+      ("type", "Battery"),
+      ("scope", "Device"),
+      ("capacity", "5"),
+    ]);
+
+    assert_eq!(PowerSupply::read_from(root), None);
+
+    supply(root, "BAT0", &[
+      ("type", "Battery"),
+      ("capacity", "12"),
+      ("status", "Not charging"),
+    ]);
+    supply(root, "BAT1", &[
+      ("type", "Battery"),
+      ("capacity", "40"),
+      ("status", "Discharging"),
+    ]);
+
+    let power = PowerSupply::read_from(root).unwrap();
+
+    assert_eq!(power, PowerSupply {
+      capacity:  26,
+      status:    BatteryStatus::Discharging,
+      ac_online: false,
+    });
+    assert!(power.is_low(30));
+    assert!(!power.is_low(20));
+
+    fs::write(root.join("AC/online"), "1\n").unwrap();
+
+    let power = PowerSupply::read_from(root).unwrap();
+
+    assert!(power.ac_online);
+    assert!(!power.is_low(30));
+  }
+}
@@ -2,7 +2,18 @@ use std::env;
 
 use crate::config::{
   Config,
-  schema::{AlignGreeting, SecretMode, WidgetPosition},
+  schema::{
+    AlignGreeting,
+    ColorDepthMode,
+    ContainerAnchor,
+    GlyphsMode,
+    IdleMode,
+    KeyboardLayoutConfig,
+    LineMode,
+    SecretMode,
+    StatusSegment,
+    WidgetPosition,
+  },
 };
 
 /// Apply environment variable overrides to configuration
@@ -28,6 +39,17 @@ pub fn apply_env_vars(config: &mut Config) {
     config.session.command = Some(value);
   }
 
+  if let Ok(value) = env::var("TUIGREET_VT_SWITCH") {
+    if let Ok(vt_switch) = parse_bool(&value) {
+      config.session.vt_switch = vt_switch;
+    } else {
+      tracing::warn!(
+        "Invalid TUIGREET_VT_SWITCH value: '{}', expected true/false",
+        value
+      );
+    }
+  }
+
   // Display config
   if let Ok(value) = env::var("TUIGREET_TIME") {
     if let Ok(show_time) = parse_bool(&value) {
@@ -44,10 +66,65 @@ pub fn apply_env_vars(config: &mut Config) {
     config.display.time_format = Some(value);
   }
 
+  if let Ok(value) = env::var("TUIGREET_BIG_CLOCK") {
+    if let Ok(big_clock) = parse_bool(&value) {
+      config.display.big_clock = big_clock;
+    } else {
+      tracing::warn!(
+        "Invalid TUIGREET_BIG_CLOCK value: '{}', expected true/false",
+        value
+      );
+    }
+  }
+
+  if let Ok(value) = env::var("TUIGREET_BIG_CLOCK_FORMAT") {
+    config.display.big_clock_format = Some(value);
+  }
+
+  if let Ok(value) = env::var("TUIGREET_BIG_CLOCK_DATE") {
+    config.display.big_clock_date = Some(value);
+  }
+
   if let Ok(value) = env::var("TUIGREET_GREETING") {
     config.display.greeting = Some(value);
   }
 
+  if let Ok(value) = env::var("TUIGREET_GREETING_FILE") {
+    config.display.greeting_file = Some(value);
+  }
+
+  if let Ok(value) = env::var("TUIGREET_GREETING_COMMAND") {
+    config.display.greeting_command = Some(value);
+  }
+
+  if let Ok(value) = env::var("TUIGREET_GREETING_COMMAND_TIMEOUT") {
+    if let Ok(timeout) = value.parse::<u64>() {
+      config.display.greeting_command_timeout = Some(timeout);
+    } else {
+      tracing::warn!(
+        "Invalid TUIGREET_GREETING_COMMAND_TIMEOUT value: '{}', expected \
+         number",
+        value
+      );
+    }
+  }
+
+  if let Ok(value) = env::var("TUIGREET_GREETING_COMMAND_REFRESH") {
+    if let Ok(refresh) = value.parse::<u64>() {
+      config.display.greeting_command_refresh = Some(refresh);
+    } else {
+      tracing::warn!(
+        "Invalid TUIGREET_GREETING_COMMAND_REFRESH value: '{}', expected \
+         number",
+        value
+      );
+    }
+  }
+
+  if let Ok(value) = env::var("TUIGREET_GREETING_RANDOM") {
+    config.display.greeting_random = Some(value);
+  }
+
   if let Ok(value) = env::var("TUIGREET_ISSUE") {
     if let Ok(issue) = parse_bool(&value) {
       config.display.issue = issue;
@@ -59,6 +136,121 @@ pub fn apply_env_vars(config: &mut Config) {
     }
   }
 
+  if let Ok(value) = env::var("TUIGREET_COLOR_DEPTH") {
+    match value.to_lowercase().as_str() {
+      "auto" => config.display.color_depth = ColorDepthMode::Auto,
+      "truecolor" => config.display.color_depth = ColorDepthMode::Truecolor,
+      "256" => config.display.color_depth = ColorDepthMode::Indexed,
+      "16" => config.display.color_depth = ColorDepthMode::Basic,
+      _ => {
+        tracing::warn!(
+          "Invalid TUIGREET_COLOR_DEPTH value: '{}', expected 'auto', \
+           'truecolor', '256', or '16'",
+          value
+        );
+      },
+    }
+  }
+
+  if let Ok(value) = env::var("TUIGREET_VT_PALETTE") {
+    if let Ok(vt_palette) = parse_bool(&value) {
+      config.display.vt_palette = vt_palette;
+    } else {
+      tracing::warn!(
+        "Invalid TUIGREET_VT_PALETTE value: '{}', expected true/false",
+        value
+      );
+    }
+  }
+
+  if let Ok(value) = env::var("TUIGREET_GLYPHS") {
+    match value.to_lowercase().as_str() {
+      "auto" => config.display.glyphs = GlyphsMode::Auto,
+      "unicode" => config.display.glyphs = GlyphsMode::Unicode,
+      "ascii" => config.display.glyphs = GlyphsMode::Ascii,
+      "nerdfont" => config.display.glyphs = GlyphsMode::Nerdfont,
+      _ => {
+        tracing::warn!(
+          "Invalid TUIGREET_GLYPHS value: '{}', expected 'auto', 'unicode', \
+           'ascii', or 'nerdfont'",
+          value
+        );
+      },
+    }
+  }
+
+  if let Ok(value) = env::var("TUIGREET_IDLE_TIMEOUT") {
+    if let Ok(seconds) = value.parse::<u64>() {
+      config.display.idle_timeout = Some(seconds);
+    } else {
+      tracing::warn!(
+        "Invalid TUIGREET_IDLE_TIMEOUT value: '{}', expected number of seconds",
+        value
+      );
+    }
+  }
+
+  if let Ok(value) = env::var("TUIGREET_IDLE_MODE") {
+    match value.to_lowercase().as_str() {
+      "blank" => config.display.idle_mode = IdleMode::Blank,
+      "clock" => config.display.idle_mode = IdleMode::Clock,
+      _ => {
+        tracing::warn!(
+          "Invalid TUIGREET_IDLE_MODE value: '{}', expected 'blank' or 'clock'",
+          value
+        );
+      },
+    }
+  }
+
+  if let Ok(value) = env::var("TUIGREET_BATTERY_LOW") {
+    if let Ok(percent) = value.parse::<u8>() {
+      config.display.battery_low = Some(percent);
+    } else {
+      tracing::warn!(
+        "Invalid TUIGREET_BATTERY_LOW value: '{}', expected percentage",
+        value
+      );
+    }
+  }
+
+  if let Ok(value) = env::var("TUIGREET_MOUSE") {
+    if let Ok(mouse) = parse_bool(&value) {
+      config.display.mouse = mouse;
+    } else {
+      tracing::warn!(
+        "Invalid TUIGREET_MOUSE value: '{}', expected true/false",
+        value
+      );
+    }
+  }
+
+  if let Ok(value) = env::var("TUIGREET_SCREEN_READER") {
+    if let Ok(screen_reader) = parse_bool(&value) {
+      config.display.screen_reader = screen_reader;
+    } else {
+      tracing::warn!(
+        "Invalid TUIGREET_SCREEN_READER value: '{}', expected true/false",
+        value
+      );
+    }
+  }
+
+  if let Ok(value) = env::var("TUIGREET_LINE_MODE") {
+    match value.to_lowercase().as_str() {
+      "auto" => config.display.line_mode = LineMode::Auto,
+      "always" => config.display.line_mode = LineMode::Always,
+      "never" => config.display.line_mode = LineMode::Never,
+      _ => {
+        tracing::warn!(
+          "Invalid TUIGREET_LINE_MODE value: '{}', expected 'auto', 'always' \
+           or 'never'",
+          value
+        );
+      },
+    }
+  }
+
   // Remember config
   if let Ok(value) = env::var("TUIGREET_REMEMBER") {
     if let Ok(remember) = parse_bool(&value) {
@@ -128,6 +320,86 @@ pub fn apply_env_vars(config: &mut Config) {
     }
   }
 
+  if let Ok(value) = env::var("TUIGREET_USER_MENU_ENUMERATE") {
+    if let Ok(enumerate) = parse_bool(&value) {
+      config.user_menu.enumerate = enumerate;
+    } else {
+      tracing::warn!(
+        "Invalid TUIGREET_USER_MENU_ENUMERATE value: '{}', expected \
+         true/false",
+        value
+      );
+    }
+  }
+
+  if let Ok(value) = env::var("TUIGREET_USER_MENU_MAX_USERS") {
+    if let Ok(max_users) = value.parse::<usize>() {
+      config.user_menu.max_users = max_users;
+    } else {
+      tracing::warn!(
+        "Invalid TUIGREET_USER_MENU_MAX_USERS value: '{}', expected number",
+        value
+      );
+    }
+  }
+
+  if let Ok(value) = env::var("TUIGREET_USER_MENU_TIMEOUT") {
+    if let Ok(timeout) = value.parse::<u64>() {
+      config.user_menu.timeout = timeout;
+    } else {
+      tracing::warn!(
+        "Invalid TUIGREET_USER_MENU_TIMEOUT value: '{}', expected number",
+        value
+      );
+    }
+  }
+
+  // Username normalization
+  if let Ok(value) = env::var("TUIGREET_USERNAME_TRIM") {
+    if let Ok(trim) = parse_bool(&value) {
+      config.username.trim = trim;
+    } else {
+      tracing::warn!(
+        "Invalid TUIGREET_USERNAME_TRIM value: '{}', expected true/false",
+        value
+      );
+    }
+  }
+
+  if let Ok(value) = env::var("TUIGREET_USERNAME_LOWERCASE") {
+    if let Ok(lowercase) = parse_bool(&value) {
+      config.username.lowercase = lowercase;
+    } else {
+      tracing::warn!(
+        "Invalid TUIGREET_USERNAME_LOWERCASE value: '{}', expected true/false",
+        value
+      );
+    }
+  }
+
+  if let Ok(value) = env::var("TUIGREET_USERNAME_STRIP_REALM") {
+    if let Ok(strip_realm) = parse_bool(&value) {
+      config.username.strip_realm = strip_realm;
+    } else {
+      tracing::warn!(
+        "Invalid TUIGREET_USERNAME_STRIP_REALM value: '{}', expected \
+         true/false",
+        value
+      );
+    }
+  }
+
+  if let Ok(value) = env::var("TUIGREET_USERNAME_MAP_DOMAIN") {
+    if let Ok(map_domain) = parse_bool(&value) {
+      config.username.map_domain = map_domain;
+    } else {
+      tracing::warn!(
+        "Invalid TUIGREET_USERNAME_MAP_DOMAIN value: '{}', expected true/false",
+        value
+      );
+    }
+  }
+
   // Layout config
   if let Ok(value) = env::var("TUIGREET_WIDTH") {
     if let Ok(width) = value.parse::<u16>() {
@@ -248,8 +520,50 @@ pub fn apply_env_vars(config: &mut Config) {
     }
   }
 
+  if let Ok(value) = env::var("TUIGREET_KB_LAYOUTS") {
+    if let Ok(key) = value.parse::<u8>() {
+      if (1..=12).contains(&key) {
+        config.keybindings.layouts = key;
+      } else {
+        tracing::warn!(
+          "Invalid TUIGREET_KB_LAYOUTS value: '{}', expected 1-12",
+          value
+        );
+      }
+    } else {
+      tracing::warn!(
+        "Invalid TUIGREET_KB_LAYOUTS value: '{}', expected number",
+        value
+      );
+    }
+  }
+
+  if let Ok(value) = env::var("TUIGREET_KB_VTS") {
+    if let Ok(key) = value.parse::<u8>() {
+      if (1..=12).contains(&key) {
+        config.keybindings.vts = key;
+      } else {
+        tracing::warn!(
+          "Invalid TUIGREET_KB_VTS value: '{}', expected 1-12",
+          value
+        );
+      }
+    } else {
+      tracing::warn!(
+        "Invalid TUIGREET_KB_VTS value: '{}', expected number",
+        value
+      );
+    }
+  }
+
   // Theme config - parse TUIGREET_THEME environment variable
-  if let Ok(value) = env::var("TUIGREET_THEME") {
+  if let Ok(value) = env::var("TUIGREET_THEME")
+    && !value.trim().is_empty()
+    && !value.contains('=')
+  {
+    // A bare name selects a preset
+    config.theme.preset = Some(value.trim().to_string());
+  } else if let Ok(value) = env::var("TUIGREET_THEME") {
     // Parse theme string using the existing `Theme::parse` logic
     // Format: "border=white;text=green;container=blue" etc.
     let mut spec_parts = Vec::new();
@@ -263,7 +577,8 @@ pub fn apply_env_vars(config: &mut Config) {
         // Validate that this is a known theme component
         match key {
           "border" | "text" | "time" | "container" | "title" | "greet"
-          | "prompt" | "input" | "action" | "button" => {
+          | "prompt" | "input" | "action" | "button" | "selected"
+          | "battery_low" | "border_type" => {
             // Apply to the theme config
             match key {
               "border" => config.theme.border = Some(color.to_string()),
@@ -276,10 +591,24 @@ pub fn apply_env_vars(config: &mut Config) {
               "input" => config.theme.input = Some(color.to_string()),
               "action" => config.theme.action = Some(color.to_string()),
               "button" => config.theme.button = Some(color.to_string()),
+              "selected" => config.theme.selected = Some(color.to_string()),
+              "battery_low" => {
+                config.theme.battery_low = Some(color.to_string())
+              },
+              "border_type" => {
+                config.theme.border_type = Some(color.to_string())
+              },
               _ => unreachable!(),
             }
             spec_parts.push(format!("{}={}", key, color));
           },
+          key if key.starts_with("status.") => {
+            config
+              .theme
+              .status
+              .insert(key["status.".len()..].to_string(), color.to_string());
+            spec_parts.push(format!("{}={}", key, color));
+          },
           _ => {
             tracing::warn!(
               "Unknown theme component '{}' in TUIGREET_THEME environment \
@@ -378,6 +707,33 @@ pub fn apply_env_vars(config: &mut Config) {
       .collect();
   }
 
+  if let Ok(value) = env::var("TUIGREET_CONTAINER_ANCHOR") {
+    let anchor = match value.to_lowercase().as_str() {
+      "top_left" => Some(ContainerAnchor::TopLeft),
+      "top" => Some(ContainerAnchor::Top),
+      "top_right" => Some(ContainerAnchor::TopRight),
+      "left" => Some(ContainerAnchor::Left),
+      "center" => Some(ContainerAnchor::Center),
+      "right" => Some(ContainerAnchor::Right),
+      "bottom_left" => Some(ContainerAnchor::BottomLeft),
+      "bottom" => Some(ContainerAnchor::Bottom),
+      "bottom_right" => Some(ContainerAnchor::BottomRight),
+      _ => {
+        tracing::warn!(
+          "Invalid TUIGREET_CONTAINER_ANCHOR value: '{}', expected one of \
+           'top_left', 'top', 'top_right', 'left', 'center', 'right', \
+           'bottom_left', 'bottom', or 'bottom_right'",
+          value
+        );
+        None
+      },
+    };
+
+    if let Some(anchor) = anchor {
+      config.layout.anchor = anchor;
+    }
+  }
+
   // Widget positioning configuration
   if let Ok(value) = env::var("TUIGREET_TIME_POSITION") {
     match value.to_lowercase().as_str() {
@@ -413,7 +769,99 @@ pub fn apply_env_vars(config: &mut Config) {
     }
   }
 
+  if let Ok(value) = env::var("TUIGREET_STATUS_LEFT") {
+    match parse_status_segments(&value) {
+      Ok(segments) => config.status_bar.left = segments,
+      Err(name) => {
+        tracing::warn!(
+          "Invalid TUIGREET_STATUS_LEFT value: '{}', unknown segment '{}'",
+          value,
+          name
+        );
+      },
+    }
+  }
+
+  if let Ok(value) = env::var("TUIGREET_STATUS_RIGHT") {
+    match parse_status_segments(&value) {
+      Ok(segments) => config.status_bar.right = segments,
+      Err(name) => {
+        tracing::warn!(
+          "Invalid TUIGREET_STATUS_RIGHT value: '{}', unknown segment '{}'",
+          value,
+          name
+        );
+      },
+    }
+  }
+
+  if let Ok(value) = env::var("TUIGREET_STATUS_COMMAND_REFRESH") {
+    match value.parse::<u64>() {
+      Ok(seconds) => config.status_bar.command_refresh = Some(seconds),
+      Err(_) => {
+        tracing::warn!(
+          "Invalid TUIGREET_STATUS_COMMAND_REFRESH value: '{}', expected a \
+           number of seconds",
+          value
+        );
+      },
+    }
+  }
+
+  if let Ok(value) = env::var("TUIGREET_NUMLOCK") {
+    if let Ok(numlock) = parse_bool(&value) {
+      config.keyboard.numlock = numlock;
+    } else {
+      tracing::warn!(
+        "Invalid TUIGREET_NUMLOCK value: '{}', expected true/false",
+        value
+      );
+    }
+  }
+
+  if let Ok(value) = env::var("TUIGREET_KEYBOARD_LAYOUTS") {
+    match parse_keyboard_layouts(&value) {
+      Ok(layouts) => config.keyboard.layouts = layouts,
+      Err(layout) => {
+        tracing::warn!(
+          "Invalid TUIGREET_KEYBOARD_LAYOUTS value: '{}', expected \
+           keymap[:layout[:variant]] in '{}'",
+          value,
+          layout
+        );
+      },
+    }
+  }
+
+  if let Ok(value) = env::var("TUIGREET_BIG_CLOCK_POSITION") {
+    match value.to_lowercase().as_str() {
+      "default" => {
+        config.layout.widgets.big_clock_position = WidgetPosition::Default
+      },
+      "top" => config.layout.widgets.big_clock_position = WidgetPosition::Top,
+      "bottom" => {
+        config.layout.widgets.big_clock_position = WidgetPosition::Bottom
+      },
+      "hidden" => {
+        config.layout.widgets.big_clock_position = WidgetPosition::Hidden
+      },
+      _ => {
+        tracing::warn!(
+          "Invalid TUIGREET_BIG_CLOCK_POSITION value: '{}', expected \
+           'default', 'top', 'bottom', or 'hidden'",
+          value
+        );
+      },
+    }
+  }
+
   // Individual theme component environment variables
+  if let Ok(value) = env::var("TUIGREET_THEME_PRESET") {
+    config.theme.preset = Some(value);
+  }
+  if let Ok(value) = env::var("TUIGREET_THEME_FILE") {
+    config.theme.file = Some(value);
+  }
   if let Ok(value) = env::var("TUIGREET_THEME_BORDER") {
     config.theme.border = Some(value);
   }
@@ -444,6 +892,66 @@ pub fn apply_env_vars(config: &mut Config) {
   if let Ok(value) = env::var("TUIGREET_THEME_BUTTON") {
     config.theme.button = Some(value);
   }
+  if let Ok(value) = env::var("TUIGREET_THEME_SELECTED") {
+    config.theme.selected = Some(value);
+  }
+  if let Ok(value) = env::var("TUIGREET_THEME_BATTERY_LOW") {
+    config.theme.battery_low = Some(value);
+  }
+  if let Ok(value) = env::var("TUIGREET_THEME_BORDER_TYPE") {
+    config.theme.border_type = Some(value);
+  }
+}
+
+/// Parse a comma-separated list of status bar segment names, returning the
+/// first unknown name on error
+fn parse_status_segments(value: &str) -> Result<Vec<StatusSegment>, String> {
+  value
+    .split(',')
+    .map(str::trim)
+    .filter(|name| !name.is_empty())
+    .map(|name| {
+      StatusSegment::from_name(&name.to_lowercase()).ok_or(name.to_string())
+    })
+    .collect()
+}
+
+/// Parse a comma-separated list of keyboard layouts, each written as
+/// `keymap[:layout[:variant]]`
+fn parse_keyboard_layouts(
+  value: &str,
+) -> Result<Vec<KeyboardLayoutConfig>, String> {
+  let non_empty = |part: Option<&str>| {
+    part
+      .map(str::trim)
+      .filter(|part| !part.is_empty())
+      .map(str::to_string)
+  };
+
+  value
+    .split(',')
+    .map(str::trim)
+    .filter(|layout| !layout.is_empty())
+    .map(|layout| {
+      let mut parts = layout.split(':');
+
+      match (
+        non_empty(parts.next()),
+        parts.next(),
+        parts.next(),
+        parts.next(),
+      ) {
+        (Some(keymap), layout, variant, None) => {
+          Ok(KeyboardLayoutConfig {
+            keymap,
+            layout: non_empty(layout),
+            variant: non_empty(variant),
+          })
+        },
+        _ => Err(layout.to_string()),
+      }
+    })
+    .collect()
 }
 
 /// Parse a boolean value from string
@@ -478,6 +986,47 @@ mod tests {
     assert_eq!(parse_bool(""), Err(()));
   }
 
+  #[test]
+  fn test_parse_status_segments() {
+    assert_eq!(
+      parse_status_segments("hostname, IP,clock"),
+      Ok(vec![
+        StatusSegment::Hostname,
+        StatusSegment::Ip,
+        StatusSegment::Clock
+      ])
+    );
+    assert_eq!(parse_status_segments(""), Ok(vec![]));
+    assert_eq!(
+      parse_status_segments("hostname,wifi"),
+      Err("wifi".to_string())
+    );
+  }
+
+  #[test]
+  fn test_parse_keyboard_layouts() {
+    assert_eq!(
+      parse_keyboard_layouts("us, de-latin1-nodeadkeys:de:nodeadkeys"),
+      Ok(vec![
+        KeyboardLayoutConfig {
+          keymap:  "us".to_string(),
+          layout:  None,
+          variant: None,
+        },
+        KeyboardLayoutConfig {
+          keymap:  "de-latin1-nodeadkeys".to_string(),
+          layout:  Some("de".to_string()),
+          variant: Some("nodeadkeys".to_string()),
+        },
+      ])
+    );
+    assert_eq!(
+      parse_keyboard_layouts("fr:fr:oss:extra"),
+      Err("fr:fr:oss:extra".to_string())
+    );
+    assert_eq!(parse_keyboard_layouts(":de"), Err(":de".to_string()));
+  }
+
   #[test]
   fn test_env_vars_processed_once_with_multiple_theme_components() {
     unsafe {
@@ -6,7 +6,7 @@ use std::{
 
 use dirs::config_dir;
 
-use crate::config::{Config, ConfigError};
+use crate::config::{Config, ConfigError, LayoutNode, LayoutWidget};
 
 /// Load configuration from the appropriate paths
 pub fn load_config(
@@ -141,6 +141,9 @@ pub fn merge_configs(dest: &mut Config, src: Config) {
   if src.session.environments != defaults.session.environments {
     dest.session.environments = src.session.environments;
   }
+  if src.session.vt_switch != defaults.session.vt_switch {
+    dest.session.vt_switch = src.session.vt_switch;
+  }
 
   // Display config
   if src.display.show_time != defaults.display.show_time {
@@ -152,6 +155,27 @@ pub fn merge_configs(dest: &mut Config, src: Config) {
   if src.display.greeting != defaults.display.greeting {
     dest.display.greeting = src.display.greeting;
   }
+  if src.display.greeting_file != defaults.display.greeting_file {
+    dest.display.greeting_file = src.display.greeting_file;
+  }
+  if src.display.greeting_command != defaults.display.greeting_command {
+    dest.display.greeting_command = src.display.greeting_command;
+  }
+  if src.display.greeting_command_timeout
+    != defaults.display.greeting_command_timeout
+  {
+    dest.display.greeting_command_timeout =
+      src.display.greeting_command_timeout;
+  }
+  if src.display.greeting_command_refresh
+    != defaults.display.greeting_command_refresh
+  {
+    dest.display.greeting_command_refresh =
+      src.display.greeting_command_refresh;
+  }
+  if src.display.greeting_random != defaults.display.greeting_random {
+    dest.display.greeting_random = src.display.greeting_random;
+  }
   if src.display.issue != defaults.display.issue {
     dest.display.issue = src.display.issue;
   }
@@ -160,6 +184,42 @@ pub fn merge_configs(dest: &mut Config, src: Config) {
   if src.display.align_greeting != defaults.display.align_greeting {
     dest.display.align_greeting = src.display.align_greeting;
   }
+  if src.display.color_depth != defaults.display.color_depth {
+    dest.display.color_depth = src.display.color_depth;
+  }
+  if src.display.big_clock != defaults.display.big_clock {
+    dest.display.big_clock = src.display.big_clock;
+  }
+  if src.display.big_clock_format != defaults.display.big_clock_format {
+    dest.display.big_clock_format = src.display.big_clock_format;
+  }
+  if src.display.big_clock_date != defaults.display.big_clock_date {
+    dest.display.big_clock_date = src.display.big_clock_date;
+  }
+  if src.display.vt_palette != defaults.display.vt_palette {
+    dest.display.vt_palette = src.display.vt_palette;
+  }
+  if src.display.glyphs != defaults.display.glyphs {
+    dest.display.glyphs = src.display.glyphs;
+  }
+  if src.display.battery_low.is_some() {
+    dest.display.battery_low = src.display.battery_low;
+  }
+  if src.display.idle_timeout.is_some() {
+    dest.display.idle_timeout = src.display.idle_timeout;
+  }
+  if src.display.idle_mode != defaults.display.idle_mode {
+    dest.display.idle_mode = src.display.idle_mode;
+  }
+  if src.display.mouse != defaults.display.mouse {
+    dest.display.mouse = src.display.mouse;
+  }
+  if src.display.screen_reader != defaults.display.screen_reader {
+    dest.display.screen_reader = src.display.screen_reader;
+  }
+  if src.display.line_mode != defaults.display.line_mode {
+    dest.display.line_mode = src.display.line_mode;
+  }
 
   // Remember config
   if src.remember.username != defaults.remember.username {
@@ -182,6 +242,32 @@ pub fn merge_configs(dest: &mut Config, src: Config) {
   if src.user_menu.max_uid != defaults.user_menu.max_uid {
     dest.user_menu.max_uid = src.user_menu.max_uid;
   }
+  if src.user_menu.enumerate != defaults.user_menu.enumerate {
+    dest.user_menu.enumerate = src.user_menu.enumerate;
+  }
+  if src.user_menu.max_users != defaults.user_menu.max_users {
+    dest.user_menu.max_users = src.user_menu.max_users;
+  }
+  if src.user_menu.timeout != defaults.user_menu.timeout {
+    dest.user_menu.timeout = src.user_menu.timeout;
+  }
+
+  // Username config
+  if src.username.trim != defaults.username.trim {
+    dest.username.trim = src.username.trim;
+  }
+  if src.username.lowercase != defaults.username.lowercase {
+    dest.username.lowercase = src.username.lowercase;
+  }
+  if src.username.strip_realm != defaults.username.strip_realm {
+    dest.username.strip_realm = src.username.strip_realm;
+  }
+  if src.username.map_domain != defaults.username.map_domain {
+    dest.username.map_domain = src.username.map_domain;
+  }
+  if src.username.aliases != defaults.username.aliases {
+    dest.username.aliases = src.username.aliases;
+  }
 
   // Secret config
   if src.secret.mode != defaults.secret.mode {
@@ -214,6 +300,33 @@ pub fn merge_configs(dest: &mut Config, src: Config) {
   {
     dest.layout.widgets.status_position = src.layout.widgets.status_position;
   }
+  if src.layout.widgets.big_clock_position
+    != defaults.layout.widgets.big_clock_position
+  {
+    dest.layout.widgets.big_clock_position =
+      src.layout.widgets.big_clock_position;
+  }
+  if src.layout.anchor != defaults.layout.anchor {
+    dest.layout.anchor = src.layout.anchor;
+  }
+  if src.layout.offset_x != defaults.layout.offset_x {
+    dest.layout.offset_x = src.layout.offset_x;
+  }
+  if src.layout.offset_y != defaults.layout.offset_y {
+    dest.layout.offset_y = src.layout.offset_y;
+  }
+  if src.layout.width_percent.is_some() {
+    dest.layout.width_percent = src.layout.width_percent;
+  }
+  if src.layout.height_percent.is_some() {
+    dest.layout.height_percent = src.layout.height_percent;
+  }
+  if src.layout.tree.is_some() {
+    dest.layout.tree = src.layout.tree;
+  }
+  if src.layout.logo.is_some() {
+    dest.layout.logo = src.layout.logo;
+  }
 
   // Power config
   if src.power.use_setsid != defaults.power.use_setsid {
@@ -230,9 +343,40 @@ pub fn merge_configs(dest: &mut Config, src: Config) {
   if src.keybindings.power != defaults.keybindings.power {
     dest.keybindings.power = src.keybindings.power;
   }
+  if src.keybindings.vts != defaults.keybindings.vts {
+    dest.keybindings.vts = src.keybindings.vts;
+  }
+  if src.keybindings.layouts != defaults.keybindings.layouts {
+    dest.keybindings.layouts = src.keybindings.layouts;
+  }
+
+  // Keyboard config
+  if src.keyboard.numlock != defaults.keyboard.numlock {
+    dest.keyboard.numlock = src.keyboard.numlock;
+  }
+  if !src.keyboard.layouts.is_empty() {
+    dest.keyboard.layouts = src.keyboard.layouts;
+  }
+
+  // Status bar config
+  if src.status_bar.left != defaults.status_bar.left {
+    dest.status_bar.left = src.status_bar.left;
+  }
+  if src.status_bar.right != defaults.status_bar.right {
+    dest.status_bar.right = src.status_bar.right;
+  }
+  if src.status_bar.command_refresh != defaults.status_bar.command_refresh {
+    dest.status_bar.command_refresh = src.status_bar.command_refresh;
+  }
 
   // Theme config
   // We merge individual fields if they're different from defaults
+  if src.theme.preset != defaults.theme.preset {
+    dest.theme.preset = src.theme.preset;
+  }
+  if src.theme.file != defaults.theme.file {
+    dest.theme.file = src.theme.file;
+  }
   if src.theme.border != defaults.theme.border {
     dest.theme.border = src.theme.border;
   }
@@ -263,6 +407,16 @@ pub fn merge_configs(dest: &mut Config, src: Config) {
   if src.theme.button != defaults.theme.button {
     dest.theme.button = src.theme.button;
   }
+  if src.theme.selected != defaults.theme.selected {
+    dest.theme.selected = src.theme.selected;
+  }
+  if src.theme.battery_low != defaults.theme.battery_low {
+    dest.theme.battery_low = src.theme.battery_low;
+  }
+  if src.theme.border_type != defaults.theme.border_type {
+    dest.theme.border_type = src.theme.border_type;
+  }
+  dest.theme.status.extend(src.theme.status);
 }
 
 impl Config {
@@ -274,13 +428,55 @@ impl Config {
     let mut warnings = Vec::new();
 
     // Check mutually exclusive options
-    if self.display.issue && self.display.greeting.is_some() {
+    let greeting_sources: Vec<&str> = [
+      ("display.issue", self.display.issue),
+      ("display.greeting", self.display.greeting.is_some()),
+      (
+        "display.greeting_file",
+        self.display.greeting_file.is_some(),
+      ),
+      (
+        "display.greeting_command",
+        self.display.greeting_command.is_some(),
+      ),
+      (
+        "display.greeting_random",
+        self.display.greeting_random.is_some(),
+      ),
+    ]
+    .into_iter()
+    .filter_map(|(name, set)| set.then_some(name))
+    .collect();
+
+    if let [first, second, ..] = greeting_sources[..] {
       return Err(ConfigError::MutuallyExclusive(
-        "display.issue".to_string(),
-        "display.greeting".to_string(),
+        first.to_string(),
+        second.to_string(),
       ));
     }
 
+    for (name, seconds) in [
+      (
+        "display.greeting_command_timeout",
+        self.display.greeting_command_timeout,
+      ),
+      (
+        "display.greeting_command_refresh",
+        self.display.greeting_command_refresh,
+      ),
+      (
+        "status_bar.command_refresh",
+        self.status_bar.command_refresh,
+      ),
+      ("display.idle_timeout", self.display.idle_timeout),
+    ] {
+      if seconds == Some(0) {
+        return Err(ConfigError::InvalidRange(format!(
+          "{name} must be at least one second"
+        )));
+      }
+    }
+
     if self.remember.session && self.remember.user_session {
       return Err(ConfigError::MutuallyExclusive(
         "remember.session".to_string(),
@@ -302,12 +498,64 @@ impl Config {
       ));
     }
 
-    // Check keybindings are distinct
-    let keys = [
+    if let Some(percent) = self.display.battery_low
+      && percent > 100
+    {
+      return Err(ConfigError::InvalidRange(
+        "display.battery_low must not exceed 100".to_string(),
+      ));
+    }
+
+    // Check container percentages
+    for (name, percent) in [
+      ("layout.width_percent", self.layout.width_percent),
+      ("layout.height_percent", self.layout.height_percent),
+    ] {
+      if let Some(percent) = percent
+        && !(1..=100).contains(&percent)
+      {
+        return Err(ConfigError::InvalidRange(format!(
+          "{name} must be between 1 and 100"
+        )));
+      }
+    }
+
+    // Check the layout tree places the prompt container exactly once, and
+    // that widgets are only drawn by leaves
+    if let Some(ref tree) = self.layout.tree {
+      if tree.count(LayoutWidget::Prompt) != 1 {
+        return Err(ConfigError::Validation(
+          "layout.tree must contain exactly one prompt widget".to_string(),
+        ));
+      }
+
+      fn has_widget_with_children(node: &LayoutNode) -> bool {
+        (node.widget.is_some() && !node.children.is_empty())
+          || node.children.iter().any(has_widget_with_children)
+      }
+
+      if has_widget_with_children(tree) {
+        return Err(ConfigError::Validation(
+          "layout.tree nodes cannot have both a widget and children"
+            .to_string(),
+        ));
+      }
+    }
+
+    // Check keybindings are distinct, the running sessions menu only being
+    // bound when VT switching is enabled, and the keyboard layout menu when
+    // layouts are configured
+    let mut keys = vec![
       self.keybindings.command,
       self.keybindings.sessions,
       self.keybindings.power,
     ];
+    if self.session.vt_switch {
+      keys.push(self.keybindings.vts);
+    }
+    if !self.keyboard.layouts.is_empty() {
+      keys.push(self.keybindings.layouts);
+    }
     if keys.iter().collect::<HashSet<_>>().len() != keys.len() {
       return Err(ConfigError::DuplicateKeybindings);
     }
@@ -317,18 +565,28 @@ impl Config {
       ("command", self.keybindings.command),
       ("sessions", self.keybindings.sessions),
       ("power", self.keybindings.power),
+      ("vts", self.keybindings.vts),
+      ("layouts", self.keybindings.layouts),
     ] {
       if !(1..=12).contains(&key) {
         return Err(ConfigError::InvalidFKey(name.to_string(), key));
       }
     }
 
-    // Validate time format if provided
-    if let Some(ref format) = self.display.time_format
-      && chrono::format::StrftimeItems::new(format)
-        .any(|item| matches!(item, chrono::format::Item::Error))
+    // Validate time formats if provided
+    for format in [
+      &self.display.time_format,
+      &self.display.big_clock_format,
+      &self.display.big_clock_date,
+    ]
+    .into_iter()
+    .flatten()
     {
-      return Err(ConfigError::InvalidTimeFormat);
+      if chrono::format::StrftimeItems::new(format)
+        .any(|item| matches!(item, chrono::format::Item::Error))
+      {
+        return Err(ConfigError::InvalidTimeFormat);
+      }
     }
 
     // Validate session wrapper executables if requested
@@ -543,6 +801,7 @@ fn command_exists(command: &str) -> bool {
 #[cfg(test)]
 mod tests {
   use super::*;
+  use crate::config::{LayoutDirection, LayoutSize, StatusSegment};
 
   #[test]
   fn test_mutual_exclusive_remember_flags() {
@@ -594,6 +853,39 @@ user_session = true
     }
   }
 
+  #[test]
+  fn test_greeting_sources_validation() {
+    let toml_content = r#"
+[display]
+greeting_file = "/etc/motd"
+greeting_command = "fortune"
+"#;
+
+    let config: Config =
+      toml::from_str(toml_content).expect("Failed to parse TOML");
+
+    match config.validate(false) {
+      Err(ConfigError::MutuallyExclusive(opt1, opt2)) => {
+        assert_eq!(opt1, "display.greeting_file");
+        assert_eq!(opt2, "display.greeting_command");
+      },
+      result => panic!("Expected MutuallyExclusive error, got: {:?}", result),
+    }
+
+    let mut config = Config::default();
+    config.display.greeting_command = Some("fortune".to_string());
+    config.display.greeting_command_refresh = Some(600);
+
+    assert!(config.validate(false).is_ok());
+
+    config.display.greeting_command_timeout = Some(0);
+
+    assert!(matches!(
+      config.validate(false),
+      Err(ConfigError::InvalidRange(_))
+    ));
+  }
+
   #[test]
   fn test_keybindings_distinctness_in_config() {
     let toml_content = r#"
@@ -618,6 +910,50 @@ power = 7
     }
   }
 
+  #[test]
+  fn test_vts_keybinding_only_checked_when_enabled() {
+    let toml_content = r#"
+[keybindings]
+power = 4
+"#;
+
+    let mut config: Config =
+      toml::from_str(toml_content).expect("Failed to parse TOML");
+
+    assert!(config.validate(false).is_ok());
+
+    config.session.vt_switch = true;
+
+    assert!(matches!(
+      config.validate(false),
+      Err(ConfigError::DuplicateKeybindings)
+    ));
+  }
+
+  #[test]
+  fn test_keyboard_layouts() {
+    let toml_content = r#"
+[keybindings]
+layouts = 2
+
+[keyboard]
+layouts = [{ keymap = "us" }, { keymap = "fr-bepo", layout = "fr" }]
+"#;
+
+    let mut config: Config =
+      toml::from_str(toml_content).expect("Failed to parse TOML");
+
+    assert_eq!(config.keyboard.layouts[1].layout.as_deref(), Some("fr"));
+    assert!(matches!(
+      config.validate(false),
+      Err(ConfigError::DuplicateKeybindings)
+    ));
+
+    config.keyboard.layouts.clear();
+
+    assert!(config.validate(false).is_ok());
+  }
+
   #[test]
   fn test_session_config_default_consistency() {
     let default_config = Config::default();
@@ -682,6 +1018,22 @@ command = "test"
     );
   }
 
+  #[test]
+  fn test_user_menu_config_default_consistency() {
+    let partial_toml = r#"
+[user_menu]
+enabled = true
+"#;
+    let partial_config: Config =
+      toml::from_str(partial_toml).expect("Failed to parse partial TOML");
+
+    assert_eq!(partial_config.user_menu, crate::config::UserMenuConfig {
+      enabled: true,
+      ..Default::default()
+    });
+    assert!(partial_config.user_menu.enumerate);
+  }
+
   #[test]
   fn test_power_config_default_consistency() {
     let default_config = Config::default();
@@ -757,4 +1109,137 @@ session_wrapper = "   "
       "Whitespace-only wrapper command should fail validation"
     );
   }
+
+  #[test]
+  fn test_status_bar_segments() {
+    let config: Config = toml::from_str(
+      r#"
+[status_bar]
+left = ["hostname", "ip", { command = "cat /etc/role" }]
+command_refresh = 60
+
+[theme.status]
+hostname = "green bold"
+"#,
+    )
+    .expect("Failed to parse TOML");
+
+    assert_eq!(config.status_bar.left, vec![
+      StatusSegment::Hostname,
+      StatusSegment::Ip,
+      StatusSegment::Command("cat /etc/role".to_string()),
+    ]);
+    assert_eq!(config.status_bar.right, vec![StatusSegment::CapsLock]);
+    assert_eq!(
+      config.theme.status.get("hostname").map(String::as_str),
+      Some("green bold")
+    );
+
+    let mut merged = Config::default();
+    merged
+      .theme
+      .status
+      .insert("clock".to_string(), "blue".to_string());
+    merge_configs(&mut merged, config);
+
+    assert_eq!(merged.status_bar.left.len(), 3);
+    assert_eq!(merged.status_bar.command_refresh, Some(60));
+    assert_eq!(merged.theme.status.len(), 2);
+
+    assert!(
+      toml::from_str::<Config>("[status_bar]\nleft = [\"wifi\"]").is_err()
+    );
+  }
+
+  #[test]
+  fn test_layout_tree_sizes() {
+    let config: Config = toml::from_str(
+      r#"
+[layout.tree]
+direction = "horizontal"
+children = [
+  { widget = "logo", size = "30%" },
+  { widget = "prompt", size = "min:40" },
+  { size = "max:10" },
+  { size = "fill:2" },
+  { widget = "clock", size = "3" },
+]
+"#,
+    )
+    .expect("Failed to parse TOML");
+
+    let tree = config.layout.tree.as_ref().unwrap();
+    let sizes: Vec<LayoutSize> =
+      tree.children.iter().map(|child| child.size).collect();
+
+    assert_eq!(tree.direction, LayoutDirection::Horizontal);
+    assert_eq!(tree.size, LayoutSize::Fill(1));
+    assert_eq!(sizes, vec![
+      LayoutSize::Percentage(30),
+      LayoutSize::Min(40),
+      LayoutSize::Max(10),
+      LayoutSize::Fill(2),
+      LayoutSize::Length(3),
+    ]);
+    assert!(config.validate(false).is_ok());
+
+    for size in ["120%", "big", "min:"] {
+      let toml = format!("[layout.tree]\nsize = \"{size}\"");
+
+      assert!(toml::from_str::<Config>(&toml).is_err(), "{size}");
+    }
+  }
+
+  #[test]
+  fn test_layout_tree_validation() {
+    let no_prompt: Config = toml::from_str(
+      r#"
+[layout.tree]
+children = [{ widget = "logo" }, { widget = "clock" }]
+"#,
+    )
+    .expect("Failed to parse TOML");
+
+    assert!(no_prompt.validate(false).is_err());
+
+    let widget_with_children: Config = toml::from_str(
+      r#"
+[layout.tree]
+widget = "logo"
+children = [{ widget = "prompt" }]
+"#,
+    )
+    .expect("Failed to parse TOML");
+
+    assert!(widget_with_children.validate(false).is_err());
+  }
+
+  #[test]
+  fn test_layout_percentages_validation() {
+    let mut config = Config::default();
+    config.layout.width_percent = Some(60);
+    config.layout.height_percent = Some(100);
+
+    assert!(config.validate(false).is_ok());
+
+    config.layout.height_percent = Some(0);
+
+    assert!(matches!(
+      config.validate(false),
+      Err(ConfigError::InvalidRange(_))
+    ));
+
+    config.layout.height_percent = None;
+    config.layout.width_percent = Some(150);
+
+    assert!(config.validate(false).is_err());
+
+    config.layout.width_percent = None;
+    config.display.battery_low = Some(101);
+
+    assert!(matches!(
+      config.validate(false),
+      Err(ConfigError::InvalidRange(_))
+    ));
+  }
 }
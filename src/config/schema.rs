@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 
 /// Root configuration structure
@@ -18,6 +20,9 @@ pub struct Config {
   #[serde(default)]
   pub user_menu: UserMenuConfig,
 
+  #[serde(default)]
+  pub username: UsernameConfig,
+
   #[serde(default)]
   pub secret: SecretConfig,
 
@@ -31,6 +36,12 @@ pub struct Config {
   pub keybindings: KeybindingsConfig,
 
   #[serde(default)]
+  pub keyboard: KeyboardConfig,
+
+  #[serde(default)]
+  pub status_bar: StatusBarConfig,
+
+  #[serde(default, deserialize_with = "deserialize_theme")]
   pub theme: ThemeConfig,
 }
 
@@ -81,6 +92,10 @@ pub struct SessionConfig {
   /// Environment variables for default session
   #[serde(default)]
   pub environments: Vec<String>,
+
+  /// Offer switching to a user's session already running on another VT
+  #[serde(default)]
+  pub vt_switch: bool,
 }
 
 impl Default for SessionConfig {
@@ -92,6 +107,7 @@ impl Default for SessionConfig {
       session_wrapper:  None,
       xsession_wrapper: default_xsession_wrapper(),
       environments:     Vec::new(),
+      vt_switch:        false,
     }
   }
 }
@@ -107,10 +123,43 @@ pub struct DisplayConfig {
   #[serde(default)]
   pub time_format: Option<String>,
 
+  /// Show the time with large block digits
+  #[serde(default)]
+  pub big_clock: bool,
+
+  /// Format of the big clock (strftime), drawn with digits, `:`, `-`, `.` and
+  /// `/` only
+  #[serde(default)]
+  pub big_clock_format: Option<String>,
+
+  /// Format of the date line under the big clock (strftime), hidden if unset
+  #[serde(default)]
+  pub big_clock_date: Option<String>,
+
   /// Custom greeting message
   #[serde(default)]
   pub greeting: Option<String>,
 
+  /// File holding the greeting message, ANSI escape sequences allowed
+  #[serde(default)]
+  pub greeting_file: Option<String>,
+
+  /// Command whose output is used as the greeting message
+  #[serde(default)]
+  pub greeting_command: Option<String>,
+
+  /// Seconds after which the greeting command is killed (default: 5)
+  #[serde(default)]
+  pub greeting_command_timeout: Option<u64>,
+
+  /// Seconds between two runs of the greeting command, run once if unset
+  #[serde(default)]
+  pub greeting_command_refresh: Option<u64>,
+
+  /// File from which a random line is picked as the greeting message
+  #[serde(default)]
+  pub greeting_random: Option<String>,
+
   /// Show /etc/issue file
   #[serde(default)]
   pub issue: bool,
@@ -118,6 +167,42 @@ pub struct DisplayConfig {
   /// Greeting text alignment
   #[serde(default)]
   pub align_greeting: AlignGreeting,
+
+  /// Colors supported by the terminal, theme colors being approximated to fit
+  #[serde(default)]
+  pub color_depth: ColorDepthMode,
+
+  /// Program the palette of the Linux console with the theme colors
+  #[serde(default)]
+  pub vt_palette: bool,
+
+  /// Symbols used for borders and indicators, depending on the console font
+  #[serde(default)]
+  pub glyphs: GlyphsMode,
+
+  /// Charge in percent under which the battery is shown as low (default: 15)
+  #[serde(default)]
+  pub battery_low: Option<u8>,
+
+  /// Seconds without a key press after which the screen is blanked
+  #[serde(default)]
+  pub idle_timeout: Option<u64>,
+
+  /// What is shown on the screen once blanked
+  #[serde(default)]
+  pub idle_mode: IdleMode,
+
+  /// Capture the mouse to click buttons, menu entries and fields
+  #[serde(default)]
+  pub mouse: bool,
+
+  /// Draw prompts and menus as plain lines, for screen readers
+  #[serde(default)]
+  pub screen_reader: bool,
+
+  /// Hold the conversation line by line instead of drawing the screen
+  #[serde(default)]
+  pub line_mode: LineMode,
 }
 
 /// Remember/cache configuration
@@ -137,7 +222,7 @@ pub struct RememberConfig {
 }
 
 /// User menu configuration
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct UserMenuConfig {
   /// Enable user selection menu
   #[serde(default)]
@@ -150,14 +235,67 @@ pub struct UserMenuConfig {
   /// Maximum UID to display in user menu
   #[serde(default = "default_max_uid")]
   pub max_uid: u32,
+
+  /// Enumerate all users in the background, instead of only looking up typed
+  /// usernames
+  #[serde(default = "default_enumerate_users")]
+  pub enumerate: bool,
+
+  /// Number of users after which enumeration falls back to lookups
+  #[serde(default = "default_max_users")]
+  pub max_users: usize,
+
+  /// Seconds after which enumeration falls back to lookups
+  #[serde(default = "default_enumeration_timeout")]
+  pub timeout: u64,
 }
 
 impl Default for UserMenuConfig {
   fn default() -> Self {
     Self {
-      enabled: false,
-      min_uid: default_min_uid(),
-      max_uid: default_max_uid(),
+      enabled:   false,
+      min_uid:   default_min_uid(),
+      max_uid:   default_max_uid(),
+      enumerate: default_enumerate_users(),
+      max_users: default_max_users(),
+      timeout:   default_enumeration_timeout(),
+    }
+  }
+}
+
+/// Username normalization applied before starting authentication
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct UsernameConfig {
+  /// Remove leading and trailing whitespace
+  #[serde(default = "default_trim_username")]
+  pub trim: bool,
+
+  /// Convert the username to lowercase
+  #[serde(default)]
+  pub lowercase: bool,
+
+  /// Remove an `@realm` suffix
+  #[serde(default)]
+  pub strip_realm: bool,
+
+  /// Rewrite `DOMAIN\user` to the SSSD `user@DOMAIN` format
+  #[serde(default)]
+  pub map_domain: bool,
+
+  /// Logins to use in place of alternative identifiers, such as badge IDs or
+  /// email addresses
+  #[serde(default)]
+  pub aliases: BTreeMap<String, String>,
+}
+
+impl Default for UsernameConfig {
+  fn default() -> Self {
+    Self {
+      trim:        default_trim_username(),
+      lowercase:   false,
+      strip_realm: false,
+      map_domain:  false,
+      aliases:     BTreeMap::new(),
     }
   }
 }
@@ -202,9 +340,37 @@ pub struct LayoutConfig {
   #[serde(default)]
   pub prompt_padding: Option<u16>,
 
+  /// Position of the main container and menus within their area
+  #[serde(default)]
+  pub anchor: ContainerAnchor,
+
+  /// Horizontal offset of the container from its anchor, in cells
+  #[serde(default)]
+  pub offset_x: i16,
+
+  /// Vertical offset of the container from its anchor, in cells
+  #[serde(default)]
+  pub offset_y: i16,
+
+  /// Width of the container as a percentage of its area, instead of `width`
+  #[serde(default)]
+  pub width_percent: Option<u16>,
+
+  /// Minimum height of the container as a percentage of its area
+  #[serde(default)]
+  pub height_percent: Option<u16>,
+
   /// Widget positioning options
   #[serde(default)]
   pub widgets: WidgetConfig,
+
+  /// Layout tree of the main screen, replacing the widget positions
+  #[serde(default)]
+  pub tree: Option<LayoutNode>,
+
+  /// Path to a file holding the logo widget text, ANSI sequences allowed
+  #[serde(default)]
+  pub logo: Option<String>,
 }
 
 impl Default for LayoutConfig {
@@ -214,11 +380,34 @@ impl Default for LayoutConfig {
       window_padding:    None,
       container_padding: None,
       prompt_padding:    None,
+      anchor:            ContainerAnchor::default(),
+      offset_x:          0,
+      offset_y:          0,
+      width_percent:     None,
+      height_percent:    None,
       widgets:           WidgetConfig::default(),
+      tree:              None,
+      logo:              None,
     }
   }
 }
 
+/// Anchoring positions of the main container
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ContainerAnchor {
+  TopLeft,
+  Top,
+  TopRight,
+  Left,
+  #[default]
+  Center,
+  Right,
+  BottomLeft,
+  Bottom,
+  BottomRight,
+}
+
 /// Widget positioning configuration
 #[derive(Debug, Clone, Deserialize, Serialize, Default)]
 pub struct WidgetConfig {
@@ -229,6 +418,136 @@ pub struct WidgetConfig {
   /// Position of status bar widget
   #[serde(default)]
   pub status_position: WidgetPosition,
+
+  /// Position of big clock widget, above the prompt by default
+  #[serde(default)]
+  pub big_clock_position: WidgetPosition,
+}
+
+/// Node of the main screen layout tree, either drawing a widget or splitting
+/// its area between its children
+#[derive(Debug, Clone, Deserialize, Serialize, Default, PartialEq)]
+pub struct LayoutNode {
+  /// Widget drawn in the area of this node
+  #[serde(default)]
+  pub widget: Option<LayoutWidget>,
+
+  /// Direction in which the children are laid out
+  #[serde(default)]
+  pub direction: LayoutDirection,
+
+  /// Size of this node along the direction of its parent
+  #[serde(default)]
+  pub size: LayoutSize,
+
+  /// Child nodes sharing the area of this node
+  #[serde(default)]
+  pub children: Vec<LayoutNode>,
+}
+
+/// Widgets that can be placed in the layout tree
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum LayoutWidget {
+  Prompt,
+  Clock,
+  BigClock,
+  Greeting,
+  StatusBar,
+  SystemInfo,
+  Logo,
+  Battery,
+  Network,
+}
+
+/// Direction of a layout node
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum LayoutDirection {
+  #[default]
+  Vertical,
+  Horizontal,
+}
+
+/// Size constraint of a layout node, written `"N"` for a number of cells,
+/// `"N%"`, `"min:N"`, `"max:N"`, or `"fill"` and `"fill:N"` to share the
+/// remaining space
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq)]
+#[serde(try_from = "String", into = "String")]
+pub enum LayoutSize {
+  Length(u16),
+  Percentage(u16),
+  Min(u16),
+  Max(u16),
+  Fill(u16),
+}
+
+impl Default for LayoutSize {
+  fn default() -> Self {
+    LayoutSize::Fill(1)
+  }
+}
+
+impl TryFrom<String> for LayoutSize {
+  type Error = String;
+
+  fn try_from(value: String) -> Result<Self, Self::Error> {
+    let number = |value: &str| {
+      value
+        .trim()
+        .parse::<u16>()
+        .map_err(|_| format!("invalid layout size: {value}"))
+    };
+
+    let value = value.trim();
+
+    if value == "fill" {
+      Ok(LayoutSize::Fill(1))
+    } else if let Some(weight) = value.strip_prefix("fill:") {
+      Ok(LayoutSize::Fill(number(weight)?))
+    } else if let Some(min) = value.strip_prefix("min:") {
+      Ok(LayoutSize::Min(number(min)?))
+    } else if let Some(max) = value.strip_prefix("max:") {
+      Ok(LayoutSize::Max(number(max)?))
+    } else if let Some(percentage) = value.strip_suffix('%') {
+      match number(percentage)? {
+        percentage @ 0..=100 => Ok(LayoutSize::Percentage(percentage)),
+        _ => Err(format!("invalid layout size: {value}")),
+      }
+    } else {
+      Ok(LayoutSize::Length(number(value)?))
+    }
+  }
+}
+
+impl From<LayoutSize> for String {
+  fn from(size: LayoutSize) -> Self {
+    match size {
+      LayoutSize::Length(length) => length.to_string(),
+      LayoutSize::Percentage(percentage) => format!("{percentage}%"),
+      LayoutSize::Min(min) => format!("min:{min}"),
+      LayoutSize::Max(max) => format!("max:{max}"),
+      LayoutSize::Fill(weight) => format!("fill:{weight}"),
+    }
+  }
+}
+
+impl LayoutNode {
+  /// Whether a node of this tree draws the given widget
+  pub fn contains(&self, widget: LayoutWidget) -> bool {
+    self.widget == Some(widget)
+      || self.children.iter().any(|child| child.contains(widget))
+  }
+
+  /// Number of nodes of this tree drawing the given widget
+  pub fn count(&self, widget: LayoutWidget) -> usize {
+    usize::from(self.widget == Some(widget))
+      + self
+        .children
+        .iter()
+        .map(|child| child.count(widget))
+        .sum::<usize>()
+  }
 }
 
 /// Power management configuration
@@ -271,6 +590,14 @@ pub struct KeybindingsConfig {
   /// F-key for power menu (1-12)
   #[serde(default = "default_kb_power")]
   pub power: u8,
+
+  /// F-key for running sessions menu (1-12)
+  #[serde(default = "default_kb_vts")]
+  pub vts: u8,
+
+  /// F-key for keyboard layout menu (1-12)
+  #[serde(default = "default_kb_layouts")]
+  pub layouts: u8,
 }
 
 impl Default for KeybindingsConfig {
@@ -279,6 +606,127 @@ impl Default for KeybindingsConfig {
       command:  default_kb_command(),
       sessions: default_kb_sessions(),
       power:    default_kb_power(),
+      vts:      default_kb_vts(),
+      layouts:  default_kb_layouts(),
+    }
+  }
+}
+
+/// Console keyboard configuration
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct KeyboardConfig {
+  /// Turn Num Lock on when starting
+  #[serde(default)]
+  pub numlock: bool,
+
+  /// Layouts offered in the keyboard layout menu
+  #[serde(default)]
+  pub layouts: Vec<KeyboardLayoutConfig>,
+}
+
+/// Keyboard layout, as a console keymap and the XKB layout sessions are
+/// started with
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct KeyboardLayoutConfig {
+  /// Console keymap loaded with `loadkeys`
+  pub keymap: String,
+
+  /// XKB layout exported to sessions, the keymap name if unset
+  #[serde(default)]
+  pub layout: Option<String>,
+
+  /// XKB layout variant exported to sessions
+  #[serde(default)]
+  pub variant: Option<String>,
+}
+
+/// Status bar configuration
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct StatusBarConfig {
+  /// Segments drawn from the left edge of the status bar
+  #[serde(default = "default_status_left")]
+  pub left: Vec<StatusSegment>,
+
+  /// Segments drawn against the right edge of the status bar
+  #[serde(default = "default_status_right")]
+  pub right: Vec<StatusSegment>,
+
+  /// Interval in seconds at which command segments are run again
+  #[serde(default)]
+  pub command_refresh: Option<u64>,
+}
+
+impl Default for StatusBarConfig {
+  fn default() -> Self {
+    Self {
+      left:            default_status_left(),
+      right:           default_status_right(),
+      command_refresh: None,
+    }
+  }
+}
+
+/// Information that can be shown in the status bar, written as its name, or
+/// as `{ command = "..." }` for the output of a command
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum StatusSegment {
+  KeyHints,
+  Session,
+  Hostname,
+  Kernel,
+  Ip,
+  KeyboardLayout,
+  CapsLock,
+  NumLock,
+  ScrollLock,
+  Battery,
+  Network,
+  Uptime,
+  Clock,
+  Command(String),
+}
+
+impl StatusSegment {
+  /// Finds a segment from its name, except for command segments
+  pub fn from_name(name: &str) -> Option<StatusSegment> {
+    let segment = match name {
+      "key_hints" => StatusSegment::KeyHints,
+      "session" => StatusSegment::Session,
+      "hostname" => StatusSegment::Hostname,
+      "kernel" => StatusSegment::Kernel,
+      "ip" => StatusSegment::Ip,
+      "keyboard_layout" => StatusSegment::KeyboardLayout,
+      "caps_lock" => StatusSegment::CapsLock,
+      "num_lock" => StatusSegment::NumLock,
+      "scroll_lock" => StatusSegment::ScrollLock,
+      "battery" => StatusSegment::Battery,
+      "network" => StatusSegment::Network,
+      "uptime" => StatusSegment::Uptime,
+      "clock" => StatusSegment::Clock,
+      _ => return None,
+    };
+
+    Some(segment)
+  }
+
+  /// Name of the segment, also keying its style in the `[theme.status]` table
+  pub fn name(&self) -> &'static str {
+    match self {
+      StatusSegment::KeyHints => "key_hints",
+      StatusSegment::Session => "session",
+      StatusSegment::Hostname => "hostname",
+      StatusSegment::Kernel => "kernel",
+      StatusSegment::Ip => "ip",
+      StatusSegment::KeyboardLayout => "keyboard_layout",
+      StatusSegment::CapsLock => "caps_lock",
+      StatusSegment::NumLock => "num_lock",
+      StatusSegment::ScrollLock => "scroll_lock",
+      StatusSegment::Battery => "battery",
+      StatusSegment::Network => "network",
+      StatusSegment::Uptime => "uptime",
+      StatusSegment::Clock => "clock",
+      StatusSegment::Command(_) => "command",
     }
   }
 }
@@ -286,36 +734,54 @@ impl Default for KeybindingsConfig {
 /// Theme/color configuration
 #[derive(Debug, Clone, Deserialize, Serialize, Default)]
 pub struct ThemeConfig {
+  /// Name of the preset the theme is based on
+  #[serde(default)]
+  pub preset:      Option<String>,
+  /// Path to a theme file applied over the preset
+  #[serde(default)]
+  pub file:        Option<String>,
   /// Border color
   #[serde(default)]
-  pub border:    Option<String>,
+  pub border:      Option<String>,
   /// Base text color
   #[serde(default)]
-  pub text:      Option<String>,
+  pub text:        Option<String>,
   /// Time display color
   #[serde(default)]
-  pub time:      Option<String>,
+  pub time:        Option<String>,
   /// Container background color
   #[serde(default)]
-  pub container: Option<String>,
+  pub container:   Option<String>,
   /// Container title color
   #[serde(default)]
-  pub title:     Option<String>,
+  pub title:       Option<String>,
   /// Greeting text color
   #[serde(default)]
-  pub greet:     Option<String>,
+  pub greet:       Option<String>,
   /// Prompt text color
   #[serde(default)]
-  pub prompt:    Option<String>,
+  pub prompt:      Option<String>,
   /// User input color
   #[serde(default)]
-  pub input:     Option<String>,
+  pub input:       Option<String>,
   /// Action text color
   #[serde(default)]
-  pub action:    Option<String>,
+  pub action:      Option<String>,
   /// Action button color
   #[serde(default)]
-  pub button:    Option<String>,
+  pub button:      Option<String>,
+  /// Style of selected menu entries and active buttons
+  #[serde(default)]
+  pub selected:    Option<String>,
+  /// Style of the battery charge when it is low
+  #[serde(default)]
+  pub battery_low: Option<String>,
+  /// Border type of containers: plain, rounded, double, thick or none
+  #[serde(default)]
+  pub border_type: Option<String>,
+  /// Styles of the status bar segments, by segment name
+  #[serde(default)]
+  pub status:      BTreeMap<String, String>,
 }
 
 /// Greeting alignment options
@@ -328,6 +794,49 @@ pub enum AlignGreeting {
   Right,
 }
 
+/// Color depth options
+#[derive(Debug, Clone, Deserialize, Serialize, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ColorDepthMode {
+  #[default]
+  Auto,
+  Truecolor,
+  #[serde(rename = "256")]
+  Indexed,
+  #[serde(rename = "16")]
+  Basic,
+}
+
+/// Glyph profiles
+#[derive(Debug, Clone, Deserialize, Serialize, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum GlyphsMode {
+  #[default]
+  Auto,
+  Unicode,
+  Ascii,
+  Nerdfont,
+}
+
+/// Line by line conversation modes
+#[derive(Debug, Clone, Deserialize, Serialize, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum LineMode {
+  #[default]
+  Auto,
+  Always,
+  Never,
+}
+
+/// Idle screen modes
+#[derive(Debug, Clone, Deserialize, Serialize, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum IdleMode {
+  #[default]
+  Blank,
+  Clock,
+}
+
 /// Secret display modes
 #[derive(Debug, Clone, Deserialize, Serialize, Default, PartialEq)]
 #[serde(rename_all = "snake_case")]
@@ -348,6 +857,30 @@ pub enum WidgetPosition {
   Hidden,
 }
 
+/// Accepts either a theme table, or the name of a preset as a shorthand
+fn deserialize_theme<'de, D>(deserializer: D) -> Result<ThemeConfig, D::Error>
+where
+  D: serde::Deserializer<'de>,
+{
+  match toml::Value::deserialize(deserializer)? {
+    toml::Value::String(preset) => {
+      Ok(ThemeConfig {
+        preset: Some(preset),
+        ..Default::default()
+      })
+    },
+    value @ toml::Value::Table(_) => {
+      ThemeConfig::deserialize(value).map_err(serde::de::Error::custom)
+    },
+    value => {
+      Err(serde::de::Error::custom(format!(
+        "invalid type: {}, expected a preset name or a theme table",
+        value.type_str()
+      )))
+    },
+  }
+}
+
 // Default value functions
 fn default_log_file() -> String {
   "/tmp/tuigreet.log".to_string()
@@ -373,6 +906,22 @@ fn default_max_uid() -> u32 {
   60000
 }
 
+fn default_trim_username() -> bool {
+  true
+}
+
+fn default_enumerate_users() -> bool {
+  true
+}
+
+fn default_max_users() -> usize {
+  1000
+}
+
+fn default_enumeration_timeout() -> u64 {
+  5
+}
+
 fn default_secret_characters() -> String {
   "*".to_string()
 }
@@ -385,6 +934,14 @@ fn default_use_setsid() -> bool {
   true
 }
 
+fn default_status_left() -> Vec<StatusSegment> {
+  vec![StatusSegment::KeyHints, StatusSegment::Session]
+}
+
+fn default_status_right() -> Vec<StatusSegment> {
+  vec![StatusSegment::CapsLock]
+}
+
 fn default_kb_command() -> u8 {
   2
 }
@@ -396,3 +953,11 @@ fn default_kb_sessions() -> u8 {
 fn default_kb_power() -> u8 {
   12
 }
+
+fn default_kb_vts() -> u8 {
+  4
+}
+
+fn default_kb_layouts() -> u8 {
+  5
+}
@@ -1,10 +1,104 @@
-use crate::{config::schema::ThemeConfig, ui::common::style::Theme};
+use std::{fs, path::Path};
 
-/// Parse theme from TOML configuration
-pub fn theme_from_config(theme_config: &ThemeConfig) -> Theme {
-  let mut theme = Theme::default();
+use crate::{
+  config::{ConfigError, schema::ThemeConfig},
+  ui::common::style::Theme,
+};
+
+/// Directory where additional theme presets are looked up
+const THEMES_DIR: &str = "/etc/tuigreet/themes";
+
+/// Presets shipped with tuigreet
+const BUNDLED_PRESETS: &[(&str, &str)] = &[
+  ("nord", include_str!("../../contrib/themes/nord.toml")),
+  ("gruvbox", include_str!("../../contrib/themes/gruvbox.toml")),
+  (
+    "high-contrast",
+    include_str!("../../contrib/themes/high-contrast.toml"),
+  ),
+  ("mono", include_str!("../../contrib/themes/mono.toml")),
+];
+
+/// Build the theme from its layers: the preset, then the theme file, then
+/// either the CLI theme specification or the configured components. A CLI
+/// specification without any `=` names a preset instead.
+pub fn resolve_theme(
+  theme_config: &ThemeConfig,
+  cli_theme: Option<&str>,
+  cli_theme_file: Option<&str>,
+) -> Theme {
+  let (cli_preset, cli_spec) = match cli_theme.map(str::trim) {
+    Some(spec) if !spec.is_empty() && !spec.contains('=') => (Some(spec), None),
+    Some(spec) => (None, Some(spec)),
+    None => (None, None),
+  };
 
-  // Build theme spec string from config
+  let mut spec_parts = Vec::new();
+
+  if let Some(name) = cli_preset.or(theme_config.preset.as_deref()) {
+    match load_preset(name) {
+      Ok(preset) => spec_parts.extend(spec_from_config(&preset)),
+      Err(err) => tracing::warn!("could not load theme preset {name}: {err}"),
+    }
+  }
+
+  if let Some(path) = cli_theme_file.or(theme_config.file.as_deref()) {
+    match load_theme_file(Path::new(path)) {
+      Ok(theme) => spec_parts.extend(spec_from_config(&theme)),
+      Err(err) => tracing::warn!("could not load theme file {path}: {err}"),
+    }
+  }
+
+  // CLI theme completely overrides config theme components
+  match cli_spec {
+    Some(spec) => spec_parts.push(spec.to_string()),
+    None => spec_parts.extend(spec_from_config(theme_config)),
+  }
+
+  Theme::parse(&spec_parts.join(";"))
+}
+
+/// Load a standalone theme file, holding the same keys as the `[theme]` table
+pub fn load_theme_file(path: &Path) -> Result<ThemeConfig, ConfigError> {
+  let contents = fs::read_to_string(path).map_err(ConfigError::Io)?;
+
+  toml::from_str(&contents).map_err(ConfigError::Parse)
+}
+
+/// Find a preset by name, first in the themes directory, then among the
+/// bundled ones
+pub fn load_preset(name: &str) -> Result<ThemeConfig, ConfigError> {
+  load_preset_from(Path::new(THEMES_DIR), name)
+}
+
+fn load_preset_from(
+  themes_dir: &Path,
+  name: &str,
+) -> Result<ThemeConfig, ConfigError> {
+  if name.is_empty() || name.contains(['/', '.']) {
+    return Err(ConfigError::Validation(format!(
+      "invalid theme preset name: {name}"
+    )));
+  }
+
+  let path = themes_dir.join(format!("{name}.toml"));
+
+  if path.is_file() {
+    return load_theme_file(&path);
+  }
+
+  match BUNDLED_PRESETS.iter().find(|(preset, _)| *preset == name) {
+    Some((_, contents)) => toml::from_str(contents).map_err(ConfigError::Parse),
+    None => {
+      Err(ConfigError::Validation(format!(
+        "unknown theme preset: {name}"
+      )))
+    },
+  }
+}
+
+/// Build theme spec parts from config
+fn spec_from_config(theme_config: &ThemeConfig) -> Vec<String> {
   let mut spec_parts = Vec::new();
 
   if let Some(ref color) = theme_config.border {
@@ -37,23 +131,116 @@ pub fn theme_from_config(theme_config: &ThemeConfig) -> Theme {
   if let Some(ref color) = theme_config.button {
     spec_parts.push(format!("button={}", color));
   }
-
-  if !spec_parts.is_empty() {
-    let spec = spec_parts.join(";");
-    theme = Theme::parse(&spec);
+  if let Some(ref style) = theme_config.selected {
+    spec_parts.push(format!("selected={}", style));
+  }
+  if let Some(ref style) = theme_config.battery_low {
+    spec_parts.push(format!("battery_low={}", style));
+  }
+  if let Some(ref border_type) = theme_config.border_type {
+    spec_parts.push(format!("border_type={}", border_type));
+  }
+  for (segment, style) in &theme_config.status {
+    spec_parts.push(format!("status.{}={}", segment, style));
   }
 
-  theme
+  spec_parts
 }
 
-/// Apply CLI theme string over config theme
-pub fn apply_cli_theme(
-  mut base_theme: Theme,
-  cli_theme_spec: Option<&str>,
-) -> Theme {
-  if let Some(spec) = cli_theme_spec {
-    // CLI theme completely overrides config theme
-    base_theme = Theme::parse(spec);
+#[cfg(test)]
+mod tests {
+  use std::fs;
+
+  use tempfile::tempdir;
+  use tui::{
+    style::{Color, Modifier},
+    widgets::BorderType,
+  };
+
+  use super::{BUNDLED_PRESETS, load_preset_from, resolve_theme};
+  use crate::{
+    config::{Config, ThemeConfig},
+    ui::common::style::Themed,
+  };
+
+  #[test]
+  fn bundled_presets_parse() {
+    let dir = tempdir().unwrap();
+
+    for (name, _) in BUNDLED_PRESETS {
+      assert!(load_preset_from(dir.path(), name).is_ok(), "{name}");
+    }
+
+    assert!(load_preset_from(dir.path(), "unknown").is_err());
+    assert!(load_preset_from(dir.path(), "../nord").is_err());
+  }
+
+  #[test]
+  fn themes_dir_overrides_bundled_presets() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("nord.toml"), "border = \"red\"").unwrap();
+    fs::write(dir.path().join("custom.toml"), "text = \"blue\"").unwrap();
+
+    let nord = load_preset_from(dir.path(), "nord").unwrap();
+    let custom = load_preset_from(dir.path(), "custom").unwrap();
+
+    assert_eq!(nord.border.as_deref(), Some("red"));
+    assert_eq!(nord.text, None);
+    assert_eq!(custom.text.as_deref(), Some("blue"));
+  }
+
+  #[test]
+  fn layers_override_one_another() {
+    let dir = tempdir().unwrap();
+    let file = dir.path().join("theme.toml");
+    fs::write(&file, "border = \"green\"\nprompt = \"yellow\"").unwrap();
+
+    let config = ThemeConfig {
+      preset: Some("mono".to_string()),
+      file: Some(file.to_string_lossy().to_string()),
+      prompt: Some("cyan".to_string()),
+      ..Default::default()
+    };
+
+    let theme = resolve_theme(&config, None, None);
+
+    assert_eq!(theme.of(&[Themed::Border]).fg, Some(Color::Green));
+    assert_eq!(theme.of(&[Themed::Prompt]).fg, Some(Color::Cyan));
+    assert!(
+      theme
+        .of(&[Themed::Time])
+        .add_modifier
+        .contains(Modifier::DIM),
+      "preset keys should be kept when not overridden"
+    );
+
+    let theme = resolve_theme(&config, Some("prompt=red"), None);
+
+    assert_eq!(theme.of(&[Themed::Prompt]).fg, Some(Color::Red));
+    assert_eq!(theme.of(&[Themed::Border]).fg, Some(Color::Green));
+
+    let theme = resolve_theme(&config, Some("nord"), None);
+
+    assert_eq!(theme.border_type(), BorderType::Rounded);
+    assert_eq!(theme.of(&[Themed::Prompt]).fg, Some(Color::Cyan));
+  }
+
+  #[test]
+  fn preset_shorthand() {
+    let config: Config = toml::from_str("theme = \"gruvbox\"").unwrap();
+
+    assert_eq!(config.theme.preset.as_deref(), Some("gruvbox"));
+
+    let config: Config =
+      toml::from_str("[theme]\npreset = \"nord\"\nborder = \"red\"").unwrap();
+
+    assert_eq!(config.theme.preset.as_deref(), Some("nord"));
+    assert_eq!(config.theme.border.as_deref(), Some("red"));
+
+    // Errors in the table point at the faulty key.
+    let err = toml::from_str::<Config>("[theme]\nborder = 1").unwrap_err();
+
+    assert!(err.to_string().contains("border"), "{err}");
+    assert!(toml::from_str::<Config>("theme = 1").is_err());
   }
-  base_theme
 }
@@ -160,17 +160,68 @@ impl ConfigWatcher {
     let mut greeter_guard = greeter.write().await;
 
     // Store the old config for rollback if needed
-    let _old_config = greeter_guard.loaded_config.clone();
+    let old_config = greeter_guard.loaded_config.clone();
 
-    // Apply the new configuration
+    // Apply the new configuration, except for line mode which the terminal was
+    // set up for at startup
+    let line_mode = greeter_guard.line_mode;
     greeter_guard.apply_config(&config);
+    greeter_guard.line_mode = line_mode;
+
+    // Only run the greeting command again if its settings changed
+    let greeting_changed = old_config.as_ref().is_none_or(|old| {
+      (
+        &old.display.greeting_command,
+        old.display.greeting_command_timeout,
+        old.display.greeting_command_refresh,
+      ) != (
+        &config.display.greeting_command,
+        config.display.greeting_command_timeout,
+        config.display.greeting_command_refresh,
+      )
+    });
+
+    let status_bar_changed = old_config
+      .as_ref()
+      .is_none_or(|old| old.status_bar != config.status_bar);
+
+    let numlock_changed = old_config
+      .as_ref()
+      .is_none_or(|old| old.keyboard.numlock != config.keyboard.numlock);
+
+    let mouse_changed = old_config
+      .as_ref()
+      .is_some_and(|old| old.display.mouse != config.display.mouse);
+
+    // Only enumerate users again if the user menu settings changed
+    if old_config.map(|old| old.user_menu) != Some(config.user_menu.clone()) {
+      greeter_guard.load_users();
+    }
+
+    if greeting_changed {
+      greeter_guard.load_greeting();
+    }
+
+    if numlock_changed {
+      greeter_guard.apply_numlock();
+    }
+
+    if mouse_changed {
+      greeter_guard.apply_mouse();
+    }
 
     // Apply theme configuration
-    greeter_guard.apply_theme_config(&config.theme, None);
+    let cli_theme = greeter_guard.option("theme");
+    greeter_guard.apply_theme_config(&config.theme, cli_theme.as_deref());
 
     // Store the new config
     greeter_guard.loaded_config = Some(config);
 
+    // Status bar commands are read from the stored config
+    if status_bar_changed {
+      greeter_guard.load_status_commands();
+    }
+
     // XXX: We don't rollback on theme application failure since it's
     // non-critical The config has been successfully applied at this point
     info!("Config hot reload completed successfully");
@@ -1,68 +1,125 @@
-use std::time::Duration;
+use std::{
+  fs::File,
+  io::{self, Read},
+  os::fd::AsFd,
+  time::Duration,
+};
 
 #[cfg(not(test))] use crossterm::event::EventStream;
-use crossterm::event::{Event as TermEvent, KeyEvent};
-use futures::{StreamExt, future::FutureExt};
+use crossterm::event::{
+  Event as TermEvent,
+  KeyEvent,
+  MouseEvent,
+  MouseEventKind,
+};
+use futures::StreamExt;
 use tokio::{
   process::Command,
-  sync::mpsc::{self, Sender},
+  sync::{
+    mpsc::{self, Sender},
+    watch,
+  },
+  time::Instant,
+};
+use zeroize::Zeroize;
+
+use crate::{
+  AuthStatus,
+  ui::{
+    keyboard_layouts::KeyboardLayout,
+    users::{User, UserListing},
+  },
 };
 
-use crate::AuthStatus;
-
-const FRAME_RATE: f64 = 2.0;
+// Longest line a terminal in cooked mode lets the user type.
+const MAX_LINE: usize = 4096;
 
 pub enum Event {
   Key(KeyEvent),
+  Mouse(MouseEvent),
+  Line(String),
   Render,
+  Resize(u16, u16),
   PowerCommand(Command),
   Exit(AuthStatus),
   Refresh, // for config hot reload
+  Users(Vec<User>),
+  UsersLoaded(UserListing),
+  Greeting(String),
+  StatusCommand(String, String),
+  Layout(KeyboardLayout, Result<(), String>),
 }
 
 pub struct Events {
-  rx: mpsc::Receiver<Event>,
-  tx: mpsc::Sender<Event>,
+  rx:       mpsc::Receiver<Event>,
+  tx:       mpsc::Sender<Event>,
+  // When the screen should be drawn again if nothing else happens, if ever.
+  deadline: watch::Sender<Option<Instant>>,
 }
 
 impl Events {
   pub async fn new() -> Events {
     let (tx, rx) = mpsc::channel(10);
+    let (deadline, mut deadline_rx) = watch::channel(None);
 
     tokio::task::spawn({
       let tx = tx.clone();
 
       async move {
-        #[cfg(not(test))]
-        let mut stream = EventStream::new();
-
-        // In tests, we are not capturing events from the terminal, so we need
-        // to replace the crossterm::EventStream with a dummy pending stream.
-        #[cfg(test)]
-        let mut stream = futures::stream::pending::<Result<TermEvent, ()>>();
-
-        let mut render_interval =
-          tokio::time::interval(Duration::from_secs_f64(1.0 / FRAME_RATE));
+        let mut next_render: Option<Instant> = None;
 
         loop {
-          let render = render_interval.tick();
-          let event = stream.next().fuse();
+          let render = async {
+            match next_render {
+              Some(at) => tokio::time::sleep_until(at).await,
+              None => futures::future::pending().await,
+            }
+          };
 
           tokio::select! {
-            event = event => {
-              if let Some(Ok(TermEvent::Key(event))) = event {
-                let _ = tx.send(Event::Key(event)).await;
-                let _ = tx.send(Event::Render).await;
+            _ = render => {
+              next_render = None;
+
+              let _ = tx.send(Event::Render).await;
+            },
+
+            changed = deadline_rx.changed() => {
+              if changed.is_err() {
+                break;
               }
-            }
 
-            _ = render => { let _ = tx.send(Event::Render).await; },
+              next_render = *deadline_rx.borrow_and_update();
+            },
           }
         }
       }
     });
 
-    Events { rx, tx }
+    Events { rx, tx, deadline }
+  }
+
+  // Listens to the terminal, or reads whole lines from it when the
+  // conversation is held line by line, which is only known once the options
+  // are parsed.
+  pub fn listen(&self, lines: bool) {
+    match lines {
+      true => read_lines(self.tx.clone()),
+      false => read_terminal(self.tx.clone()),
+    }
+  }
+
+  // Schedules the next render, replacing the previous schedule. Nothing is
+  // drawn until something happens if there is no delay.
+  pub fn schedule(&self, delay: Option<Duration>) {
+    self
+      .deadline
+      .send_replace(delay.map(|delay| Instant::now() + delay));
+  }
+
+  // Whether events are waiting to be handled, in which case drawing can wait
+  // for them.
+  pub fn pending(&self) -> bool {
+    !self.rx.is_empty()
   }
 
   pub async fn next(&mut self) -> Option<Event> {
@@ -73,3 +130,82 @@ impl Events {
     self.tx.clone()
   }
 }
+
+// Reads key presses, clicks and resizes from the terminal.
+fn read_terminal(tx: Sender<Event>) {
+  tokio::task::spawn(async move {
+    #[cfg(not(test))]
+    let mut stream = EventStream::new();
+
+    // In tests, we are not capturing events from the terminal, so we need to
+    // replace the crossterm::EventStream with a dummy pending stream.
+    #[cfg(test)]
+    let mut stream = futures::stream::pending::<Result<TermEvent, ()>>();
+
+    while let Some(event) = stream.next().await {
+      let event = match event {
+        Ok(TermEvent::Key(event)) => Event::Key(event),
+        // Only clicks and wheel turns are of interest, moving the mouse around
+        // would only cause renders.
+        Ok(TermEvent::Mouse(event))
+          if matches!(
+            event.kind,
+            MouseEventKind::Down(_)
+              | MouseEventKind::ScrollUp
+              | MouseEventKind::ScrollDown
+          ) =>
+        {
+          Event::Mouse(event)
+        },
+        Ok(TermEvent::Resize(width, height)) => Event::Resize(width, height),
+        _ => continue,
+      };
+
+      if tx.send(event).await.is_err() {
+        break;
+      }
+    }
+  });
+}
+
+// Reads lines typed on a terminal in cooked mode, on a thread of its own since
+// reading them blocks. Once nothing can be read anymore, as when a serial line
+// hangs up, the greeter exits so it can be started again.
+//
+// Lines may be secrets, so they are read byte by byte around the buffer of the
+// standard input, into a buffer large enough to never be reallocated, and wiped
+// once passed on.
+fn read_lines(tx: Sender<Event>) {
+  std::thread::spawn(move || {
+    if let Ok(stdin) = io::stdin().as_fd().try_clone_to_owned() {
+      let mut stdin = File::from(stdin);
+      let mut line = Vec::with_capacity(MAX_LINE);
+      let mut byte = [0; 1];
+
+      loop {
+        match stdin.read(&mut byte) {
+          Ok(0) => break,
+          Ok(_) if byte[0] == b'\n' => {
+            let answer = String::from_utf8_lossy(&line).into_owned();
+            line.zeroize();
+
+            if tx.blocking_send(Event::Line(answer)).is_err() {
+              return;
+            }
+          },
+          Ok(_) if line.len() < MAX_LINE => line.push(byte[0]),
+          Ok(_) => {},
+          Err(err) if err.kind() == io::ErrorKind::Interrupted => {},
+          Err(_) => break,
+        }
+      }
+
+      line.zeroize();
+      byte.zeroize();
+    }
+
+    tracing::info!("end of input, exiting");
+
+    let _ = tx.blocking_send(Event::Exit(AuthStatus::Cancel));
+  });
+}
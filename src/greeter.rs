@@ -1,51 +1,106 @@
 use std::{
+  collections::HashMap,
   convert::TryInto,
   env,
   error::Error,
   ffi::OsStr,
   fmt::{self, Display},
+  fs,
+  io,
   path::PathBuf,
   process,
-  sync::Arc,
+  sync::{
+    Arc,
+    atomic::{AtomicBool, Ordering},
+  },
+  time::{Duration, Instant},
 };
 
 use chrono::{
   Locale,
   format::{Item, StrftimeItems},
 };
+use crossterm::{
+  event::{DisableMouseCapture, EnableMouseCapture},
+  execute,
+};
 use getopts::{Matches, Options};
 use i18n_embed::DesktopLanguageRequester;
 use tokio::{
   net::UnixStream,
   sync::{RwLock, RwLockWriteGuard, mpsc::Sender},
+  task::JoinHandle,
 };
 use tracing_appender::non_blocking::WorkerGuard;
+use tui::layout::Rect;
+use unicode_width::UnicodeWidthStr;
 use zeroize::Zeroize;
 
 use crate::{
+  battery::PowerSupply,
+  config::{
+    DisplayConfig,
+    IdleMode,
+    LineMode,
+    StatusSegment,
+    ThemeConfig,
+    theme::resolve_theme,
+  },
   event::Event,
+  greeting::{
+    get_cached_greeting,
+    random_greeting,
+    read_greeting_file,
+    spawn_greeting_command,
+  },
   info::{
-    get_issue,
+    enumerate_users,
     get_last_command,
     get_last_session_path,
     get_last_user_command,
+    get_last_user_layout,
     get_last_user_name,
     get_last_user_session,
     get_last_user_username,
     get_min_max_uids,
     get_sessions,
-    get_users,
+    spawn_status_commands,
   },
+  ipc::Ipc,
+  issue::get_issue,
+  line,
+  network::NetworkStatus,
   power::PowerOption,
   ui::{
-    common::{masked::MaskedString, menu::Menu, style::Theme},
+    Transcript,
+    common::{
+      color::{ColorDepth, Palette, downgrade, is_vt, program_palette},
+      glyphs::Glyphs,
+      masked::MaskedString,
+      menu::Menu,
+      style::Theme,
+      target::Target,
+    },
+    keyboard_layouts::KeyboardLayout,
     power::Power,
     sessions::{Session, SessionSource, SessionType},
-    users::User,
+    users::{User, UserListing},
+    vts::Vt,
   },
+  username::UsernameRules,
+  vt::{self, Locks},
 };
 
 const DEFAULT_LOG_FILE: &str = "/tmp/tuigreet.log";
+
+// Options setting the greeting, only one of which may be used.
+const GREETING_OPTIONS: [&str; 5] = [
+  "issue",
+  "greeting",
+  "greeting-file",
+  "greeting-command",
+  "greeting-random",
+];
 const DEFAULT_LOCALE: Locale = Locale::en_US;
 const DEFAULT_ASTERISKS_CHARS: &str = "*";
 // `startx` wants an absolute path to the executable as a first argument.
@@ -81,6 +136,8 @@ pub enum Mode {
   Command,
   Sessions,
   Power,
+  Vts,
+  Layouts,
   Processing,
 }
 
@@ -103,6 +160,25 @@ impl SecretDisplay {
   }
 }
 
+// What is drawn once the screen was blanked for being idle.
+#[derive(SmartDefault, Debug, Copy, Clone, PartialEq)]
+pub enum IdleScreen {
+  #[default]
+  // Nothing but a black screen.
+  Blank,
+  // The current time, moving around the screen every minute.
+  Clock,
+}
+
+impl IdleScreen {
+  pub fn from_mode(mode: &IdleMode) -> IdleScreen {
+    match mode {
+      IdleMode::Blank => IdleScreen::Blank,
+      IdleMode::Clock => IdleScreen::Clock,
+    }
+  }
+}
+
 // This enum models text alignment options
 #[derive(SmartDefault, Debug, Clone)]
 pub enum GreetAlign {
@@ -152,13 +228,37 @@ pub struct Greeter {
   pub xsession_wrapper: Option<String>,
 
   // Whether user menu is enabled.
-  pub user_menu: bool,
+  pub user_menu:       bool,
+  // Range of UIDs to show in the user menu.
+  #[default((1000, 60000))]
+  pub user_menu_uids:  (u32, u32),
   // Menu for user selection.
-  pub users:     Menu<User>,
+  pub users:           Menu<User>,
+  // How far along populating the user menu is.
+  pub user_listing:    UserListing,
+  // Whether all users should be enumerated upfront, or only looked up by name.
+  #[default(true)]
+  pub users_enumerate: bool,
+  // Number of users after which enumeration is abandoned.
+  #[default(1000)]
+  pub users_limit:     usize,
+  // Duration after which enumeration is abandoned.
+  #[default(Duration::from_secs(5))]
+  pub users_timeout:   Duration,
+  // Set to stop the running user enumeration, if any.
+  pub users_cancel:    Option<Arc<AtomicBool>>,
+  // Pending lookup of the username being typed, if any.
+  pub users_lookup:    Option<JoinHandle<()>>,
+  // Last logged-in user, offered for username completion.
+  pub last_user:       Option<User>,
+  // Index of the username completion to offer among all matching users.
+  pub completion:      usize,
   // Current username. Masked to display the full name if available.
-  pub username:  MaskedString,
+  pub username:        MaskedString,
+  // Normalization applied to the username before authenticating.
+  pub username_rules:  UsernameRules,
   // Prompt that should be displayed to ask for entry.
-  pub prompt:    Option<String>,
+  pub prompt:          Option<String>,
 
   // Whether the current edition prompt should be hidden.
   pub asking_for_secret: bool,
@@ -173,27 +273,111 @@ pub struct Greeter {
   pub remember_user_session: bool,
 
   // Style object for the terminal UI
-  pub theme:       Theme,
+  pub theme:            Theme,
+  // Colors the terminal is able to display.
+  pub color_depth:      ColorDepth,
+  // Whether to program the console palette with the theme colors.
+  pub vt_palette:       bool,
+  // Console palette before we programmed it, restored on exit.
+  pub vt_palette_saved: Option<Palette>,
+  // Symbols the borders and indicators are drawn with.
+  pub glyphs:           Glyphs,
   // Display the current time
-  pub time:        bool,
+  pub time:             bool,
   // Time format
-  pub time_format: Option<String>,
+  pub time_format:      Option<String>,
+  // Display the current time with large digits
+  pub big_clock:        bool,
+  // Format of the big clock
+  pub big_clock_format: Option<String>,
+  // Format of the date line under the big clock
+  pub big_clock_date:   Option<String>,
   // Greeting message (MOTD) to use to welcome the user.
-  pub greeting:    Option<String>,
+  pub greeting:         Option<String>,
+  // Command whose output is used as the greeting.
+  pub greeting_command: Option<String>,
+  // Duration after which the greeting command is killed.
+  #[default(Duration::from_secs(5))]
+  pub greeting_timeout: Duration,
+  // Interval at which the greeting command is run again.
+  pub greeting_refresh: Option<Duration>,
+  // Greeting command running in the background.
+  pub greeting_task:    Option<JoinHandle<()>>,
+  // Last output of the status bar commands, by command.
+  pub status_commands:  HashMap<String, String>,
+  // Status bar commands running in the background.
+  pub status_task:      Option<JoinHandle<()>>,
   // Transaction message to show to the user.
-  pub message:     Option<String>,
+  pub message:          Option<String>,
+  // Text of the logo widget, read from the configured file.
+  pub logo:             Option<String>,
+  // State of the batteries, read on every render.
+  pub power_supply:     Option<PowerSupply>,
+  // State of the network interfaces, read on every render.
+  pub network:          NetworkStatus,
+  // State of the lock keys, read on every render.
+  pub locks:            Locks,
+  // Charge in percent under which the battery is shown as low.
+  #[default(15)]
+  pub battery_low:      u8,
+
+  // Duration without a key press after which the screen is blanked.
+  pub idle_timeout: Option<Duration>,
+  // What is drawn on the blanked screen.
+  pub idle_screen:  IdleScreen,
+  // Whether the screen is blanked.
+  pub idle:         bool,
+  // Time of the last key press.
+  #[default(Instant::now())]
+  pub last_input:   Instant,
+
+  // Whether to capture the mouse.
+  pub mouse:   bool,
+  // What can be clicked on the screen, as of the last render.
+  pub targets: Vec<(Rect, Target)>,
+
+  // Whether to draw plain lines for screen readers instead of boxes.
+  pub screen_reader: bool,
+  // What was said so far, when drawn as plain lines.
+  pub transcript:    Transcript,
+  // Whether the conversation is held line by line in cooked mode, as asked or
+  // because the terminal cannot draw the screen. Only decided at startup.
+  pub line_mode:     bool,
+
+  // Whether to offer switching to a user's existing session.
+  pub vt_switch: bool,
+  // Menu for switching to occupied virtual terminals.
+  pub vts:       Menu<Vt>,
+
+  // Menu for keyboard layouts.
+  pub layouts:       Menu<KeyboardLayout>,
+  // Layout that was last selected, exported to the session.
+  pub layout:        Option<KeyboardLayout>,
+  // Console keymap before we loaded one, restored on exit.
+  pub keymap_saved:  Option<String>,
+  // Whether a keymap was loaded, or started loading, since it was saved.
+  pub keymap_loaded: bool,
 
   // Menu for power options.
   pub powers:       Menu<Power>,
   // Whether to prefix the power commands with `setsid`.
   pub power_setsid: bool,
 
+  // Whether the session command output should be discarded.
+  pub silent: bool,
+
   #[default(2)]
   pub kb_command:  u8,
   #[default(3)]
   pub kb_sessions: u8,
   #[default(12)]
   pub kb_power:    u8,
+  #[default(4)]
+  pub kb_vts:      u8,
+  #[default(5)]
+  pub kb_layouts:  u8,
+  // Whether to turn Num Lock on when starting.
+  pub numlock:     bool,
 
   // The software is waiting for a response from `greetd`.
   pub working: bool,
@@ -299,6 +483,9 @@ impl Greeter {
     }
 
     greeter.logger = crate::init_logger(&greeter);
+    greeter.load_users();
+    greeter.load_greeting();
+    greeter.load_status_commands();
 
     let sessions = get_sessions(&greeter).unwrap_or_default();
 
@@ -318,6 +505,10 @@ impl Greeter {
     if greeter.remember
       && let Some(username) = get_last_user_username()
     {
+      greeter.last_user = Some(User {
+        username: username.clone(),
+        name:     get_last_user_name(),
+      });
       greeter.username = MaskedString::from(username, get_last_user_name());
 
       // If, on top of that, we should remember their last session.
@@ -364,6 +555,188 @@ impl Greeter {
     greeter
   }
 
+  // (Re)populates the user menu. Users are enumerated in the background when
+  // allowed to, and otherwise looked up by name as they are typed.
+  pub fn load_users(&mut self) {
+    if let Some(cancel) = self.users_cancel.take() {
+      cancel.store(true, Ordering::SeqCst);
+    }
+
+    self.users.options.clear();
+    self.users.selected = 0;
+
+    match self.events {
+      Some(ref events) if self.user_menu && self.users_enumerate => {
+        let cancel = Arc::new(AtomicBool::new(false));
+
+        tracing::info!("enumerating users in the background");
+
+        enumerate_users(
+          self.user_menu_uids,
+          self.users_limit,
+          self.users_timeout,
+          cancel.clone(),
+          events.clone(),
+        );
+
+        self.users_cancel = Some(cancel);
+        self.set_user_listing(UserListing::Loading);
+      },
+
+      _ => self.set_user_listing(UserListing::Lookup),
+    }
+  }
+
+  // Runs the greeting command in the background, replacing the one that may
+  // already be running. Its output from the last time it ran is shown until it
+  // completes.
+  pub fn load_greeting(&mut self) {
+    if let Some(task) = self.greeting_task.take() {
+      task.abort();
+    }
+
+    let Some(ref command) = self.greeting_command else {
+      return;
+    };
+
+    if self.greeting.is_none() {
+      self.greeting = get_cached_greeting(command);
+    }
+
+    if let Some(ref events) = self.events {
+      self.greeting_task = Some(spawn_greeting_command(
+        command.clone(),
+        self.greeting_timeout,
+        self.greeting_refresh,
+        events.clone(),
+      ));
+    }
+  }
+
+  // Runs the commands of the configured status bar segments in the
+  // background, replacing those that may already be running.
+  pub fn load_status_commands(&mut self) {
+    if let Some(task) = self.status_task.take() {
+      task.abort();
+    }
+
+    let Some(ref config) = self.loaded_config else {
+      return;
+    };
+
+    let status_bar = &config.status_bar;
+    let commands: Vec<String> = status_bar
+      .left
+      .iter()
+      .chain(&status_bar.right)
+      .filter_map(|segment| {
+        match segment {
+          StatusSegment::Command(command) => Some(command.clone()),
+          _ => None,
+        }
+      })
+      .collect();
+
+    self
+      .status_commands
+      .retain(|command, _| commands.contains(command));
+
+    if let Some(ref events) = self.events
+      && !commands.is_empty()
+    {
+      self.status_task = Some(spawn_status_commands(
+        commands,
+        status_bar.command_refresh.map(Duration::from_secs),
+        events.clone(),
+      ));
+    }
+  }
+
+  // Reads the state of the machine shown by the widgets again, before the
+  // screen is drawn. Lock keys are only known on a virtual terminal.
+  pub fn refresh_status(&mut self) {
+    self.power_supply = PowerSupply::read();
+    self.network = NetworkStatus::read();
+    self.locks = match is_vt() {
+      true => vt::get_locks().unwrap_or_default(),
+      false => Locks::default(),
+    };
+  }
+
+  // Adds newly found users to the user menu, ignoring those already listed.
+  pub fn add_users(&mut self, users: Vec<User>) {
+    for user in users {
+      if !self
+        .users
+        .options
+        .iter()
+        .any(|u| u.username == user.username)
+      {
+        self.users.options.push(user);
+      }
+    }
+  }
+
+  pub fn set_user_listing(&mut self, listing: UserListing) {
+    if listing != UserListing::Loading {
+      tracing::info!(
+        "found {} users, listing is {listing:?}",
+        self.users.options.len()
+      );
+    }
+
+    self.user_listing = listing;
+    self.users.title = match listing {
+      UserListing::Loading => fl!("title_users_loading"),
+      _ => fl!("title_users"),
+    };
+  }
+
+  // Known users the username being typed could be completed to, along with
+  // the text that would complete it, and whether the full name was matched.
+  pub fn username_completions(&self) -> Vec<(&User, &str, bool)> {
+    let prefix = &self.username.value;
+
+    if prefix.is_empty() || self.username.mask.is_some() {
+      return vec![];
+    }
+
+    let mut completions: Vec<(&User, &str, bool)> = vec![];
+
+    for user in self.last_user.iter().chain(self.users.options.iter()) {
+      if completions
+        .iter()
+        .any(|(u, ..)| u.username == user.username)
+      {
+        continue;
+      }
+
+      if let Some((rest, by_name)) = user.complete(prefix) {
+        completions.push((user, rest, by_name));
+      }
+    }
+
+    completions
+  }
+
+  // The username completion currently offered, if any. A login matching what
+  // was typed exactly is offered over longer ones, so it can be picked.
+  pub fn username_completion(&self) -> Option<(&User, &str, bool)> {
+    let completions = self.username_completions();
+
+    if let Some(exact) = completions
+      .iter()
+      .find(|(_, rest, by_name)| rest.is_empty() && !by_name)
+    {
+      return Some(*exact);
+    }
+
+    match completions.len() {
+      0 => None,
+      count => completions.get(self.completion % count).copied(),
+    }
+  }
+
   // Scrub memory of all data, unless `soft` is true, in which case, we will
   // keep the username (can happen if a wrong password was entered, we want to
   // give the user another chance, as PAM would).
@@ -380,6 +753,65 @@ impl Greeter {
     }
   }
 
+  // Whether no key was pressed for long enough to blank the screen, which is
+  // not done while waiting for `greetd`.
+  pub fn should_idle(&self) -> bool {
+    self.until_idle().is_some_and(|left| left.is_zero())
+  }
+
+  // Time left before the screen is blanked if no key is pressed, if it can be.
+  pub fn until_idle(&self) -> Option<Duration> {
+    if self.idle || self.working || self.done {
+      return None;
+    }
+
+    self
+      .idle_timeout
+      .map(|timeout| timeout.saturating_sub(self.last_input.elapsed()))
+  }
+
+  // Blanks the screen, forgetting what was typed. A session that was being
+  // authenticated is cancelled, since its username is forgotten too.
+  pub async fn blank(&mut self) {
+    tracing::info!("blanking the screen after being idle");
+
+    let authenticating = [self.mode, self.previous_mode]
+      .iter()
+      .any(|mode| matches!(mode, Mode::Password | Mode::Action));
+
+    if authenticating {
+      Ipc::cancel(self).await;
+      self.reset(false).await;
+    } else {
+      self.mode = Mode::Username;
+      self.previous_mode = Mode::Username;
+    }
+
+    self.scrub(true, false);
+    self.previous_buffer.zeroize();
+    self.cursor_offset = 0;
+    self.completion = 0;
+    self.transcript = Transcript::default();
+
+    // The remembered user is not secret, and is offered again.
+    if self.remember
+      && let Some(ref user) = self.last_user
+    {
+      self.username =
+        MaskedString::from(user.username.clone(), user.name.clone());
+    }
+
+    self.idle = true;
+  }
+
+  // Draws the screen again after a key was pressed while it was blanked.
+  pub fn wake(&mut self) {
+    tracing::info!("waking up from idle screen");
+
+    self.idle = false;
+    self.last_input = Instant::now();
+  }
+
   // Reset the software to its initial state.
   pub async fn reset(&mut self, soft: bool) {
     if soft {
@@ -593,6 +1025,24 @@ impl Greeter {
       "show custom text above login prompt",
       "GREETING",
     );
+    opts.optopt(
+      "",
+      "greeting-file",
+      "show the content of a file above login prompt",
+      "FILE",
+    );
+    opts.optopt(
+      "",
+      "greeting-command",
+      "show the output of a command above login prompt",
+      "COMMAND",
+    );
+    opts.optopt(
+      "",
+      "greeting-random",
+      "show a random line of a file above login prompt",
+      "FILE",
+    );
     opts.optflag("t", "time", "display the current date and time");
     opts.optopt(
       "",
@@ -600,6 +1050,11 @@ impl Greeter {
       "custom strftime format for displaying date and time",
       "FORMAT",
     );
+    opts.optflag(
+      "",
+      "big-clock",
+      "display the current time with large digits",
+    );
     opts.optflag("r", "remember", "remember last logged-in username");
     opts.optflag("", "remember-session", "remember last selected session");
     opts.optflag(
@@ -612,6 +1067,11 @@ impl Greeter {
       "user-menu",
       "allow graphical selection of users from a menu",
     );
+    opts.optflag(
+      "",
+      "vt-switch",
+      "offer switching to sessions already running on other VTs",
+    );
     opts.optopt(
       "",
       "user-menu-min-uid",
@@ -624,7 +1084,66 @@ impl Greeter {
       "maximum UID to display in the user selection menu",
       "UID",
     );
-    opts.optopt("", "theme", "define the application theme colors", "THEME");
+    opts.optopt(
+      "",
+      "theme",
+      "define the application theme colors, or the name of a preset",
+      "THEME",
+    );
+    opts.optopt(
+      "",
+      "color-depth",
+      "colors supported by the terminal: auto, truecolor, 256 or 16 (default: \
+       auto)",
+      "DEPTH",
+    );
+    opts.optflag(
+      "",
+      "vt-palette",
+      "program the console palette with the theme colors",
+    );
+    opts.optopt(
+      "",
+      "glyphs",
+      "symbols to draw the interface with: auto, unicode, ascii or nerdfont \
+       (default: auto)",
+      "PROFILE",
+    );
+    opts.optopt(
+      "",
+      "theme-file",
+      "load the application theme from a TOML file",
+      "FILE",
+    );
+    opts.optopt(
+      "",
+      "idle-timeout",
+      "blank the screen after that many seconds without a key press",
+      "SECS",
+    );
+    opts.optopt(
+      "",
+      "idle-mode",
+      "what to show on the blanked screen: blank or clock (default: blank)",
+      "MODE",
+    );
+    opts.optflag(
+      "",
+      "mouse",
+      "capture the mouse to click buttons, menu entries and fields",
+    );
+    opts.optflag(
+      "",
+      "screen-reader",
+      "draw prompts and menus as plain lines, for screen readers",
+    );
+    opts.optopt(
+      "",
+      "line-mode",
+      "hold the conversation line by line: auto, always or never (default: \
+       auto, on dumb and small terminals)",
+      "MODE",
+    );
     opts.optflag("", "asterisks", "display asterisks when a secret is typed");
     opts.optopt(
       "",
@@ -694,6 +1213,19 @@ impl Greeter {
       "F-key to use to open the power menu",
       "[1-12]",
     );
+    opts.optopt(
+      "",
+      "kb-vts",
+      "F-key to use to open the running sessions menu",
+      "[1-12]",
+    );
+    opts.optopt(
+      "",
+      "kb-layouts",
+      "F-key to use to open the keyboard layout menu",
+      "[1-12]",
+    );
+    opts.optflag("", "numlock", "turn Num Lock on when starting");
 
     opts.optopt("", "config", "path to configuration file", "PATH");
     opts.optflag("", "no-config", "disable loading configuration files");
@@ -737,19 +1269,108 @@ impl Greeter {
       }
     }
 
-    if self.config().opt_present("issue")
-      && self.config().opt_present("greeting")
+    if GREETING_OPTIONS
+      .iter()
+      .filter(|option| self.config().opt_present(option))
+      .count()
+      > 1
     {
       return Err(
-        "Only one of --issue and --greeting may be used at the same time"
+        "Only one of --issue, --greeting, --greeting-file, --greeting-command \
+         and --greeting-random may be used at the same time"
           .into(),
       );
     }
 
+    self.color_depth = match self.option("color-depth").as_deref() {
+      None | Some("auto") => ColorDepth::detect(),
+      Some("truecolor") => ColorDepth::TrueColor,
+      Some("256") => ColorDepth::Indexed,
+      Some("16") => ColorDepth::Basic,
+      Some(value) => {
+        return Err(
+          format!(
+            "--color-depth must be one of auto, truecolor, 256 or 16, got \
+             {value}"
+          )
+          .into(),
+        );
+      },
+    };
+
+    self.vt_palette = self.config().opt_present("vt-palette");
+
+    self.glyphs = match self.option("glyphs").as_deref() {
+      None | Some("auto") => Glyphs::detect(),
+      Some("unicode") => Glyphs::Unicode,
+      Some("ascii") => Glyphs::Ascii,
+      Some("nerdfont") => Glyphs::NerdFont,
+      Some(value) => {
+        return Err(
+          format!(
+            "--glyphs must be one of auto, unicode, ascii or nerdfont, got \
+             {value}"
+          )
+          .into(),
+        );
+      },
+    };
+
+    self.mouse = self.config().opt_present("mouse");
+    self.screen_reader = self.config().opt_present("screen-reader");
+
+    self.line_mode = match self.option("line-mode").as_deref() {
+      None | Some("auto") => line::wanted(&LineMode::Auto),
+      Some("always") => true,
+      Some("never") => false,
+      Some(value) => {
+        return Err(
+          format!(
+            "--line-mode must be one of auto, always or never, got {value}"
+          )
+          .into(),
+        );
+      },
+    };
+
+    if let Some(timeout) = self.option("idle-timeout") {
+      match timeout.parse::<u64>() {
+        Ok(seconds) if seconds > 0 => {
+          self.idle_timeout = Some(Duration::from_secs(seconds))
+        },
+        _ => {
+          return Err(
+            format!(
+              "--idle-timeout must be a positive number of seconds, got \
+               {timeout}"
+            )
+            .into(),
+          );
+        },
+      }
+    }
+
+    self.idle_screen = match self.option("idle-mode").as_deref() {
+      None | Some("blank") => IdleScreen::Blank,
+      Some("clock") => IdleScreen::Clock,
+      Some(value) => {
+        return Err(
+          format!("--idle-mode must be one of blank or clock, got {value}")
+            .into(),
+        );
+      },
+    };
+
     if self.config().opt_present("theme")
-      && let Some(spec) = self.config().opt_str("theme")
+      || self.config().opt_present("theme-file")
     {
-      self.theme = Theme::parse(spec.as_str());
+      let theme = resolve_theme(
+        &ThemeConfig::default(),
+        self.option("theme").as_deref(),
+        self.option("theme-file").as_deref(),
+      );
+
+      self.set_theme(theme);
     }
 
     if self.config().opt_present("asterisks") {
@@ -780,6 +1401,8 @@ impl Greeter {
       self.time_format = Some(format);
     }
 
+    self.big_clock = self.config().opt_present("big-clock");
+
     if self.config().opt_present("user-menu") {
       self.user_menu = true;
 
@@ -802,13 +1425,7 @@ impl Greeter {
         );
       }
 
-      self.users = Menu {
-        title:    fl!("title_users"),
-        options:  get_users(min_uid, max_uid),
-        selected: 0,
-      };
-
-      tracing::info!("found {} users", self.users.options.len());
+      self.user_menu_uids = (min_uid, max_uid);
     }
 
     if self.config().opt_present("remember-session")
@@ -832,6 +1449,16 @@ impl Greeter {
       self.config().opt_present("remember-user-session");
     self.greeting = self.option("greeting");
 
+    if let Some(path) = self.option("greeting-file") {
+      self.greeting = read_greeting_file(path);
+    }
+
+    if let Some(path) = self.option("greeting-random") {
+      self.greeting = random_greeting(path);
+    }
+
+    self.greeting_command = self.option("greeting-command");
+
     // If the `--cmd` argument is provided, it will override the selected
     // session.
     if let Some(command) = self.option("cmd") {
@@ -907,17 +1534,46 @@ impl Greeter {
       .opt_str("kb-power")
       .map(|i| i.parse::<u8>().unwrap_or_default())
       .unwrap_or(12);
+    self.kb_vts = self
+      .config()
+      .opt_str("kb-vts")
+      .map(|i| i.parse::<u8>().unwrap_or_default())
+      .unwrap_or(4);
+    self.kb_layouts = self
+      .config()
+      .opt_str("kb-layouts")
+      .map(|i| i.parse::<u8>().unwrap_or_default())
+      .unwrap_or(5);
 
-    if self.kb_command == self.kb_sessions
-      || self.kb_sessions == self.kb_power
-      || self.kb_power == self.kb_command
-    {
+    self.vt_switch = self.config().opt_present("vt-switch");
+    self.numlock = self.config().opt_present("numlock");
+
+    if !self.keybindings_distinct() {
       return Err("keybindings must all be distinct".into());
     }
 
     Ok(())
   }
 
+  // Checks that no two menus are bound to the same F-key. The running
+  // sessions and keyboard layout menus are only taken into account when they
+  // are enabled.
+  pub fn keybindings_distinct(&self) -> bool {
+    let mut keys = vec![self.kb_command, self.kb_sessions, self.kb_power];
+
+    if self.vt_switch {
+      keys.push(self.kb_vts);
+    }
+    if !self.layouts.options.is_empty() {
+      keys.push(self.kb_layouts);
+    }
+
+    keys
+      .iter()
+      .enumerate()
+      .all(|(index, key)| !keys[index + 1..].contains(key))
+  }
+
   pub fn set_prompt(&mut self, prompt: &str) {
     self.prompt = if prompt.ends_with(' ') {
       Some(prompt.into())
@@ -934,7 +1590,7 @@ impl Greeter {
   pub fn prompt_width(&self) -> usize {
     match &self.prompt {
       None => 0,
-      Some(prompt) => prompt.chars().count(),
+      Some(prompt) => prompt.width(),
     }
   }
 
@@ -991,7 +1647,7 @@ impl Greeter {
       self.xsession_wrapper = config.session.xsession_wrapper.clone();
     }
 
-     self.silent = self.config().opt_present("silent");
+    self.silent = self.config().opt_present("silent");
 
     // Display config
     if !self.config().opt_present("time") {
@@ -1004,15 +1660,105 @@ impl Greeter {
       self.time_format = config.display.time_format.clone();
     }
 
-    if !self.config().opt_present("greeting")
-      && config.display.greeting.is_some()
+    if !self.config().opt_present("big-clock") {
+      self.big_clock = config.display.big_clock;
+    }
+
+    self.big_clock_format = config.display.big_clock_format.clone();
+    self.big_clock_date = config.display.big_clock_date.clone();
+
+    // Greetings given on the command line replace every configured one
+    if !GREETING_OPTIONS
+      .iter()
+      .any(|option| self.config().opt_present(option))
     {
-      self.greeting = config.display.greeting.clone();
+      // A greeting drawn at random or output by the greeting command is kept
+      // on reload, as long as the greetings are configured the same.
+      let previous = self.greeting.take();
+      let unchanged = self.loaded_config.as_ref().is_some_and(|old| {
+        let greetings = |display: &DisplayConfig| {
+          (
+            display.issue,
+            display.greeting.clone(),
+            display.greeting_file.clone(),
+            display.greeting_random.clone(),
+            display.greeting_command.clone(),
+          )
+        };
+
+        greetings(&old.display) == greetings(&config.display)
+      });
+
+      if config.display.issue {
+        self.greeting = get_issue();
+      } else if let Some(ref greeting) = config.display.greeting {
+        self.greeting = Some(greeting.clone());
+      } else if let Some(ref path) = config.display.greeting_file {
+        self.greeting = read_greeting_file(path);
+      } else if unchanged {
+        self.greeting = previous;
+      } else if let Some(ref path) = config.display.greeting_random {
+        self.greeting = random_greeting(path);
+      }
+
+      self.greeting_command = config.display.greeting_command.clone();
+    }
+
+    if let Some(timeout) = config.display.greeting_command_timeout {
+      self.greeting_timeout = Duration::from_secs(timeout);
+    }
+
+    self.greeting_refresh = config
+      .display
+      .greeting_command_refresh
+      .map(Duration::from_secs);
+
+    self.logo = config.layout.logo.as_ref().and_then(|path| {
+      fs::read_to_string(path)
+        .inspect_err(|err| {
+          tracing::warn!("could not read logo file {path}: {err}");
+        })
+        .ok()
+    });
+
+    if !self.config().opt_present("vt-switch") {
+      self.vt_switch = config.session.vt_switch;
+    }
+
+    if !self.config().opt_present("color-depth") {
+      self.color_depth = ColorDepth::from_mode(&config.display.color_depth);
+    }
+
+    if !self.config().opt_present("vt-palette") {
+      self.vt_palette = config.display.vt_palette;
+    }
+
+    if !self.config().opt_present("glyphs") {
+      self.glyphs = Glyphs::from_mode(&config.display.glyphs);
+    }
+
+    if let Some(percent) = config.display.battery_low {
+      self.battery_low = percent;
+    }
+
+    if !self.config().opt_present("idle-timeout") {
+      self.idle_timeout = config.display.idle_timeout.map(Duration::from_secs);
+    }
+
+    if !self.config().opt_present("idle-mode") {
+      self.idle_screen = IdleScreen::from_mode(&config.display.idle_mode);
+    }
+
+    if !self.config().opt_present("mouse") {
+      self.mouse = config.display.mouse;
+    }
+
+    if !self.config().opt_present("screen-reader") {
+      self.screen_reader = config.display.screen_reader;
     }
 
-    if !self.config().opt_present("issue") {
-      // XXX: issue handling is done in parse_options, so we need to set
-      // greeting from issue there
+    if !self.config().opt_present("line-mode") {
+      self.line_mode = line::wanted(&config.display.line_mode);
     }
 
     // Remember config
@@ -1044,19 +1790,18 @@ impl Greeter {
           );
           self.user_menu = false;
         } else {
-          use crate::info::get_users;
-          self.users = Menu {
-            title:    fl!("title_users"),
-            options:  get_users(
-              config.user_menu.min_uid,
-              config.user_menu.max_uid,
-            ),
-            selected: 0,
-          };
+          self.user_menu_uids =
+            (config.user_menu.min_uid, config.user_menu.max_uid);
         }
       }
     }
 
+    self.username_rules = UsernameRules::from(&config.username);
+
+    self.users_enumerate = config.user_menu.enumerate;
+    self.users_limit = config.user_menu.max_users;
+    self.users_timeout = Duration::from_secs(config.user_menu.timeout);
+
     // Secret config
     if !self.config().opt_present("asterisks") {
       match config.secret.mode {
@@ -1078,17 +1823,46 @@ impl Greeter {
     if !self.config().opt_present("kb-power") {
       self.kb_power = config.keybindings.power;
     }
+    if !self.config().opt_present("kb-vts") {
+      self.kb_vts = config.keybindings.vts;
+    }
+    if !self.config().opt_present("kb-layouts") {
+      self.kb_layouts = config.keybindings.layouts;
+    }
 
-    if self.kb_command == self.kb_sessions
-      || self.kb_sessions == self.kb_power
-      || self.kb_power == self.kb_command
+    // Keyboard config
+    if !self.config().opt_present("numlock") {
+      self.numlock = config.keyboard.numlock;
+    }
+
+    self.layouts = Menu {
+      title:    fl!("title_layouts"),
+      options:  config
+        .keyboard
+        .layouts
+        .iter()
+        .map(KeyboardLayout::from)
+        .collect(),
+      selected: 0,
+    };
+
+    // Forget the selected layout if it is not offered anymore.
+    if let Some(ref layout) = self.layout
+      && !self.layouts.options.contains(layout)
     {
+      self.layout = None;
+    }
+
+    if !self.keybindings_distinct() {
       tracing::error!(
         "Keybindings must all be distinct after merging CLI and config. \
-         Found: command={}, sessions={}, power={}. Using CLI values only.",
+         Found: command={}, sessions={}, power={}, vts={}, layouts={}. Using \
+         CLI values only.",
         self.kb_command,
         self.kb_sessions,
-        self.kb_power
+        self.kb_power,
+        self.kb_vts,
+        self.kb_layouts
       );
 
       if !self.config().opt_present("kb-command") {
@@ -1100,6 +1874,12 @@ impl Greeter {
       if !self.config().opt_present("kb-power") {
         self.kb_power = 12;
       }
+      if !self.config().opt_present("kb-vts") {
+        self.kb_vts = 4;
+      }
+      if !self.config().opt_present("kb-layouts") {
+        self.kb_layouts = 5;
+      }
     }
   }
 
@@ -1109,13 +1889,214 @@ impl Greeter {
     theme_config: &crate::config::ThemeConfig,
     cli_theme: Option<&str>,
   ) {
-    use crate::config::theme::{apply_cli_theme, theme_from_config};
+    // Layer the configured theme over its preset and theme file, the theme
+    // file given on the command line replacing the configured one
+    let theme = resolve_theme(
+      theme_config,
+      cli_theme,
+      self.option("theme-file").as_deref(),
+    );
+
+    self.set_theme(theme);
+  }
+
+  // Adapts the theme to the colors the terminal can display, programming the
+  // console palette to match it when asked to and running on a VT.
+  pub fn set_theme(&mut self, theme: Theme) {
+    if self.vt_palette && is_vt() {
+      let base = match self.vt_palette_saved {
+        Some(palette) => Ok(palette),
+        None => vt::get_palette(),
+      };
+
+      match base {
+        Ok(base) => {
+          let (programmed, palette) = program_palette(theme.clone(), &base);
+
+          match vt::set_palette(&palette) {
+            Ok(_) => {
+              self.vt_palette_saved = Some(base);
+              self.theme = programmed;
+
+              return;
+            },
+            Err(err) => tracing::warn!("could not set the VT palette: {err}"),
+          }
+        },
+        Err(err) => tracing::warn!("could not read the VT palette: {err}"),
+      }
+    }
 
-    // Start with theme from config
-    let config_theme = theme_from_config(theme_config);
+    let depth = self.color_depth;
 
-    // Apply CLI theme override if present
-    self.theme = apply_cli_theme(config_theme, cli_theme);
+    self.theme = theme.map_colors(|color| downgrade(color, depth));
+  }
+
+  // Loads the console keymap of the provided layout in the background, so
+  // secrets can be typed with it, and selects the layout once it is loaded.
+  // Outside of a virtual terminal, the keymap is left alone and the layout is
+  // only exported to the session.
+  pub fn select_layout(&mut self, layout: KeyboardLayout) {
+    match self.events {
+      Some(ref events) if is_vt() => {
+        let events = events.clone();
+
+        self.keymap_loaded = true;
+
+        tokio::task::spawn_blocking(move || {
+          let result =
+            vt::load_keymap(&layout.keymap).map_err(|err| err.to_string());
+
+          let _ = events.blocking_send(Event::Layout(layout, result));
+        });
+      },
+
+      _ => self.layout = Some(layout),
+    }
+  }
+
+  // Selects a layout whose keymap was loaded, or tells why it could not be.
+  pub fn set_layout(
+    &mut self,
+    layout: KeyboardLayout,
+    result: Result<(), String>,
+  ) {
+    match result {
+      Ok(()) => self.layout = Some(layout),
+
+      Err(err) => {
+        tracing::error!("could not load keymap {}: {err}", layout.keymap);
+
+        self.message = Some(format!("{}: {err}", fl!("layout_failed")));
+      },
+    }
+  }
+
+  // Saves the console keymap before any layout is loaded, so it can be put
+  // back on exit.
+  pub async fn save_keymap(&mut self) {
+    if !is_vt() || self.layouts.options.is_empty() {
+      return;
+    }
+
+    let result = tokio::task::spawn_blocking(|| {
+      vt::dump_keymap().map_err(|err| err.to_string())
+    })
+    .await
+    .map_err(|err| err.to_string())
+    .and_then(|result| result);
+
+    match result {
+      Ok(keymap) => self.keymap_saved = Some(keymap),
+      Err(err) => tracing::warn!("could not read the console keymap: {err}"),
+    }
+  }
+
+  // Puts the console keymap back as it was before we loaded one.
+  pub async fn restore_keymap(&mut self) {
+    let Some(keymap) = self.keymap_saved.take() else {
+      return;
+    };
+
+    if !self.keymap_loaded {
+      return;
+    }
+
+    let result = tokio::task::spawn_blocking(move || {
+      vt::restore_keymap(&keymap).map_err(|err| err.to_string())
+    })
+    .await
+    .map_err(|err| err.to_string())
+    .and_then(|result| result);
+
+    if let Err(err) = result {
+      tracing::warn!("could not restore the console keymap: {err}");
+    }
+  }
+
+  // Switches to the layout the current user last logged in with, before they
+  // type their password.
+  pub fn restore_user_layout(&mut self) {
+    if !self.remember {
+      return;
+    }
+
+    let Ok(keymap) = get_last_user_layout(&self.username.value) else {
+      return;
+    };
+
+    if self
+      .layout
+      .as_ref()
+      .is_some_and(|layout| layout.keymap == keymap)
+    {
+      return;
+    }
+
+    let layout = self
+      .layouts
+      .options
+      .iter()
+      .find(|layout| layout.keymap == keymap)
+      .cloned();
+
+    if let Some(layout) = layout {
+      tracing::info!("remembered user layout is {keymap}");
+
+      self.select_layout(layout);
+    }
+  }
+
+  // Turns Num Lock on when asked to and running on a VT.
+  pub fn apply_numlock(&self) {
+    if self.numlock
+      && is_vt()
+      && let Err(err) = vt::enable_numlock()
+    {
+      tracing::warn!("could not enable num lock: {err}");
+    }
+  }
+
+  // Selects an entry of the menu being shown, returning whether there is such
+  // an entry.
+  pub fn select_entry(&mut self, index: usize) -> bool {
+    let (selected, len) = match self.mode {
+      Mode::Users => (&mut self.users.selected, self.users.options.len()),
+      Mode::Sessions => {
+        (&mut self.sessions.selected, self.sessions.options.len())
+      },
+      Mode::Power => (&mut self.powers.selected, self.powers.options.len()),
+      Mode::Vts => (&mut self.vts.selected, self.vts.options.len()),
+      Mode::Layouts => (&mut self.layouts.selected, self.layouts.options.len()),
+      _ => return false,
+    };
+
+    if index < len {
+      *selected = index;
+    }
+
+    index < len
+  }
+
+  // Captures the mouse when asked to, or lets go of it otherwise.
+  pub fn apply_mouse(&self) {
+    let result = match self.mouse {
+      true => execute!(io::stdout(), EnableMouseCapture),
+      false => execute!(io::stdout(), DisableMouseCapture),
+    };
+
+    if let Err(err) = result {
+      tracing::warn!("could not capture the mouse: {err}");
+    }
+  }
+
+  // Puts the console palette back as it was before we programmed it.
+  pub fn restore_palette(&mut self) {
+    if let Some(palette) = self.vt_palette_saved.take()
+      && let Err(err) = vt::set_palette(&palette)
+    {
+      tracing::warn!("could not restore the VT palette: {err}");
+    }
   }
 }
 
@@ -1139,7 +2120,17 @@ fn print_version() {
 
 #[cfg(test)]
 mod test {
-  use crate::{Greeter, SecretDisplay, ui::sessions::SessionSource};
+  use std::time::Duration;
+
+  use crate::{
+    Greeter,
+    IdleScreen,
+    SecretDisplay,
+    ui::{
+      sessions::SessionSource,
+      users::{User, UserListing},
+    },
+  };
 
   #[test]
   fn test_prompt_width() {
@@ -1170,6 +2161,36 @@ mod test {
     assert_eq!(greeter.prompt, None);
   }
 
+  #[test]
+  fn test_users_without_enumeration() {
+    let mut greeter = Greeter::default();
+    greeter.user_menu = true;
+    greeter.users_enumerate = false;
+
+    greeter.load_users();
+
+    assert_eq!(greeter.user_listing, UserListing::Lookup);
+    assert!(greeter.users_cancel.is_none());
+
+    greeter.add_users(vec![
+      User {
+        username: "apognu".into(),
+        name:     Some("Antoine".into()),
+      },
+      User {
+        username: "bob".into(),
+        name:     None,
+      },
+    ]);
+    greeter.add_users(vec![User {
+      username: "apognu".into(),
+      name:     None,
+    }]);
+
+    assert_eq!(greeter.users.options.len(), 2);
+    assert_eq!(greeter.users.options[0].name, Some("Antoine".into()));
+  }
+
   #[tokio::test]
   async fn test_command_line_arguments() {
     let table: &[(&[&str], _, Option<fn(&Greeter)>)] = &[
@@ -1241,6 +2262,50 @@ mod test {
           assert!(matches!(greeter.xsession_wrapper, None));
         }),
       ),
+      (
+        &["--kb-vts", "12"],
+        true,
+        Some(|greeter| {
+          assert!(!greeter.vt_switch);
+          assert_eq!(greeter.kb_vts, 12);
+        }),
+      ),
+      (
+        &["--idle-timeout", "300", "--idle-mode", "clock"],
+        true,
+        Some(|greeter| {
+          assert_eq!(greeter.idle_timeout, Some(Duration::from_secs(300)));
+          assert_eq!(greeter.idle_screen, IdleScreen::Clock);
+        }),
+      ),
+      (
+        &["--mouse"],
+        true,
+        Some(|greeter| {
+          assert!(greeter.mouse);
+        }),
+      ),
+      (
+        &["--screen-reader"],
+        true,
+        Some(|greeter| {
+          assert!(greeter.screen_reader);
+        }),
+      ),
+      (
+        &["--line-mode", "always"],
+        true,
+        Some(|greeter| {
+          assert!(greeter.line_mode);
+        }),
+      ),
+      (
+        &["--line-mode", "never"],
+        true,
+        Some(|greeter| {
+          assert!(!greeter.line_mode);
+        }),
+      ),
       // Invalid combinations
       (
         &["--remember-session", "--remember-user-session"],
@@ -1251,8 +2316,17 @@ mod test {
       (&["--remember-user-session"], false, None),
       (&["--min-uid", "10000", "--max-uid", "5000"], false, None),
       (&["--issue", "--greeting", "Hello, world!"], false, None),
+      (
+        &["--greeting", "Hello", "--greeting-command", "date"],
+        false,
+        None,
+      ),
       (&["--kb-command", "F2", "--kb-sessions", "F2"], false, None),
+      (&["--vt-switch", "--kb-vts", "12"], false, None),
       (&["--time-format", "%i %"], false, None),
+      (&["--idle-timeout", "0"], false, None),
+      (&["--idle-mode", "screensaver"], false, None),
+      (&["--line-mode", "serial"], false, None),
       (&["--cmd", "cmd", "--env"], false, None),
       (&["--cmd", "cmd", "--env", "A"], false, None),
     ];
@@ -1310,4 +2384,37 @@ mod test {
       "Keybindings must be distinct after applying config"
     );
   }
+
+  #[tokio::test]
+  async fn test_greeting_reloaded() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("greetings");
+    let lines = (0..100).map(|n| n.to_string()).collect::<Vec<_>>();
+    std::fs::write(&path, lines.join("\n")).unwrap();
+
+    let mut greeter = Greeter::default();
+    greeter.config = Greeter::options().parse([""]).ok();
+
+    let mut config = crate::config::Config::default();
+    config.display.greeting_random = Some(path.to_string_lossy().to_string());
+
+    greeter.apply_config(&config);
+    greeter.loaded_config = Some(config.clone());
+
+    let greeting = greeter.greeting.clone();
+    assert!(greeting.is_some());
+
+    // Reloading the same configuration does not draw another greeting.
+    for _ in 0..10 {
+      greeter.apply_config(&config);
+
+      assert_eq!(greeter.greeting, greeting);
+    }
+
+    // Removing the greeting from the configuration removes it from the screen.
+    config.display.greeting_random = None;
+    greeter.apply_config(&config);
+
+    assert_eq!(greeter.greeting, None);
+  }
 }
@@ -0,0 +1,134 @@
+use std::{fs, path::Path, time::Duration};
+
+use rand::seq::IndexedRandom;
+use tokio::{sync::mpsc::Sender, task::JoinHandle};
+
+use crate::{event::Event, info::run_command};
+
+const GREETING_CACHE: &str = "/var/cache/tuigreet/greeting";
+
+// Reads the greeting from a file, keeping its ANSI escape sequences.
+pub fn read_greeting_file<P>(path: P) -> Option<String>
+where
+  P: AsRef<Path>,
+{
+  let path = path.as_ref();
+
+  fs::read_to_string(path)
+    .inspect_err(|err| {
+      tracing::warn!("could not read greeting file {}: {err}", path.display());
+    })
+    .ok()
+}
+
+// Picks one of the non-empty lines of a file at random.
+pub fn random_greeting<P>(path: P) -> Option<String>
+where
+  P: AsRef<Path>,
+{
+  let contents = read_greeting_file(path)?;
+  let lines: Vec<&str> = contents
+    .lines()
+    .filter(|line| !line.trim().is_empty())
+    .collect();
+
+  lines.choose(&mut rand::rng()).map(|line| line.to_string())
+}
+
+// Output of the greeting command from the last time it ran, shown until it
+// completes again. The output of another command, from before the
+// configuration changed, is not shown.
+pub fn get_cached_greeting(command: &str) -> Option<String> {
+  read_cache(GREETING_CACHE, command)
+}
+
+// The cache holds the command, then a NUL byte, which no command can contain,
+// then its output.
+fn read_cache<P>(path: P, command: &str) -> Option<String>
+where
+  P: AsRef<Path>,
+{
+  fs::read_to_string(path)
+    .ok()?
+    .split_once('\0')
+    .filter(|(cached, _)| *cached == command)
+    .map(|(_, greeting)| greeting.to_string())
+    .filter(|greeting| !greeting.trim().is_empty())
+}
+
+fn write_cache<P>(path: P, command: &str, greeting: &str)
+where
+  P: AsRef<Path>,
+{
+  let _ = fs::write(path, format!("{command}\0{greeting}"));
+}
+
+// Runs the greeting command in the background, sending its output to the UI
+// each time it completes, then again every `refresh` if provided. A run that
+// does not complete within `timeout` is killed and leaves the greeting as is.
+pub fn spawn_greeting_command(
+  command: String,
+  timeout: Duration,
+  refresh: Option<Duration>,
+  events: Sender<Event>,
+) -> JoinHandle<()> {
+  tokio::task::spawn(async move {
+    loop {
+      if let Some(greeting) = run_command(&command, timeout).await {
+        write_cache(GREETING_CACHE, &command, &greeting);
+        let _ = events.send(Event::Greeting(greeting)).await;
+      }
+
+      match refresh {
+        Some(refresh) => tokio::time::sleep(refresh).await,
+        None => break,
+      }
+    }
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use std::fs;
+
+  use tempfile::tempdir;
+
+  use super::{random_greeting, read_cache, write_cache};
+
+  #[test]
+  fn random_line() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("greetings");
+
+    fs::write(&path, "\nfirst\n  \nsecond\n").unwrap();
+
+    for _ in 0..10 {
+      let greeting = random_greeting(&path).unwrap();
+
+      assert!(["first", "second"].contains(&greeting.as_str()));
+    }
+
+    fs::write(&path, "\n").unwrap();
+
+    assert_eq!(random_greeting(&path), None);
+    assert_eq!(random_greeting(dir.path().join("missing")), None);
+  }
+
+  #[test]
+  fn cache_per_command() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("greeting");
+
+    assert_eq!(read_cache(&path, "fortune"), None);
+
+    write_cache(&path, "fortune", "Hello\nthere");
+
+    assert_eq!(read_cache(&path, "fortune"), Some("Hello\nthere".into()));
+    assert_eq!(read_cache(&path, "fortune -s"), None);
+
+    // Caches written before the command was kept are ignored.
+    fs::write(&path, "Hello").unwrap();
+
+    assert_eq!(read_cache(&path, "fortune"), None);
+  }
+}
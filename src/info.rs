@@ -3,22 +3,31 @@ use std::{
   error::Error,
   fs::{self, File},
   io::{self, BufRead, BufReader},
+  mem,
   path::{Path, PathBuf},
-  process::Command,
-  sync::OnceLock,
+  process::Stdio,
+  sync::{
+    Arc,
+    Mutex,
+    OnceLock,
+    PoisonError,
+    atomic::{AtomicBool, Ordering},
+  },
+  time::{Duration, Instant},
 };
 
-use chrono::Local;
 use ini::Ini;
+use tokio::{process::Command, sync::mpsc::Sender, task::JoinHandle};
 use utmp_rs::{UtmpEntry, UtmpParser};
 use uzers::os::unix::UserExt;
 
 use crate::{
   Greeter,
+  event::Event,
   ui::{
     common::masked::MaskedString,
     sessions::{Session, SessionType},
-    users::User,
+    users::{User, UserListing},
   },
 };
 
@@ -26,10 +35,26 @@ const LAST_USER_USERNAME: &str = "/var/cache/tuigreet/lastuser";
 const LAST_USER_NAME: &str = "/var/cache/tuigreet/lastuser-name";
 const LAST_COMMAND: &str = "/var/cache/tuigreet/lastsession";
 const LAST_SESSION: &str = "/var/cache/tuigreet/lastsession-path";
+const LAST_LAYOUT: &str = "/var/cache/tuigreet/lastlayout";
+
+pub const UTMP_PATH: &str = "/var/run/utmp";
+const SYSTEMD_SESSIONS_PATH: &str = "/run/systemd/sessions";
+const VCONSOLE_CONF: &str = "/etc/vconsole.conf";
+const UPTIME_PATH: &str = "/proc/uptime";
 
 const DEFAULT_MIN_UID: u32 = 1000;
 const DEFAULT_MAX_UID: u32 = 60000;
 
+const USERS_BATCH_SIZE: usize = 50;
+const USERS_BATCH_INTERVAL: Duration = Duration::from_millis(100);
+const USER_LOOKUP_DELAY: Duration = Duration::from_millis(300);
+
+const STATUS_COMMAND_TIMEOUT: Duration = Duration::from_secs(5);
+
+// Held while walking the user database, which `getpwent` cannot do twice at
+// once.
+static USERS_WALK: Mutex<()> = Mutex::new(());
+
 static XDG_DATA_DIRS: OnceLock<Vec<PathBuf>> = OnceLock::new();
 static DEFAULT_SESSION_PATHS: OnceLock<Vec<(PathBuf, SessionType)>> =
   OnceLock::new();
@@ -65,70 +90,21 @@ pub fn get_hostname() -> String {
   }
 }
 
-pub fn get_issue() -> Option<String> {
-  let (date, time) = {
-    let now = Local::now();
-
-    (
-      now.format("%a %b %_d %Y").to_string(),
-      now.format("%H:%M:%S").to_string(),
-    )
-  };
-
-  let user_count = match UtmpParser::from_path("/var/run/utmp")
-    .map(|utmp| {
-      utmp.into_iter().fold(0, |acc, entry| {
-        match entry {
-          Ok(UtmpEntry::UserProcess { .. }) => acc + 1,
-          Ok(UtmpEntry::LoginProcess { .. }) => acc + 1,
-          _ => acc,
-        }
-      })
-    })
-    .unwrap_or(0)
-  {
-    n if n < 2 => format!("{n} user"),
-    n => format!("{n} users"),
-  };
-
-  let uts = nix::sys::utsname::uname();
-  let vtnr: usize = env::var("XDG_VTNR")
-    .unwrap_or_else(|_| "0".to_string())
-    .parse()
-    .unwrap_or(0);
-
-  if let Ok(issue) = fs::read_to_string("/etc/issue") {
-    let issue = issue
-      .replace("\\S", "Linux")
-      .replace("\\l", &format!("tty{vtnr}"))
-      .replace("\\d", &date)
-      .replace("\\t", &time)
-      .replace("\\U", &user_count);
-
-    let issue = match uts {
-      Ok(uts) => {
-        issue
-          .replace("\\s", uts.sysname().to_str().unwrap_or(""))
-          .replace("\\r", uts.release().to_str().unwrap_or(""))
-          .replace("\\v", uts.version().to_str().unwrap_or(""))
-          .replace("\\n", uts.nodename().to_str().unwrap_or(""))
-          .replace("\\m", uts.machine().to_str().unwrap_or(""))
-          .replace("\\o", uts.domainname().to_str().unwrap_or(""))
-      },
-
-      _ => issue,
-    };
-
-    return Some(
-      issue
-        .replace("\\x1b", "\x1b")
-        .replace("\\033", "\x1b")
-        .replace("\\e", "\x1b")
-        .replace(r"\\", r"\"),
-    );
+// Describes the machine for the system information widget, from its host name
+// and kernel.
+pub fn get_system_info() -> String {
+  match nix::sys::utsname::uname() {
+    Ok(uts) => {
+      fl!(
+        "system_info",
+        hostname = uts.nodename().to_string_lossy().to_string(),
+        system = uts.sysname().to_string_lossy().to_string(),
+        release = uts.release().to_string_lossy().to_string(),
+        machine = uts.machine().to_string_lossy().to_string()
+      )
+    },
+    _ => String::new(),
   }
-
-  None
 }
 
 pub fn get_last_user_username() -> Option<String> {
@@ -215,6 +191,18 @@ where
   );
 }
 
+pub fn get_last_user_layout(username: &str) -> Result<String, io::Error> {
+  Ok(
+    fs::read_to_string(format!("{LAST_LAYOUT}-{username}"))?
+      .trim()
+      .to_string(),
+  )
+}
+
+pub fn write_last_user_layout(username: &str, keymap: &str) {
+  let _ = fs::write(format!("{LAST_LAYOUT}-{username}"), keymap);
+}
+
 pub fn delete_last_session() {
   let _ = fs::remove_file(LAST_SESSION);
 }
@@ -235,30 +223,135 @@ pub fn delete_last_user_command(username: &str) {
   let _ = fs::remove_file(format!("{LAST_COMMAND}-{username}"));
 }
 
-pub fn get_users(min_uid: u32, max_uid: u32) -> Vec<User> {
+// Iterates over the users of the system within the provided UID range. This
+// can be slow and large when users come from a directory service, so callers
+// should not consume it on the UI thread.
+pub fn get_users(min_uid: u32, max_uid: u32) -> impl Iterator<Item = User> {
   let users = unsafe { uzers::all_users() };
 
-  let users: Vec<User> = users
-    .filter(|user| user.uid() >= min_uid && user.uid() <= max_uid)
-    .map(|user| {
-      User {
-        username: user.name().to_string_lossy().to_string(),
-        name:     match user.gecos() {
-          name if name.is_empty() => None,
-          name => {
-            let name = name.to_string_lossy();
-
-            match name.split_once(',') {
-              Some((name, _)) => Some(name.to_string()),
-              None => Some(name.to_string()),
-            }
-          },
-        },
+  users
+    .filter(move |user| user.uid() >= min_uid && user.uid() <= max_uid)
+    .map(|user| to_user(&user))
+}
+
+// Enumerates users on a blocking thread, sending them to the UI in batches as
+// they are found. Enumeration gives up when it takes longer than `timeout` or
+// finds more than `limit` users, in which case users should be looked up by
+// name instead. Setting `cancel` stops the enumeration without notice. An
+// enumeration that was replaced is waited for before walking the users again.
+pub fn enumerate_users(
+  (min_uid, max_uid): (u32, u32),
+  limit: usize,
+  timeout: Duration,
+  cancel: Arc<AtomicBool>,
+  events: Sender<Event>,
+) {
+  let enumeration = tokio::task::spawn_blocking({
+    let cancel = cancel.clone();
+    let events = events.clone();
+
+    move || {
+      let _walk = USERS_WALK.lock().unwrap_or_else(PoisonError::into_inner);
+
+      let mut batch = Vec::with_capacity(USERS_BATCH_SIZE);
+      let mut flushed_at = Instant::now();
+      let mut count = 0;
+
+      for user in get_users(min_uid, max_uid) {
+        if cancel.load(Ordering::SeqCst) {
+          return None;
+        }
+
+        count += 1;
+
+        if count > limit {
+          tracing::warn!("more than {limit} users found, stopping enumeration");
+
+          let _ = events.blocking_send(Event::Users(batch));
+
+          return Some(UserListing::Lookup);
+        }
+
+        batch.push(user);
+
+        if batch.len() >= USERS_BATCH_SIZE
+          || flushed_at.elapsed() >= USERS_BATCH_INTERVAL
+        {
+          let _ = events.blocking_send(Event::Users(mem::take(&mut batch)));
+
+          flushed_at = Instant::now();
+        }
       }
-    })
-    .collect();
 
-  users
+      let _ = events.blocking_send(Event::Users(batch));
+
+      Some(UserListing::Complete)
+    }
+  });
+
+  tokio::task::spawn(async move {
+    let listing = match tokio::time::timeout(timeout, enumeration).await {
+      Ok(Ok(Some(listing))) => listing,
+      Ok(Ok(None)) => return,
+      Ok(Err(err)) => {
+        tracing::error!("user enumeration failed: {err}");
+
+        UserListing::Lookup
+      },
+      Err(_) => {
+        tracing::warn!("user enumeration timed out after {timeout:?}");
+
+        UserListing::Lookup
+      },
+    };
+
+    // If the enumeration was cancelled in the meantime, its results are not
+    // relevant anymore. Otherwise, this stops it if it timed out.
+    if !cancel.swap(true, Ordering::SeqCst) {
+      let _ = events.send(Event::UsersLoaded(listing)).await;
+    }
+  });
+}
+
+// Looks a single user up by its exact username, in the background, sending it
+// to the UI if it exists within the provided UID range. The lookup only starts
+// once typing pauses, and aborting the returned task drops it along with its
+// result.
+pub fn lookup_user(
+  username: String,
+  (min_uid, max_uid): (u32, u32),
+  events: Sender<Event>,
+) -> JoinHandle<()> {
+  tokio::task::spawn(async move {
+    tokio::time::sleep(USER_LOOKUP_DELAY).await;
+
+    let lookup = tokio::task::spawn_blocking(move || {
+      uzers::get_user_by_name(&username)
+        .filter(|user| user.uid() >= min_uid && user.uid() <= max_uid)
+        .map(|user| to_user(&user))
+    });
+
+    if let Ok(Some(user)) = lookup.await {
+      let _ = events.send(Event::Users(vec![user])).await;
+    }
+  })
+}
+
+fn to_user(user: &uzers::User) -> User {
+  User {
+    username: user.name().to_string_lossy().to_string(),
+    name:     match user.gecos() {
+      name if name.is_empty() => None,
+      name => {
+        let name = name.to_string_lossy();
+
+        match name.split_once(',') {
+          Some((name, _)) => Some(name.to_string()),
+          None => Some(name.to_string()),
+        }
+      },
+    },
+  }
 }
 
 pub fn get_min_max_uids(
@@ -389,13 +482,262 @@ where
   }))
 }
 
-pub fn capslock_status() -> bool {
-  let mut command = Command::new("kbdinfo");
-  command.args(["gkbled", "capslock"]);
+// Lists the virtual terminals currently running a user session, along with
+// the user owning them, sorted by VT number.
+//
+// Sessions are gathered both from utmp and from systemd-logind's runtime
+// state, since not all session managers register in both. The VT we are
+// running on is never reported.
+pub fn get_occupied_vts() -> Vec<(u32, String)> {
+  let own_vt = env::var("XDG_VTNR")
+    .ok()
+    .and_then(|vtnr| vtnr.parse::<u32>().ok());
+
+  let mut vts: Vec<(u32, String)> = vec![];
+
+  if let Ok(utmp) = UtmpParser::from_path(UTMP_PATH) {
+    for entry in utmp.into_iter().flatten() {
+      if let UtmpEntry::UserProcess { line, user, .. } = entry
+        && let Some(vt) = parse_vt_line(&line)
+      {
+        vts.push((vt, user));
+      }
+    }
+  }
+
+  if let Ok(entries) = fs::read_dir(SYSTEMD_SESSIONS_PATH) {
+    for entry in entries.flatten() {
+      if let Ok(session) = fs::read_to_string(entry.path())
+        && let Some(vt) = parse_systemd_session(&session)
+      {
+        vts.push(vt);
+      }
+    }
+  }
+
+  vts.retain(|(vt, _)| Some(*vt) != own_vt);
+  vts.sort();
+  vts.dedup();
+
+  vts
+}
+
+// Lists the virtual terminals on which the provided user has a session.
+pub fn get_user_vts(username: &str) -> Vec<u32> {
+  get_occupied_vts()
+    .into_iter()
+    .filter(|(_, user)| user == username)
+    .map(|(vt, _)| vt)
+    .collect()
+}
+
+// Extracts the VT number from a utmp line, such as `tty3`.
+fn parse_vt_line(line: &str) -> Option<u32> {
+  line
+    .strip_prefix("/dev/")
+    .unwrap_or(line)
+    .strip_prefix("tty")
+    .and_then(|vt| vt.parse::<u32>().ok())
+    .filter(|vt| *vt > 0)
+}
+
+// Extracts the VT and user of a live session from a systemd-logind session
+// state file.
+fn parse_systemd_session(session: &str) -> Option<(u32, String)> {
+  let mut vt = None;
+  let mut user = None;
+
+  for line in session.lines() {
+    match line.split_once('=') {
+      Some(("VTNR", value)) => vt = value.parse::<u32>().ok(),
+      Some(("USER", value)) => user = Some(value.to_string()),
+      Some(("STATE", "closing")) => return None,
+      Some(("CLASS", class)) if class != "user" => return None,
+      _ => {},
+    }
+  }
+
+  match (vt, user) {
+    (Some(vt), Some(user)) if vt > 0 => Some((vt, user)),
+    _ => None,
+  }
+}
+
+// Runs a shell command and returns its output, unless it fails or does not
+// complete within `timeout`, in which case it is killed.
+pub async fn run_command(command: &str, timeout: Duration) -> Option<String> {
+  tracing::info!("running command: {command}");
+
+  let child = Command::new("/bin/sh")
+    .arg("-c")
+    .arg(command)
+    .stdin(Stdio::null())
+    .stdout(Stdio::piped())
+    .stderr(Stdio::null())
+    .kill_on_drop(true)
+    .spawn()
+    .inspect_err(|err| tracing::warn!("could not run command {command}: {err}"))
+    .ok()?;
+
+  match tokio::time::timeout(timeout, child.wait_with_output()).await {
+    Ok(Ok(output)) if output.status.success() => {
+      Some(String::from_utf8_lossy(&output.stdout).into_owned())
+    },
+    Ok(Ok(output)) => {
+      tracing::warn!("command {command} failed with {}", output.status);
+
+      None
+    },
+    Ok(Err(err)) => {
+      tracing::warn!("could not run command {command}: {err}");
+
+      None
+    },
+    Err(_) => {
+      tracing::warn!("command {command} timed out after {timeout:?}");
+
+      None
+    },
+  }
+}
+
+// Runs the commands of the status bar segments in the background, sending the
+// first line of their output to the UI, then again every `refresh` if
+// provided.
+pub fn spawn_status_commands(
+  commands: Vec<String>,
+  refresh: Option<Duration>,
+  events: Sender<Event>,
+) -> JoinHandle<()> {
+  tokio::task::spawn(async move {
+    loop {
+      for command in &commands {
+        if let Some(output) = run_command(command, STATUS_COMMAND_TIMEOUT).await
+        {
+          let output = output.lines().next().unwrap_or_default().to_string();
+
+          let _ = events
+            .send(Event::StatusCommand(command.clone(), output))
+            .await;
+        }
+      }
+
+      match refresh {
+        Some(refresh) => tokio::time::sleep(refresh).await,
+        None => break,
+      }
+    }
+  })
+}
+
+pub fn get_kernel_release() -> String {
+  match nix::sys::utsname::uname() {
+    Ok(uts) => uts.release().to_string_lossy().to_string(),
+    _ => String::new(),
+  }
+}
+
+// Keyboard layout sessions will be started with, as exported to compositors,
+// or else the keymap the console was configured with.
+pub fn get_keyboard_layout() -> Option<String> {
+  if let Ok(layout) = env::var("XKB_DEFAULT_LAYOUT")
+    && !layout.is_empty()
+  {
+    return Some(layout);
+  }
+
+  let vconsole = fs::read_to_string(VCONSOLE_CONF).ok()?;
+
+  parse_vconsole_keymap(&vconsole)
+}
+
+fn parse_vconsole_keymap(vconsole: &str) -> Option<String> {
+  vconsole.lines().find_map(|line| {
+    let keymap = line.trim().strip_prefix("KEYMAP=")?;
+    let keymap = keymap.trim_matches(['"', '\'']);
+
+    (!keymap.is_empty()).then(|| keymap.to_string())
+  })
+}
+
+pub fn get_uptime() -> Option<Duration> {
+  let uptime = fs::read_to_string(UPTIME_PATH).ok()?;
+  let seconds = uptime.split_whitespace().next()?.parse::<f64>().ok()?;
 
-  match command.output() {
-    Ok(output) => output.status.code() == Some(0),
-    Err(_) => false,
+  Some(Duration::from_secs_f64(seconds))
+}
+
+#[cfg(test)]
+mod test {
+  use std::time::Duration;
+
+  use super::{
+    parse_systemd_session,
+    parse_vconsole_keymap,
+    parse_vt_line,
+    run_command,
+  };
+
+  #[test]
+  fn vt_from_utmp_line() {
+    assert_eq!(parse_vt_line("tty3"), Some(3));
+    assert_eq!(parse_vt_line("/dev/tty12"), Some(12));
+    assert_eq!(parse_vt_line("tty0"), None);
+    assert_eq!(parse_vt_line("pts/1"), None);
+    assert_eq!(parse_vt_line(":0"), None);
+  }
+
+  #[test]
+  fn vt_from_systemd_session() {
+    let session = [
+      "# This is private data. Do not parse.",
+      "UID=1000",
+      "USER=apognu",
+      "ACTIVE=0",
+      "STATE=online",
+      "TYPE=wayland",
+      "CLASS=user",
+      "VTNR=3",
+    ]
+    .join("\n");
+
+    assert_eq!(
+      parse_systemd_session(&session),
+      Some((3, "apognu".to_string()))
+    );
+
+    let closing = session.replace("STATE=online", "STATE=closing");
+    let greeter = session.replace("CLASS=user", "CLASS=greeter");
+    let remote = session.replace("VTNR=3", "");
+
+    assert_eq!(parse_systemd_session(&closing), None);
+    assert_eq!(parse_systemd_session(&greeter), None);
+    assert_eq!(parse_systemd_session(&remote), None);
+  }
+
+  #[tokio::test]
+  async fn command_output() {
+    let timeout = Duration::from_secs(5);
+
+    assert_eq!(
+      run_command("printf 'maintenance at 22:00'", timeout).await,
+      Some("maintenance at 22:00".to_string())
+    );
+    assert_eq!(run_command("echo hello; false", timeout).await, None);
+    assert_eq!(
+      run_command("sleep 5", Duration::from_millis(50)).await,
+      None
+    );
+  }
+
+  #[test]
+  fn keymap_from_vconsole() {
+    assert_eq!(
+      parse_vconsole_keymap("FONT=ter-v16n\nKEYMAP=\"de-latin1\"\n"),
+      Some("de-latin1".to_string())
+    );
+    assert_eq!(parse_vconsole_keymap("KEYMAP=us"), Some("us".to_string()));
+    assert_eq!(parse_vconsole_keymap("FONT=ter-v16n\nKEYMAP=\n"), None);
   }
 }
 
@@ -406,7 +748,7 @@ mod nsswrapper_tests {
   fn nsswrapper_get_users_from_nss() {
     use super::get_users;
 
-    let users = get_users(1000, 2000);
+    let users = get_users(1000, 2000).collect::<Vec<_>>();
 
     assert_eq!(users.len(), 2);
     assert_eq!(users[0].username, "joe");
@@ -45,9 +45,9 @@ async fn authentication_bad_password() {
         runner.send_text("apognu").await;
         runner.wait_until_buffer_contains("Password:").await;
         runner.send_text("password2").await;
-        runner.wait_for_render().await;
-
-        assert!(runner.output().await.contains("Authentication failed"));
+        runner
+          .wait_until_buffer_contains("Authentication failed")
+          .await;
       }
     }
   });
@@ -55,6 +55,68 @@ async fn authentication_bad_password() {
   runner.join_until_end(events).await;
 }
 
+#[tokio::test]
+async fn authentication_bad_password_screen_reader() {
+  let opts = SessionOptions {
+    username: "apognu".to_string(),
+    password: "password".to_string(),
+    mfa:      false,
+  };
+
+  let mut runner = IntegrationRunner::new(
+    opts,
+    Some(|greeter| {
+      greeter.screen_reader = true;
+    }),
+  )
+  .await;
+
+  let events = tokio::task::spawn({
+    let mut runner = runner.clone();
+
+    async move {
+      runner.wait_until_buffer_contains("Username:").await;
+      runner.send_text("apognu").await;
+      runner.wait_until_buffer_contains("Password:").await;
+      runner.send_text("password2").await;
+      runner
+        .wait_until_buffer_contains("Authentication failed")
+        .await;
+
+      // The same user is asked for a password again right away.
+      let lines = loop {
+        let lines = runner
+          .output()
+          .await
+          .lines()
+          .map(str::trim_end)
+          .filter(|line| !line.is_empty())
+          .skip(1)
+          .map(String::from)
+          .collect::<Vec<_>>();
+
+        if lines.len() == 4 && lines[3] == "Password:" {
+          break lines;
+        }
+
+        runner.wait_for_render().await;
+      };
+
+      // Every step is a line of its own, in the order it happened, without a
+      // single border around them.
+      assert_eq!(lines, [
+        "Username: apognu",
+        "Password:",
+        "Authentication failed, please try again.",
+        "Password:"
+      ]);
+      assert!(!runner.output().await.contains('─'));
+    }
+  });
+
+  runner.join_until_end(events).await;
+}
+
 #[tokio::test]
 async fn authentication_ok_mfa() {
   let opts = SessionOptions {
@@ -101,10 +163,10 @@ async fn authentication_bad_mfa() {
       runner.send_text("password").await;
       runner.wait_until_buffer_contains("7 + 2 =   ").await;
       runner.send_text("10").await;
-      runner.wait_for_render().await;
-
-      assert!(runner.output().await.contains("Authentication failed"));
-      assert!(runner.output().await.contains("Password:"));
+      runner
+        .wait_until_buffer_contains("Authentication failed")
+        .await;
+      runner.wait_until_buffer_contains("Password:").await;
     }
   });
 
@@ -45,9 +45,8 @@ use unicode_width::UnicodeWidthStr;
 #[derive(Clone)]
 pub struct TestBackend {
   tick:   mpsc::Sender<bool>,
-  width:  u16,
+  // Shared with the test, which can resize it like a terminal would be.
   buffer: Arc<Mutex<Buffer>>,
-  height: u16,
   cursor: bool,
   pos:    (u16, u16),
 }
@@ -88,12 +87,10 @@ impl TestBackend {
     let (tx, rx) = mpsc::channel::<bool>(10);
 
     let backend = Self {
-      tick: tx,
-      width,
-      height,
+      tick:   tx,
       buffer: buffer.clone(),
       cursor: false,
-      pos: (0, 0),
+      pos:    (0, 0),
     };
 
     (backend, buffer, rx)
@@ -158,7 +155,7 @@ impl Backend for TestBackend {
     let mut buffer = buffer.lock().unwrap();
 
     match clear_type {
-      ClearType::All => self.clear()?,
+      ClearType::All => buffer.reset(),
       ClearType::AfterCursor => {
         let index = buffer.index_of(self.pos.0, self.pos.1) + 1;
         buffer.content[index..].fill(Cell::default());
@@ -169,12 +166,12 @@ impl Backend for TestBackend {
       },
       ClearType::CurrentLine => {
         let line_start_index = buffer.index_of(0, self.pos.1);
-        let line_end_index = buffer.index_of(self.width - 1, self.pos.1);
+        let line_end_index = buffer.index_of(buffer.area.width - 1, self.pos.1);
         buffer.content[line_start_index..=line_end_index].fill(Cell::default());
       },
       ClearType::UntilNewLine => {
         let index = buffer.index_of(self.pos.0, self.pos.1);
-        let line_end_index = buffer.index_of(self.width - 1, self.pos.1);
+        let line_end_index = buffer.index_of(buffer.area.width - 1, self.pos.1);
         buffer.content[index..=line_end_index].fill(Cell::default());
       },
     }
@@ -184,16 +181,16 @@ impl Backend for TestBackend {
   fn append_lines(&mut self, n: u16) -> io::Result<()> {
     let cursor_pos = self.get_cursor_position()?;
     let (cur_x, cur_y) = (cursor_pos.x, cursor_pos.y);
+    let Size { width, height } = self.size()?;
 
-    let new_cursor_x =
-      cur_x.saturating_add(1).min(self.width.saturating_sub(1));
+    let new_cursor_x = cur_x.saturating_add(1).min(width.saturating_sub(1));
 
-    let max_y = self.height.saturating_sub(1);
+    let max_y = height.saturating_sub(1);
     let lines_after_cursor = max_y.saturating_sub(cur_y);
     if n > lines_after_cursor {
       let rotate_by = n.saturating_sub(lines_after_cursor).min(max_y);
 
-      if rotate_by == self.height - 1 {
+      if rotate_by == height - 1 {
         self.clear()?;
       }
 
@@ -204,7 +201,7 @@ impl Backend for TestBackend {
         .lock()
         .unwrap()
         .content
-        .rotate_left((self.width * rotate_by).into());
+        .rotate_left((width * rotate_by).into());
     }
 
     let new_cursor_y = cur_y.saturating_add(n).min(max_y);
@@ -214,7 +211,7 @@ impl Backend for TestBackend {
   }
 
   fn size(&self) -> io::Result<Size> {
-    Ok(Size::new(self.width, self.height))
+    Ok(self.buffer.lock().unwrap().area.as_size())
   }
 
   fn window_size(&mut self) -> io::Result<WindowSize> {
@@ -223,7 +220,7 @@ impl Backend for TestBackend {
       height: 480,
     };
     Ok(WindowSize {
-      columns_rows: (self.width, self.height).into(),
+      columns_rows: self.size()?,
       pixels:       WINDOW_PIXEL_SIZE,
     })
   }
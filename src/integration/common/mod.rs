@@ -7,7 +7,14 @@ use std::{
   time::Duration,
 };
 
-use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use crossterm::event::{
+  KeyCode,
+  KeyEvent,
+  KeyModifiers,
+  MouseButton,
+  MouseEvent,
+  MouseEventKind,
+};
 use libgreetd_stub::SessionOptions;
 use tempfile::NamedTempFile;
 use tokio::{
@@ -17,7 +24,7 @@ use tokio::{
   },
   task::{JoinError, JoinHandle},
 };
-use tui::buffer::Buffer;
+use tui::{buffer::Buffer, layout::Rect};
 
 pub(super) use self::{
   backend::{TestBackend, output},
@@ -25,7 +32,9 @@ pub(super) use self::{
 };
 use crate::{
   Greeter,
+  config::LineMode,
   event::{Event, Events},
+  line,
   ui::sessions::SessionSource,
 };
 
@@ -85,6 +94,10 @@ impl IntegrationRunner {
         greeter.config = Greeter::options().parse(&[""]).ok();
       }
 
+      // The screen is drawn on the test backend, whatever the terminal running
+      // the tests.
+      greeter.line_mode = line::wanted(&LineMode::Never);
+
       greeter.logfile = "/tmp/tuigreet.log".to_string();
       greeter.socket = socket.to_str().unwrap().to_string();
       greeter.events = Some(events.sender());
@@ -131,7 +144,7 @@ impl IntegrationRunner {
     };
 
     tokio::select! {
-      _ = tokio::time::sleep(Duration::from_secs(5)) => {},
+      _ = tokio::time::sleep(Duration::from_secs(5)) => panic!("test did not finish"),
       _ = server => {}
       _ = client => {},
       ret = events => rethrow(ret),
@@ -198,6 +211,52 @@ impl IntegrationRunner {
       .await;
   }
 
+  // Sends a mouse event at some position on the screen.
+  #[allow(unused, unused_must_use)]
+  pub async fn send_mouse(&self, kind: MouseEventKind, column: u16, row: u16) {
+    self
+      .0
+      .write()
+      .await
+      .sender
+      .send(Event::Mouse(MouseEvent {
+        kind,
+        column,
+        row,
+        modifiers: KeyModifiers::empty(),
+      }))
+      .await;
+  }
+
+  // Clicks on the first place some text is shown on the screen.
+  #[allow(unused)]
+  pub async fn click(&self, needle: &str) {
+    let (column, row) = self
+      .output()
+      .await
+      .position(needle)
+      .unwrap_or_else(|| panic!("{needle:?} is not on the screen"));
+
+    self
+      .send_mouse(MouseEventKind::Down(MouseButton::Left), column, row)
+      .await;
+  }
+
+  // Resizes the screen the greeter draws on, and tells it like a terminal
+  // would.
+  #[allow(unused, unused_must_use)]
+  pub async fn resize(&self, width: u16, height: u16) {
+    let runner = self.0.write().await;
+
+    runner
+      .buffer
+      .lock()
+      .unwrap()
+      .resize(Rect::new(0, 0, width, height));
+
+    runner.sender.send(Event::Resize(width, height)).await;
+  }
+
   #[allow(unused)]
   pub async fn wait_for_render(&mut self) {
     self.0.write().await.tick.recv().await;
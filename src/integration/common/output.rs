@@ -1,5 +1,7 @@
 use std::ops::Deref;
 
+use unicode_width::UnicodeWidthStr;
+
 pub(in crate::integration) struct Output(pub String);
 
 impl Deref for Output {
@@ -12,6 +14,15 @@ impl Deref for Output {
 
 #[allow(dead_code)]
 impl Output {
+  // Finds the column and row at which some text is shown on the screen.
+  pub fn position(&self, needle: &str) -> Option<(u16, u16)> {
+    self.lines().enumerate().find_map(|(row, line)| {
+      let index = line.find(needle)?;
+
+      Some((line[..index].width() as u16, row as u16))
+    })
+  }
+
   pub fn debug_print(&self) {
     for line in self.lines() {
       println!("{}", line);
@@ -1,6 +1,7 @@
 use std::time::Duration;
 
 use chrono::Local;
+use crossterm::event::KeyCode;
 use libgreetd_stub::SessionOptions;
 
 use super::common::IntegrationRunner;
@@ -25,9 +26,9 @@ async fn show_greet() {
     let mut runner = runner.clone();
 
     async move {
-      runner.wait_for_render().await;
-
-      assert!(runner.output().await.contains("Lorem ipsum dolor sit amet"));
+      runner
+        .wait_until_buffer_contains("Lorem ipsum dolor sit amet")
+        .await;
     }
   });
 
@@ -112,3 +113,130 @@ async fn show_time() {
 
   runner.join_until_end(events).await;
 }
+
+#[tokio::test]
+async fn blank_when_idle() {
+  let opts = SessionOptions {
+    username: "apognu".to_string(),
+    password: "password".to_string(),
+    mfa:      false,
+  };
+
+  let mut runner = IntegrationRunner::new(
+    opts,
+    Some(|greeter| {
+      greeter.idle_timeout = Some(Duration::from_secs(1));
+    }),
+  )
+  .await;
+
+  let events = tokio::task::spawn({
+    let mut runner = runner.clone();
+
+    async move {
+      runner.send_key(KeyCode::Char('a')).await;
+      runner.send_key(KeyCode::Char('p')).await;
+      runner.wait_until_buffer_contains("Username: ap").await;
+
+      while runner.output().await.contains("Username:") {
+        runner.wait_for_render().await;
+      }
+
+      assert!(runner.output().await.trim().is_empty());
+
+      runner.send_key(KeyCode::Char('x')).await;
+      runner.wait_until_buffer_contains("Username:").await;
+
+      let output = runner.output().await;
+
+      assert!(!output.contains("Username: ap"));
+      assert!(!output.contains("Username: x"));
+    }
+  });
+
+  runner.join_until_end(events).await;
+}
+
+#[tokio::test]
+async fn blank_when_idle_screen_reader() {
+  let opts = SessionOptions {
+    username: "apognu".to_string(),
+    password: "password".to_string(),
+    mfa:      false,
+  };
+
+  let mut runner = IntegrationRunner::new(
+    opts,
+    Some(|greeter| {
+      greeter.screen_reader = true;
+      greeter.idle_timeout = Some(Duration::from_secs(1));
+    }),
+  )
+  .await;
+
+  let events = tokio::task::spawn({
+    let mut runner = runner.clone();
+
+    async move {
+      runner.wait_until_buffer_contains("Username:").await;
+      runner.send_text("apognu").await;
+      runner.wait_until_buffer_contains("Password:").await;
+
+      while runner.output().await.contains("Password:") {
+        runner.wait_for_render().await;
+      }
+
+      runner.send_key(KeyCode::Char('x')).await;
+      runner.wait_until_buffer_contains("Username:").await;
+
+      // What was said before blanking is not shown to whoever comes next.
+      let output = runner.output().await;
+
+      assert!(!output.contains("apognu"));
+      assert!(!output.contains("Password:"));
+    }
+  });
+
+  runner.join_until_end(events).await;
+}
+
+#[tokio::test]
+async fn reflow_on_resize() {
+  let opts = SessionOptions {
+    username: "apognu".to_string(),
+    password: "password".to_string(),
+    mfa:      false,
+  };
+
+  let mut runner = IntegrationRunner::new_with_size(
+    opts,
+    Some(|greeter| {
+      greeter.greeting = Some("Lorem ipsum dolor sit amet".to_string());
+    }),
+    (80, 24),
+  )
+  .await;
+
+  let events = tokio::task::spawn({
+    let mut runner = runner.clone();
+
+    async move {
+      runner
+        .wait_until_buffer_contains("Lorem ipsum dolor sit amet")
+        .await;
+
+      runner.resize(20, 12).await;
+      runner
+        .wait_until_buffer_contains("│    Lorem ipsum   │")
+        .await;
+
+      let output = runner.output().await;
+
+      assert!(output.contains("│  dolor sit amet  │"));
+      assert!(output.contains("│ Username:        │"));
+      assert!(output.lines().all(|line| line.chars().count() == 20));
+    }
+  });
+
+  runner.join_until_end(events).await;
+}
@@ -81,14 +81,12 @@ async fn change_command() {
     async move {
       runner.wait_until_buffer_contains("Username:").await;
       runner.send_key(KeyCode::F(3)).await;
-      runner.wait_for_render().await;
-
-      assert!(runner.output().await.contains("CMD uname"));
+      runner.wait_until_buffer_contains("CMD uname").await;
 
       runner.send_key(KeyCode::F(2)).await;
-      runner.wait_for_render().await;
-
-      assert!(runner.output().await.contains("Change session command"));
+      runner
+        .wait_until_buffer_contains("Change session command")
+        .await;
       assert!(runner.output().await.contains("New command: uname"));
 
       runner
@@ -96,9 +94,7 @@ async fn change_command() {
         .await;
       runner.send_text("mynewcommand").await;
       runner.send_key(KeyCode::Enter).await;
-      runner.wait_for_render().await;
-
-      assert!(runner.output().await.contains("CMD mynewcommand"));
+      runner.wait_until_buffer_contains("CMD mynewcommand").await;
     }
   });
 
@@ -140,27 +136,23 @@ async fn session_menu() {
     async move {
       runner.wait_until_buffer_contains("Username:").await;
       runner.send_key(KeyCode::F(3)).await;
-      runner.wait_for_render().await;
-
-      assert!(runner.output().await.contains("List of sessions"));
+      runner.wait_until_buffer_contains("List of sessions").await;
       assert!(runner.output().await.contains("My Session"));
       assert!(runner.output().await.contains("Second Session"));
 
       runner.send_key(KeyCode::Down).await;
       runner.send_key(KeyCode::Down).await;
       runner.send_key(KeyCode::Enter).await;
-      runner.wait_for_render().await;
-
-      assert!(runner.output().await.contains("SESS Second Session"));
+      runner
+        .wait_until_buffer_contains("SESS Second Session")
+        .await;
 
       runner.send_key(KeyCode::F(3)).await;
       runner.wait_for_render().await;
       runner.send_key(KeyCode::Up).await;
       runner.send_key(KeyCode::Up).await;
       runner.send_key(KeyCode::Enter).await;
-      runner.wait_for_render().await;
-
-      assert!(runner.output().await.contains("SESS My Session"));
+      runner.wait_until_buffer_contains("SESS My Session").await;
     }
   });
 
@@ -204,9 +196,7 @@ async fn power_menu() {
     async move {
       runner.wait_until_buffer_contains("Username:").await;
       runner.send_key(KeyCode::F(12)).await;
-      runner.wait_for_render().await;
-
-      assert!(runner.output().await.contains("What to do?"));
+      runner.wait_until_buffer_contains("What to do?").await;
       assert!(runner.output().await.contains("Turn it off"));
       assert!(runner.output().await.contains("And back on again"));
     }
@@ -252,17 +242,13 @@ async fn users_menu() {
       runner.wait_until_buffer_contains("select a user").await;
 
       runner.send_key(KeyCode::Enter).await;
-      runner.wait_for_render().await;
-
-      assert!(runner.output().await.contains("Antoine POPINEAU"));
+      runner.wait_until_buffer_contains("Antoine POPINEAU").await;
       assert!(runner.output().await.contains("Bob JOE"));
 
       runner.send_key(KeyCode::Down).await;
       runner.send_key(KeyCode::Enter).await;
-      runner.wait_for_render().await;
-
+      runner.wait_until_buffer_contains("Password:").await;
       assert!(runner.output().await.contains("Username: Bob JOE"));
-      assert!(runner.output().await.contains("Password:"));
 
       runner.send_key(KeyCode::Esc).await;
       runner.wait_for_render().await;
@@ -270,19 +256,17 @@ async fn users_menu() {
       runner.wait_until_buffer_contains("select a user").await;
 
       runner.send_text("otheruser").await;
-      runner.wait_for_render().await;
-
+      runner.wait_until_buffer_contains("Password:").await;
       assert!(runner.output().await.contains("Username: otheruser"));
-      assert!(runner.output().await.contains("Password:"));
 
       runner.send_key(KeyCode::Esc).await;
+      runner.wait_until_buffer_contains("select a user").await;
+
       runner.send_key(KeyCode::Enter).await;
       runner.send_key(KeyCode::Up).await;
       runner.send_key(KeyCode::Enter).await;
-      runner.wait_for_render().await;
-
+      runner.wait_until_buffer_contains("Password:").await;
       assert!(runner.output().await.contains("Username: Antoine POPINEAU"));
-      assert!(runner.output().await.contains("Password:"));
 
       runner.send_text("password").await;
     }
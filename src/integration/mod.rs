@@ -4,5 +4,6 @@ mod auth;
 mod display;
 mod exit;
 mod menus;
+mod mouse;
 mod movement;
 mod remember;
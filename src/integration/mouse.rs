@@ -0,0 +1,91 @@
+use crossterm::event::{KeyCode, MouseEventKind};
+use libgreetd_stub::SessionOptions;
+
+use super::common::IntegrationRunner;
+use crate::ui::{common::menu::Menu, sessions::Session};
+
+#[tokio::test]
+async fn session_menu_with_mouse() {
+  let opts = SessionOptions {
+    username: "apognu".to_string(),
+    password: "password".to_string(),
+    mfa:      false,
+  };
+
+  let mut runner = IntegrationRunner::new(
+    opts,
+    Some(|greeter| {
+      greeter.sessions = Menu::<Session> {
+        title:    "List of sessions".to_string(),
+        options:  vec![
+          Session {
+            name: "My Session".to_string(),
+            ..Default::default()
+          },
+          Session {
+            name: "Second Session".to_string(),
+            ..Default::default()
+          },
+        ],
+        selected: 0,
+      };
+    }),
+  )
+  .await;
+
+  let events = tokio::task::spawn({
+    let mut runner = runner.clone();
+
+    async move {
+      runner.wait_until_buffer_contains("Username:").await;
+      runner.click("Choose session").await;
+      runner.wait_until_buffer_contains("List of sessions").await;
+
+      runner.click("Second Session").await;
+      runner
+        .wait_until_buffer_contains("SESS Second Session")
+        .await;
+
+      runner.click("F3").await;
+      runner.wait_until_buffer_contains("List of sessions").await;
+      runner.send_mouse(MouseEventKind::ScrollUp, 0, 0).await;
+      runner.send_key(KeyCode::Enter).await;
+      runner.wait_until_buffer_contains("SESS My Session").await;
+    }
+  });
+
+  runner.join_until_end(events).await;
+}
+
+#[tokio::test]
+async fn fields_with_mouse() {
+  let opts = SessionOptions {
+    username: "apognu".to_string(),
+    password: "password".to_string(),
+    mfa:      false,
+  };
+
+  let mut runner = IntegrationRunner::new(opts, None).await;
+
+  let events = tokio::task::spawn({
+    let mut runner = runner.clone();
+
+    async move {
+      runner.wait_until_buffer_contains("Username:").await;
+      runner.send_text("apognu").await;
+      runner.wait_until_buffer_contains("Password:").await;
+
+      // Clicking the username while answering the password starts over.
+      runner.click("apognu").await;
+
+      while runner.output().await.contains("Password:") {
+        runner.wait_for_render().await;
+      }
+
+      assert!(runner.output().await.contains("Username:"));
+      assert!(!runner.output().await.contains("apognu"));
+    }
+  });
+
+  runner.join_until_end(events).await;
+}
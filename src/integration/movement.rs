@@ -21,9 +21,7 @@ async fn keyboard_movement() {
       for char in "apognu".chars() {
         runner.send_key(KeyCode::Char(char)).await;
       }
-      runner.wait_for_render().await;
-
-      assert!(runner.output().await.contains("Username: apognu"));
+      runner.wait_until_buffer_contains("Username: apognu").await;
 
       runner.send_key(KeyCode::Left).await;
       runner.send_key(KeyCode::Char('l')).await;
@@ -37,17 +35,15 @@ async fn keyboard_movement() {
         .send_modified_key(KeyCode::Char('e'), KeyModifiers::CONTROL)
         .await;
       runner.send_key(KeyCode::Char('e')).await;
-      runner.wait_for_render().await;
-
-      assert!(runner.output().await.contains("Username: aapognlure"));
+      runner
+        .wait_until_buffer_contains("Username: aapognlure")
+        .await;
 
       runner.send_key(KeyCode::Left).await;
       runner
         .send_modified_key(KeyCode::Char('u'), KeyModifiers::CONTROL)
         .await;
-      runner.wait_for_render().await;
-
-      assert!(runner.output().await.contains("Username:      "));
+      runner.wait_until_buffer_contains("Username:      ").await;
     }
   });
 
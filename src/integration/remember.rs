@@ -31,9 +31,7 @@ async fn remember_username() {
 
       runner.wait_until_buffer_contains("Password:").await;
       runner.send_key(KeyCode::Esc).await;
-      runner.wait_for_render().await;
-
-      assert!(runner.output().await.contains("Username:       "));
+      runner.wait_until_buffer_contains("Username:       ").await;
       assert!(!runner.output().await.contains("Password:"));
     }
   });
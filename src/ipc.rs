@@ -22,6 +22,7 @@ use crate::{
     delete_last_user_command,
     delete_last_user_session,
     write_last_user_command,
+    write_last_user_layout,
     write_last_user_session,
     write_last_username,
   },
@@ -83,6 +84,14 @@ impl Ipc {
       self
         .parse_response(&mut *greeter.write().await, response)
         .await?;
+
+      // The screen is only drawn when something changes, which the response
+      // just did.
+      let events = greeter.read().await.events.clone();
+
+      if let Some(events) = events {
+        let _ = events.send(Event::Render).await;
+      }
     }
 
     Ok(())
@@ -189,6 +198,12 @@ impl Ipc {
                 _ => {},
               }
             }
+
+            if let Some(ref layout) = greeter.layout {
+              tracing::info!("caching last user layout: {}", layout.keymap);
+
+              write_last_user_layout(&greeter.username.value, &layout.keymap);
+            }
           }
 
           if let Some(ref sender) = greeter.events {
@@ -308,6 +323,7 @@ fn wrap_session_command<'a>(
   session: Option<&Session>,
   default: &'a DefaultCommand<'a>,
 ) -> (Cow<'a, str>, Vec<String>) {
+  let mut command = Cow::Borrowed(default.command());
   let mut env: Vec<String> = vec![];
 
   match session {
@@ -358,6 +374,11 @@ fn wrap_session_command<'a>(
     },
   }
 
+  // Have compositors use the layout the password was typed with.
+  if let Some(ref layout) = greeter.layout {
+    env.append(&mut layout.env());
+  }
+
   if greeter.silent {
     (Cow::Owned(format!("{command} >/dev/null 2>&1")), env)
   } else {
@@ -373,7 +394,10 @@ mod test {
   use crate::{
     Greeter,
     ipc::{DefaultCommand, desktop_names_to_xdg},
-    ui::sessions::{Session, SessionType},
+    ui::{
+      keyboard_layouts::KeyboardLayout,
+      sessions::{Session, SessionType},
+    },
   };
 
   #[test]
@@ -445,6 +469,33 @@ mod test {
     ]);
   }
 
+  #[test]
+  fn keyboard_layout() {
+    let mut greeter = Greeter::default();
+    greeter.layout = Some(KeyboardLayout {
+      keymap:  "de-latin1-nodeadkeys".into(),
+      layout:  "de".into(),
+      variant: Some("nodeadkeys".into()),
+    });
+
+    let session = Session {
+      name: "Session1".into(),
+      session_type: SessionType::Wayland,
+      command: "Session1Cmd".into(),
+      path: Some(PathBuf::from("/Session1Path")),
+      ..Default::default()
+    };
+
+    let default = DefaultCommand(&session.command, None);
+    let (_, env) = wrap_session_command(&greeter, Some(&session), &default);
+
+    assert_eq!(env, vec![
+      "XDG_SESSION_TYPE=wayland",
+      "XKB_DEFAULT_LAYOUT=de",
+      "XKB_DEFAULT_VARIANT=nodeadkeys"
+    ]);
+  }
+
   #[test]
   fn xdg_current_desktop() {
     assert_eq!(
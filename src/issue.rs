@@ -0,0 +1,420 @@
+use std::{
+  ffi::OsStr,
+  fs,
+  io,
+  path::{Path, PathBuf},
+};
+
+use chrono::Local;
+use nix::sys::{
+  termios,
+  utsname::{UtsName, uname},
+};
+use utmp_rs::{UtmpEntry, UtmpParser};
+
+use crate::{
+  info::UTMP_PATH,
+  network::{NetworkStatus, get_address},
+};
+
+// Issue files looked up in turn, along with the directories holding more of
+// them, as agetty does. Only the first existing file is used.
+const ISSUE_LOCATIONS: [(&str, &str); 3] = [
+  ("etc/issue", "etc/issue.d"),
+  ("run/issue", "run/issue.d"),
+  ("usr/lib/issue", "usr/lib/issue.d"),
+];
+
+const OS_RELEASE_PATHS: [&str; 2] = ["/etc/os-release", "/usr/lib/os-release"];
+
+// Escapes which accept an argument between braces.
+const ARGUMENT_ESCAPES: [char; 5] = ['4', '6', 'N', 'S', 'e'];
+
+// Names accepted by the `\e{name}` escape, as known to agetty.
+const COLORS: [(&str, &str); 22] = [
+  ("black", "\x1b[30m"),
+  ("blink", "\x1b[5m"),
+  ("blue", "\x1b[34m"),
+  ("bold", "\x1b[1m"),
+  ("brown", "\x1b[33m"),
+  ("cyan", "\x1b[36m"),
+  ("darkgray", "\x1b[1;30m"),
+  ("gray", "\x1b[37m"),
+  ("green", "\x1b[32m"),
+  ("halfbright", "\x1b[2m"),
+  ("lightblue", "\x1b[1;34m"),
+  ("lightcyan", "\x1b[1;36m"),
+  ("lightgray", "\x1b[37m"),
+  ("lightgreen", "\x1b[1;32m"),
+  ("lightmagenta", "\x1b[1;35m"),
+  ("lightred", "\x1b[1;31m"),
+  ("magenta", "\x1b[35m"),
+  ("red", "\x1b[31m"),
+  ("reset", "\x1b[0m"),
+  ("reverse", "\x1b[7m"),
+  ("yellow", "\x1b[1;33m"),
+  ("white", "\x1b[1;37m"),
+];
+
+// Reads the issue of the host and replaces its escapes.
+pub fn get_issue() -> Option<String> {
+  let issue = read_issue(Path::new("/"))?;
+
+  Some(expand(&issue, system_value))
+}
+
+// Reads the first issue file found under the provided root, followed by the
+// `.issue` files of the matching directory, in name order.
+fn read_issue(root: &Path) -> Option<String> {
+  ISSUE_LOCATIONS.iter().find_map(|(file, dir)| {
+    let mut issue = fs::read_to_string(root.join(file)).ok()?;
+
+    for path in issue_dir_files(&root.join(dir)).unwrap_or_default() {
+      match fs::read_to_string(&path) {
+        Ok(contents) => issue.push_str(&contents),
+        Err(err) => {
+          tracing::warn!("could not read issue file {}: {err}", path.display())
+        },
+      }
+    }
+
+    Some(issue)
+  })
+}
+
+fn issue_dir_files(dir: &Path) -> io::Result<Vec<PathBuf>> {
+  let mut files: Vec<PathBuf> = fs::read_dir(dir)?
+    .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+    .filter(|path| {
+      path.extension().is_some_and(|ext| ext == "issue") && path.is_file()
+    })
+    .collect();
+
+  files.sort();
+
+  Ok(files)
+}
+
+// Replaces the escapes of the issue with the values returned by the lookup
+// function, given the escape character and its argument between braces if
+// any. Escapes unknown to the lookup function are kept as they are.
+fn expand<F>(issue: &str, mut lookup: F) -> String
+where
+  F: FnMut(char, Option<&str>) -> Option<String>,
+{
+  let mut output = String::with_capacity(issue.len());
+  let mut rest = issue;
+
+  while let Some(index) = rest.find('\\') {
+    output.push_str(&rest[..index]);
+    rest = &rest[index + 1..];
+
+    // Escape characters written out in octal or hexadecimal.
+    if let Some(after) = rest.strip_prefix("033").or(rest.strip_prefix("x1b")) {
+      output.push('\x1b');
+      rest = after;
+      continue;
+    }
+
+    let mut chars = rest.chars();
+
+    let Some(escape) = chars.next() else {
+      output.push('\\');
+      break;
+    };
+
+    rest = chars.as_str();
+
+    let mut argument = None;
+
+    if ARGUMENT_ESCAPES.contains(&escape)
+      && let Some(after) = rest.strip_prefix('{')
+      && let Some(end) = after.find('}')
+    {
+      argument = Some(&after[..end]);
+      rest = &after[end + 1..];
+    }
+
+    let value = match (escape, argument) {
+      ('\\', _) => Some("\\".to_string()),
+      ('e', None) => Some("\x1b".to_string()),
+      ('e', Some(name)) => {
+        COLORS
+          .iter()
+          .find(|(color, _)| *color == name)
+          .map(|(_, sequence)| sequence.to_string())
+      },
+      _ => lookup(escape, argument),
+    };
+
+    match (value, argument) {
+      (Some(value), _) => output.push_str(&value),
+      (None, Some(argument)) => {
+        output.push_str(&format!("\\{escape}{{{argument}}}"))
+      },
+      (None, None) => {
+        output.push('\\');
+        output.push(escape);
+      },
+    }
+  }
+
+  output.push_str(rest);
+  output
+}
+
+// Values of the escapes supported by agetty, read from the system.
+fn system_value(escape: char, argument: Option<&str>) -> Option<String> {
+  let uts = uname().ok();
+  let uts_field = |field: fn(&UtsName) -> &OsStr| {
+    uts
+      .as_ref()
+      .map(|uts| field(uts).to_string_lossy().into_owned())
+      .unwrap_or_default()
+  };
+
+  let value = match escape {
+    's' => uts_field(|uts| uts.sysname()),
+    'r' => uts_field(|uts| uts.release()),
+    'v' => uts_field(|uts| uts.version()),
+    'n' => uts_field(|uts| uts.nodename()),
+    'm' => uts_field(|uts| uts.machine()),
+    'o' => uts_field(|uts| uts.domainname()),
+    'O' => dns_domain_name(&uts_field(|uts| uts.nodename())),
+    'S' => {
+      let variable = argument.unwrap_or("PRETTY_NAME");
+
+      match get_os_release(variable) {
+        Some(value) => value,
+        None if argument.is_none() => uts_field(|uts| uts.sysname()),
+        None => String::new(),
+      }
+    },
+    'd' => Local::now().format("%a %b %_d %Y").to_string(),
+    't' => Local::now().format("%H:%M:%S").to_string(),
+    'l' => get_tty_name(),
+    'b' => get_baud_rate(),
+    'u' => get_user_count().to_string(),
+    'U' => {
+      match get_user_count() {
+        n if n < 2 => format!("{n} user"),
+        n => format!("{n} users"),
+      }
+    },
+    '4' | '6' => {
+      get_address(argument, escape == '6')
+        .map(|address| address.to_string())
+        .unwrap_or_default()
+    },
+    // Not an agetty escape: state of the network, or of one interface.
+    'N' => {
+      let network = NetworkStatus::read();
+
+      match argument {
+        None => network.summary(),
+        Some(name) => {
+          match network.interface(name) {
+            Some(interface) if interface.up => interface.summary(),
+            _ => format!("{name} {}", fl!("network_down")),
+          }
+        },
+      }
+    },
+    _ => return None,
+  };
+
+  Some(value)
+}
+
+// Reads a variable of the os-release file.
+fn get_os_release(variable: &str) -> Option<String> {
+  OS_RELEASE_PATHS
+    .iter()
+    .find_map(|path| fs::read_to_string(path).ok())
+    .and_then(|contents| parse_os_release(&contents, variable))
+}
+
+fn parse_os_release(contents: &str, variable: &str) -> Option<String> {
+  contents.lines().find_map(|line| {
+    let (key, value) = line.trim().split_once('=')?;
+
+    if key != variable {
+      return None;
+    }
+
+    let value = value
+      .strip_prefix('"')
+      .and_then(|value| value.strip_suffix('"'))
+      .or_else(|| {
+        value
+          .strip_prefix('\'')
+          .and_then(|value| value.strip_suffix('\''))
+      })
+      .unwrap_or(value);
+
+    Some(value.to_string())
+  })
+}
+
+// Domain part of the fully qualified name of the host, as found in its name or
+// in the hosts file.
+fn dns_domain_name(hostname: &str) -> String {
+  if let Some((_, domain)) = hostname.split_once('.') {
+    return domain.to_string();
+  }
+
+  let prefix = format!("{hostname}.");
+
+  fs::read_to_string("/etc/hosts")
+    .ok()
+    .and_then(|hosts| {
+      hosts
+        .lines()
+        .filter(|line| !line.trim_start().starts_with('#'))
+        .flat_map(|line| line.split_whitespace().skip(1))
+        .find_map(|name| name.strip_prefix(&prefix).map(str::to_string))
+    })
+    .unwrap_or_default()
+}
+
+// Name of the terminal we are running on, such as `tty1`.
+fn get_tty_name() -> String {
+  fs::read_link("/proc/self/fd/0")
+    .ok()
+    .and_then(|path| {
+      path
+        .to_str()
+        .and_then(|path| path.strip_prefix("/dev/"))
+        .map(str::to_string)
+    })
+    .unwrap_or_else(|| {
+      format!(
+        "tty{}",
+        std::env::var("XDG_VTNR").unwrap_or_else(|_| "0".to_string())
+      )
+    })
+}
+
+// Output speed of the terminal, in bauds.
+fn get_baud_rate() -> String {
+  termios::tcgetattr(std::io::stdin())
+    .map(|attributes| {
+      let speed = termios::cfgetospeed(&attributes);
+
+      format!("{speed:?}").trim_start_matches('B').to_string()
+    })
+    .unwrap_or_default()
+}
+
+fn get_user_count() -> usize {
+  UtmpParser::from_path(UTMP_PATH)
+    .map(|utmp| {
+      utmp
+        .into_iter()
+        .filter(|entry| {
+          matches!(
+            entry,
+            Ok(UtmpEntry::UserProcess { .. } | UtmpEntry::LoginProcess { .. })
+          )
+        })
+        .count()
+    })
+    .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+  use std::fs;
+
+  use tempfile::tempdir;
+
+  use super::{expand, parse_os_release, read_issue};
+
+  fn lookup(escape: char, argument: Option<&str>) -> Option<String> {
+    match (escape, argument) {
+      ('n', None) => Some("host".to_string()),
+      ('l', None) => Some("tty1".to_string()),
+      ('S', None) => Some("Arch Linux".to_string()),
+      ('S', Some(variable)) => Some(format!("<{variable}>")),
+      ('4', None) => Some("10.0.0.2".to_string()),
+      ('4', Some(interface)) => Some(format!("<{interface}>")),
+      ('N', None) => Some("enp3s0 10.0.0.2".to_string()),
+      ('N', Some(interface)) => Some(format!("{interface} down")),
+      _ => None,
+    }
+  }
+
+  #[test]
+  fn expand_escapes() {
+    assert_eq!(
+      expand("\\S \\n \\l", lookup),
+      "Arch Linux host tty1".to_string()
+    );
+    assert_eq!(
+      expand("\\S{VERSION_ID} \\4 \\4{eth0}", lookup),
+      "<VERSION_ID> 10.0.0.2 <eth0>"
+    );
+    assert_eq!(
+      expand("\\N; \\N{wlan0}", lookup),
+      "enp3s0 10.0.0.2; wlan0 down"
+    );
+    assert_eq!(
+      expand("\\e{red}alert\\e{reset}", lookup),
+      "\x1b[31malert\x1b[0m"
+    );
+    assert_eq!(
+      expand("\\e[1m\\033[0m\\x1b[0m", lookup),
+      "\x1b[1m\x1b[0m\x1b[0m"
+    );
+  }
+
+  #[test]
+  fn escaped_backslashes() {
+    assert_eq!(expand("C:\\\\new", lookup), "C:\\new");
+    assert_eq!(expand("\\\\\\n", lookup), "\\host");
+    assert_eq!(expand("trailing \\", lookup), "trailing \\");
+  }
+
+  #[test]
+  fn unknown_escapes_are_kept() {
+    assert_eq!(expand("\\z \\e{nope}x", lookup), "\\z \\e{nope}x");
+    assert_eq!(expand("\\S{", lookup), "Arch Linux{");
+  }
+
+  #[test]
+  fn issue_directories() {
+    let root = tempdir().unwrap();
+
+    assert_eq!(read_issue(root.path()), None);
+
+    fs::create_dir_all(root.path().join("run/issue.d")).unwrap();
+    fs::write(root.path().join("run/issue"), "run\n").unwrap();
+    fs::write(root.path().join("run/issue.d/10-net.issue"), "net\n").unwrap();
+
+    assert_eq!(read_issue(root.path()).as_deref(), Some("run\nnet\n"));
+
+    fs::create_dir_all(root.path().join("etc/issue.d")).unwrap();
+    fs::write(root.path().join("etc/issue"), "etc\n").unwrap();
+    fs::write(root.path().join("etc/issue.d/20-b.issue"), "b\n").unwrap();
+    fs::write(root.path().join("etc/issue.d/10-a.issue"), "a\n").unwrap();
+    fs::write(root.path().join("etc/issue.d/30-c.txt"), "c\n").unwrap();
+
+    assert_eq!(read_issue(root.path()).as_deref(), Some("etc\na\nb\n"));
+  }
+
+  #[test]
+  fn os_release() {
+    let contents = "NAME=\"Arch Linux\"\nID=arch\nVERSION_ID='2024.01'\n";
+
+    assert_eq!(
+      parse_os_release(contents, "NAME").as_deref(),
+      Some("Arch Linux")
+    );
+    assert_eq!(parse_os_release(contents, "ID").as_deref(), Some("arch"));
+    assert_eq!(
+      parse_os_release(contents, "VERSION_ID").as_deref(),
+      Some("2024.01")
+    );
+    assert_eq!(parse_os_release(contents, "PRETTY_NAME"), None);
+  }
+}
@@ -1,8 +1,9 @@
-use std::{error::Error, sync::Arc};
+use std::{error::Error, iter, sync::Arc, time::Instant};
 
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use greetd_ipc::Request;
 use tokio::sync::RwLock;
+use unicode_segmentation::UnicodeSegmentation;
 
 use crate::{
   Greeter,
@@ -12,16 +13,21 @@ use crate::{
     delete_last_session,
     get_last_user_command,
     get_last_user_session,
+    get_occupied_vts,
+    get_user_vts,
+    lookup_user,
     write_last_command,
     write_last_session_path,
   },
   ipc::Ipc,
   power::power,
   ui::{
-    common::masked::MaskedString,
+    common::{masked::MaskedString, menu::Menu},
     sessions::{Session, SessionSource},
-    users::User,
+    users::{User, UserListing},
+    vts::{Vt, VtTarget},
   },
+  vt,
 };
 
 // Act on keyboard events.
@@ -37,6 +43,8 @@ pub async fn handle(
 ) -> Result<(), Box<dyn Error>> {
   let mut greeter = greeter.write().await;
 
+  greeter.last_input = Instant::now();
+
   if greeter.working {
     return Ok(());
   }
@@ -49,7 +57,10 @@ pub async fn handle(
       ..
     } => {
       match greeter.mode {
-        Mode::Username => greeter.username = MaskedString::default(),
+        Mode::Username => {
+          greeter.username = MaskedString::default();
+          greeter.completion = 0;
+        },
         Mode::Password => greeter.buffer = String::new(),
         Mode::Command => greeter.buffer = String::new(),
         _ => {},
@@ -83,7 +94,11 @@ pub async fn handle(
           greeter.cursor_offset = 0;
         },
 
-        Mode::Users | Mode::Sessions | Mode::Power => {
+        Mode::Users
+        | Mode::Sessions
+        | Mode::Power
+        | Mode::Vts
+        | Mode::Layouts => {
           greeter.mode = greeter.previous_mode;
         },
 
@@ -99,10 +114,20 @@ pub async fn handle(
       code: KeyCode::Left,
       ..
     } => greeter.cursor_offset -= 1,
+    // Right accepts the offered username completion, when at the end of the
+    // entry.
     KeyEvent {
       code: KeyCode::Right,
       ..
-    } => greeter.cursor_offset += 1,
+    } => {
+      let completed = greeter.mode == Mode::Username
+        && greeter.cursor_offset == 0
+        && accept_completion(&mut greeter);
+
+      if !completed {
+        greeter.cursor_offset += 1;
+      }
+    },
 
     // F2 will display the command entry prompt. If we are already in one of the
     // popup screens, we set the previous screen as being the current previous
@@ -112,9 +137,12 @@ pub async fn handle(
       ..
     } if i == greeter.kb_command => {
       greeter.previous_mode = match greeter.mode {
-        Mode::Users | Mode::Command | Mode::Sessions | Mode::Power => {
-          greeter.previous_mode
-        },
+        Mode::Users
+        | Mode::Command
+        | Mode::Sessions
+        | Mode::Power
+        | Mode::Vts
+        | Mode::Layouts => greeter.previous_mode,
         _ => greeter.mode,
       };
 
@@ -137,9 +165,12 @@ pub async fn handle(
       ..
     } if i == greeter.kb_sessions => {
       greeter.previous_mode = match greeter.mode {
-        Mode::Users | Mode::Command | Mode::Sessions | Mode::Power => {
-          greeter.previous_mode
-        },
+        Mode::Users
+        | Mode::Command
+        | Mode::Sessions
+        | Mode::Power
+        | Mode::Vts
+        | Mode::Layouts => greeter.previous_mode,
         _ => greeter.mode,
       };
 
@@ -154,15 +185,73 @@ pub async fn handle(
       ..
     } if i == greeter.kb_power => {
       greeter.previous_mode = match greeter.mode {
-        Mode::Users | Mode::Command | Mode::Sessions | Mode::Power => {
-          greeter.previous_mode
-        },
+        Mode::Users
+        | Mode::Command
+        | Mode::Sessions
+        | Mode::Power
+        | Mode::Vts
+        | Mode::Layouts => greeter.previous_mode,
         _ => greeter.mode,
       };
 
       greeter.mode = Mode::Power;
     },
 
+    // The running sessions key will display all occupied VTs, if switching to
+    // them was enabled.
+    KeyEvent {
+      code: KeyCode::F(i),
+      ..
+    } if greeter.vt_switch && i == greeter.kb_vts => {
+      greeter.previous_mode = match greeter.mode {
+        Mode::Users
+        | Mode::Command
+        | Mode::Sessions
+        | Mode::Power
+        | Mode::Vts
+        | Mode::Layouts => greeter.previous_mode,
+        _ => greeter.mode,
+      };
+
+      greeter.vts = Menu {
+        title:    fl!("title_vts"),
+        options:  get_occupied_vts()
+          .into_iter()
+          .map(|(vt, username)| Vt::switch(vt, &username))
+          .collect(),
+        selected: 0,
+      };
+
+      greeter.mode = Mode::Vts;
+    },
+
+    // The keyboard layout key will display the configured layouts, with the
+    // one in use selected.
+    KeyEvent {
+      code: KeyCode::F(i),
+      ..
+    } if !greeter.layouts.options.is_empty() && i == greeter.kb_layouts => {
+      greeter.previous_mode = match greeter.mode {
+        Mode::Users
+        | Mode::Command
+        | Mode::Sessions
+        | Mode::Power
+        | Mode::Vts
+        | Mode::Layouts => greeter.previous_mode,
+        _ => greeter.mode,
+      };
+
+      greeter.layouts.selected = greeter
+        .layout
+        .as_ref()
+        .and_then(|layout| {
+          greeter.layouts.options.iter().position(|l| l == layout)
+        })
+        .unwrap_or(0);
+
+      greeter.mode = Mode::Layouts;
+    },
+
     // Handle moving up in menus.
     KeyEvent {
       code: KeyCode::Up, ..
@@ -184,6 +273,18 @@ pub async fn handle(
       {
         greeter.powers.selected -= 1;
       }
+
+      if let Mode::Vts = greeter.mode
+        && greeter.vts.selected > 0
+      {
+        greeter.vts.selected -= 1;
+      }
+
+      if let Mode::Layouts = greeter.mode
+        && greeter.layouts.selected > 0
+      {
+        greeter.layouts.selected -= 1;
+      }
     },
 
     // Handle moving down in menus.
@@ -192,7 +293,7 @@ pub async fn handle(
       ..
     } => {
       if let Mode::Users = greeter.mode
-        && greeter.users.selected < greeter.users.options.len() - 1
+        && greeter.users.selected + 1 < greeter.users.options.len()
       {
         greeter.users.selected += 1;
       }
@@ -208,6 +309,18 @@ pub async fn handle(
       {
         greeter.powers.selected += 1;
       }
+
+      if let Mode::Vts = greeter.mode
+        && greeter.vts.selected + 1 < greeter.vts.options.len()
+      {
+        greeter.vts.selected += 1;
+      }
+
+      if let Mode::Layouts = greeter.mode
+        && greeter.layouts.selected + 1 < greeter.layouts.options.len()
+      {
+        greeter.layouts.selected += 1;
+      }
     },
 
     // ^A should go to the start of the current prompt
@@ -223,7 +336,7 @@ pub async fn handle(
         }
       };
 
-      greeter.cursor_offset = -(value.chars().count() as i16);
+      greeter.cursor_offset = -(value.graphemes(true).count() as i16);
     },
 
     // ^A should go to the end of the current prompt
@@ -233,15 +346,20 @@ pub async fn handle(
       ..
     } => greeter.cursor_offset = 0,
 
-    // Tab should validate the username entry (same as Enter).
+    // Tab should complete the username entry, cycle through completions if
+    // several users match, or validate it otherwise (same as Enter), as when
+    // the entry is a login of its own.
     KeyEvent {
       code: KeyCode::Tab, ..
-    } => {
-      match greeter.mode {
-        Mode::Username if !greeter.username.value.is_empty() => {
-          validate_username(&mut greeter, &ipc).await
-        },
-        _ => {},
+    } if greeter.mode == Mode::Username => {
+      let exact = matches!(greeter.username_completion(), Some((_, "", false)));
+
+      if !exact && greeter.username_completions().len() > 1 {
+        greeter.completion += 1;
+      } else if !accept_completion(&mut greeter)
+        && !greeter.username.value.is_empty()
+      {
+        validate_username(&mut greeter, &ipc).await
       }
     },
 
@@ -255,11 +373,18 @@ pub async fn handle(
           validate_username(&mut greeter, &ipc).await
         },
 
-        Mode::Username if greeter.user_menu => {
+        Mode::Username
+          if greeter.user_menu
+            && (!greeter.users.options.is_empty()
+              || greeter.user_listing == UserListing::Loading) =>
+        {
           greeter.previous_mode = match greeter.mode {
-            Mode::Users | Mode::Command | Mode::Sessions | Mode::Power => {
-              greeter.previous_mode
-            },
+            Mode::Users
+            | Mode::Command
+            | Mode::Sessions
+            | Mode::Power
+            | Mode::Vts
+            | Mode::Layouts => greeter.previous_mode,
             _ => greeter.mode,
           };
 
@@ -300,13 +425,13 @@ pub async fn handle(
           let username =
             greeter.users.options.get(greeter.users.selected).cloned();
 
+          greeter.mode = greeter.previous_mode;
+
           if let Some(User { username, name }) = username {
             greeter.username = MaskedString::from(username, name);
-          }
-
-          greeter.mode = greeter.previous_mode;
 
-          validate_username(&mut greeter, &ipc).await;
+            validate_username(&mut greeter, &ipc).await;
+          }
         },
 
         Mode::Sessions => {
@@ -342,6 +467,39 @@ pub async fn handle(
           greeter.mode = greeter.previous_mode;
         },
 
+        Mode::Vts => {
+          let target = greeter
+            .vts
+            .options
+            .get(greeter.vts.selected)
+            .map(|vt| vt.target);
+
+          greeter.mode = greeter.previous_mode;
+
+          match target {
+            Some(VtTarget::Switch(vt)) => switch_vt(&mut greeter, vt, false),
+            Some(VtTarget::Resume(vt)) => switch_vt(&mut greeter, vt, true),
+            Some(VtTarget::NewSession) => {
+              create_session(&mut greeter, &ipc).await
+            },
+            None => {},
+          }
+        },
+
+        Mode::Layouts => {
+          let layout = greeter
+            .layouts
+            .options
+            .get(greeter.layouts.selected)
+            .cloned();
+
+          greeter.mode = greeter.previous_mode;
+
+          if let Some(layout) = layout {
+            greeter.select_layout(layout);
+          }
+        },
+
         _ => {},
       }
     },
@@ -384,15 +542,23 @@ async fn insert_key(greeter: &mut Greeter, c: char) {
     _ => return,
   };
 
-  let index = (value.chars().count() as i16 + greeter.cursor_offset) as usize;
-  let left = value.chars().take(index);
-  let right = value.chars().skip(index);
+  let graphemes = value.grapheme_indices(true).collect::<Vec<_>>();
+  let index = graphemes
+    .get(cursor_position(graphemes.len(), greeter.cursor_offset))
+    .map_or(value.len(), |(index, _)| *index);
+
+  let mut value = value.clone();
+  value.insert(index, c);
 
-  let value = left.chain(vec![c].into_iter()).chain(right).collect();
   let mode = greeter.mode;
 
   match mode {
-    Mode::Username => greeter.username.value = value,
+    Mode::Username => {
+      greeter.username.value = value;
+      greeter.completion = 0;
+
+      lookup_username(greeter);
+    },
     Mode::Password => greeter.buffer = value,
     Mode::Command => greeter.buffer = value,
     _ => {},
@@ -410,24 +576,26 @@ async fn delete_key(greeter: &mut Greeter, key: KeyCode) {
     _ => return,
   };
 
-  let index = match key {
-    KeyCode::Backspace => {
-      (value.chars().count() as i16 + greeter.cursor_offset - 1) as usize
-    },
-    KeyCode::Delete => {
-      (value.chars().count() as i16 + greeter.cursor_offset) as usize
-    },
-    _ => 0,
-  };
+  let graphemes = value.grapheme_indices(true).collect::<Vec<_>>();
+  let position = cursor_position(graphemes.len(), greeter.cursor_offset);
 
-  if value.chars().nth(index).is_some() {
-    let left = value.chars().take(index);
-    let right = value.chars().skip(index + 1);
+  let target = match key {
+    KeyCode::Backspace => position.checked_sub(1),
+    KeyCode::Delete => Some(position),
+    _ => None,
+  };
 
-    let value = left.chain(right).collect();
+  if let Some((index, grapheme)) = target.and_then(|t| graphemes.get(t)) {
+    let value =
+      format!("{}{}", &value[..*index], &value[index + grapheme.len()..]);
 
     match greeter.mode {
-      Mode::Username => greeter.username.value = value,
+      Mode::Username => {
+        greeter.username.value = value;
+        greeter.completion = 0;
+
+        lookup_username(greeter);
+      },
       Mode::Password => greeter.buffer = value,
       Mode::Command => greeter.buffer = value,
       _ => return,
@@ -439,8 +607,125 @@ async fn delete_key(greeter: &mut Greeter, key: KeyCode) {
   }
 }
 
-// Creates a `greetd` session for the provided username.
+// Returns the index of the grapheme the cursor is on, the cursor offset
+// counting graphemes backwards from the end of the value.
+fn cursor_position(length: usize, offset: i16) -> usize {
+  (length as i16 + offset).clamp(0, length as i16) as usize
+}
+
+// Replaces the username being typed with the offered completion, if there is
+// one. When a full name was matched, it is displayed in place of the login.
+fn accept_completion(greeter: &mut Greeter) -> bool {
+  let username = match greeter.username_completion() {
+    Some((_, "", false)) | None => return false,
+    Some((user, _, by_name)) => {
+      MaskedString::from(
+        user.username.clone(),
+        if by_name { user.name.clone() } else { None },
+      )
+    },
+  };
+
+  greeter.username = username;
+  greeter.completion = 0;
+  greeter.cursor_offset = 0;
+
+  true
+}
+
+// When users could not all be enumerated, look up the username being typed, so
+// it can be offered in the user menu once it matches an existing user. Only the
+// last username typed is looked up.
+fn lookup_username(greeter: &mut Greeter) {
+  if let Some(lookup) = greeter.users_lookup.take() {
+    lookup.abort();
+  }
+
+  if !greeter.user_menu
+    || greeter.user_listing != UserListing::Lookup
+    || greeter.username.value.is_empty()
+  {
+    return;
+  }
+
+  if let Some(ref events) = greeter.events {
+    greeter.users_lookup = Some(lookup_user(
+      greeter.username.value.clone(),
+      greeter.user_menu_uids,
+      events.clone(),
+    ));
+  }
+}
+
+// Validates the provided username, offering to switch to one of its running
+// sessions if there are any, or creating a new one otherwise.
 async fn validate_username(greeter: &mut Greeter, ipc: &Ipc) {
+  match greeter.username_rules.normalize(&greeter.username.value) {
+    Ok(username) => {
+      if username != greeter.username.value {
+        tracing::info!("username was normalized to {username}");
+
+        greeter.username.value = username;
+      }
+    },
+
+    Err(err) => {
+      tracing::info!("rejecting invalid username: {err:?}");
+
+      greeter.message = Some(err.to_string());
+
+      return;
+    },
+  }
+
+  if greeter.vt_switch {
+    let vts = get_user_vts(&greeter.username.value);
+
+    if !vts.is_empty() {
+      tracing::info!("user already has sessions on VTs {:?}", vts);
+
+      greeter.vts = Menu {
+        title:    fl!("title_vts"),
+        options:  vts
+          .into_iter()
+          .map(|vt| Vt::resume(vt, &greeter.username.value))
+          .chain(iter::once(Vt::new_session()))
+          .collect(),
+        selected: 0,
+      };
+
+      greeter.previous_mode = Mode::Username;
+      greeter.mode = Mode::Vts;
+
+      return;
+    }
+  }
+
+  create_session(greeter, ipc).await;
+}
+
+// Brings the selected VT to the foreground. When this was offered in place of
+// a new session, the typed username is forgotten, so the next person to come
+// back to this greeter starts from scratch.
+fn switch_vt(greeter: &mut Greeter, vt: u32, resume: bool) {
+  match vt::activate(vt) {
+    Ok(()) => {
+      if resume {
+        greeter.username = MaskedString::default();
+        greeter.cursor_offset = 0;
+      }
+    },
+
+    Err(err) => {
+      tracing::error!("could not switch to VT {vt}: {err}");
+
+      greeter.message = Some(format!("{}: {err}", fl!("vt_switch_failed")));
+    },
+  }
+}
+
+// Creates a `greetd` session for the provided username.
+async fn create_session(greeter: &mut Greeter, ipc: &Ipc) {
   greeter.working = true;
   greeter.message = None;
 
@@ -451,6 +736,8 @@ async fn validate_username(greeter: &mut Greeter, ipc: &Ipc) {
     .await;
   greeter.buffer = String::new();
 
+  greeter.restore_user_layout();
+
   if greeter.remember_user_session {
     if let Ok(last_session) = get_last_user_session(&greeter.username.value)
       && let Some(last_session) =
@@ -478,17 +765,24 @@ async fn validate_username(greeter: &mut Greeter, ipc: &Ipc) {
 
 #[cfg(test)]
 mod test {
-  use std::sync::Arc;
+  use std::{iter, sync::Arc, time::Duration};
 
   use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
-  use tokio::sync::RwLock;
+  use tokio::sync::{RwLock, mpsc};
 
   use super::handle;
   use crate::{
     Greeter,
     Mode,
+    event::Event,
     ipc::Ipc,
-    ui::{common::masked::MaskedString, sessions::SessionSource},
+    ui::{
+      common::{masked::MaskedString, menu::Menu},
+      keyboard_layouts::KeyboardLayout,
+      sessions::SessionSource,
+      users::{User, UserListing},
+      vts::Vt,
+    },
   };
 
   #[tokio::test]
@@ -810,6 +1104,416 @@ mod test {
     }
   }
 
+  #[tokio::test]
+  async fn username_normalization() {
+    let greeter = Arc::new(RwLock::new(Greeter::default()));
+
+    {
+      let mut greeter = greeter.write().await;
+      greeter.username = MaskedString::from("apo:gnu".to_string(), None);
+    }
+
+    let result = handle(
+      greeter.clone(),
+      KeyEvent::new(KeyCode::Enter, KeyModifiers::empty()),
+      Ipc::new(),
+    )
+    .await;
+
+    {
+      let mut status = greeter.write().await;
+
+      assert!(result.is_ok());
+      assert_eq!(status.mode, Mode::Username);
+      assert!(!status.working);
+      assert!(status.message.is_some());
+
+      status.username_rules.strip_realm = true;
+      status.username = MaskedString::from(" apognu@corp ".to_string(), None);
+    }
+
+    let result = handle(
+      greeter.clone(),
+      KeyEvent::new(KeyCode::Enter, KeyModifiers::empty()),
+      Ipc::new(),
+    )
+    .await;
+
+    {
+      let status = greeter.read().await;
+
+      assert!(result.is_ok());
+      assert!(status.working);
+      assert!(status.message.is_none());
+      assert_eq!(status.username.value, "apognu");
+    }
+  }
+
+  #[tokio::test]
+  async fn username_completion() {
+    let greeter = Arc::new(RwLock::new(Greeter::default()));
+
+    {
+      let mut greeter = greeter.write().await;
+      greeter.users.options = vec![
+        User {
+          username: "apognu".into(),
+          name:     Some("Antoine Popineau".into()),
+        },
+        User {
+          username: "alice".into(),
+          name:     None,
+        },
+      ];
+    }
+
+    let _ = handle(
+      greeter.clone(),
+      KeyEvent::new(KeyCode::Char('a'), KeyModifiers::empty()),
+      Ipc::new(),
+    )
+    .await;
+
+    {
+      let status = greeter.read().await;
+
+      assert_eq!(status.username_completions().len(), 2);
+      assert_eq!(status.username_completion().unwrap().1, "pognu");
+    }
+
+    let result = handle(
+      greeter.clone(),
+      KeyEvent::new(KeyCode::Tab, KeyModifiers::empty()),
+      Ipc::new(),
+    )
+    .await;
+
+    {
+      let status = greeter.read().await;
+
+      assert!(result.is_ok());
+      assert_eq!(status.username.value, "a");
+      assert_eq!(status.username_completion().unwrap().1, "lice");
+      assert!(!status.working);
+    }
+
+    let _ = handle(
+      greeter.clone(),
+      KeyEvent::new(KeyCode::Tab, KeyModifiers::empty()),
+      Ipc::new(),
+    )
+    .await;
+    let _ = handle(
+      greeter.clone(),
+      KeyEvent::new(KeyCode::Char('n'), KeyModifiers::empty()),
+      Ipc::new(),
+    )
+    .await;
+    let result = handle(
+      greeter.clone(),
+      KeyEvent::new(KeyCode::Right, KeyModifiers::empty()),
+      Ipc::new(),
+    )
+    .await;
+
+    {
+      let status = greeter.read().await;
+
+      assert!(result.is_ok());
+      assert_eq!(status.username.value, "apognu");
+      assert_eq!(status.username.get(), "Antoine Popineau");
+      assert_eq!(status.cursor_offset, 0);
+      assert!(status.username_completion().is_none());
+    }
+  }
+
+  #[tokio::test]
+  async fn username_completion_exact() {
+    let greeter = Arc::new(RwLock::new(Greeter::default()));
+
+    {
+      let mut greeter = greeter.write().await;
+      greeter.users.options = vec![
+        User {
+          username: "alice".into(),
+          name:     None,
+        },
+        User {
+          username: "al".into(),
+          name:     None,
+        },
+      ];
+      greeter.username = MaskedString::from("al".into(), None);
+    }
+
+    {
+      let status = greeter.read().await;
+
+      assert_eq!(status.username_completions().len(), 2);
+      assert_eq!(status.username_completion().unwrap().1, "");
+    }
+
+    let result = handle(
+      greeter.clone(),
+      KeyEvent::new(KeyCode::Tab, KeyModifiers::empty()),
+      Ipc::new(),
+    )
+    .await;
+
+    {
+      let status = greeter.read().await;
+
+      assert!(result.is_ok());
+      assert!(status.working);
+      assert_eq!(status.username.value, "al");
+    }
+  }
+
+  #[tokio::test]
+  async fn lookup_last_username() {
+    let greeter = Arc::new(RwLock::new(Greeter::default()));
+    let (tx, mut rx) = mpsc::channel(10);
+
+    {
+      let mut greeter = greeter.write().await;
+      greeter.user_menu = true;
+      greeter.user_listing = UserListing::Lookup;
+      greeter.user_menu_uids = (0, 0);
+      greeter.events = Some(tx);
+    }
+
+    for key in "roott".chars().chain(iter::once('\x08')) {
+      let key = match key {
+        '\x08' => KeyCode::Backspace,
+        key => KeyCode::Char(key),
+      };
+
+      let _ = handle(
+        greeter.clone(),
+        KeyEvent::new(key, KeyModifiers::empty()),
+        Ipc::new(),
+      )
+      .await;
+    }
+
+    let event = tokio::time::timeout(Duration::from_secs(5), rx.recv()).await;
+
+    match event {
+      Ok(Some(Event::Users(users))) => {
+        assert_eq!(users.len(), 1);
+        assert_eq!(users[0].username, "root");
+      },
+      _ => panic!("the last username typed should be looked up"),
+    }
+
+    // Usernames typed before it were never looked up.
+    let event =
+      tokio::time::timeout(Duration::from_millis(500), rx.recv()).await;
+
+    assert!(event.is_err());
+  }
+
+  #[tokio::test]
+  async fn empty_user_menu() {
+    let greeter = Arc::new(RwLock::new(Greeter::default()));
+
+    {
+      let mut greeter = greeter.write().await;
+      greeter.user_menu = true;
+      greeter.user_listing = UserListing::Lookup;
+    }
+
+    let result = handle(
+      greeter.clone(),
+      KeyEvent::new(KeyCode::Enter, KeyModifiers::empty()),
+      Ipc::new(),
+    )
+    .await;
+
+    {
+      let status = greeter.read().await;
+
+      assert!(result.is_ok());
+      assert_eq!(status.mode, Mode::Username);
+    }
+
+    {
+      let mut greeter = greeter.write().await;
+      greeter.user_listing = UserListing::Loading;
+    }
+
+    for key in [KeyCode::Enter, KeyCode::Down, KeyCode::Enter] {
+      let result = handle(
+        greeter.clone(),
+        KeyEvent::new(key, KeyModifiers::empty()),
+        Ipc::new(),
+      )
+      .await;
+
+      assert!(result.is_ok());
+    }
+
+    {
+      let status = greeter.read().await;
+
+      assert_eq!(status.mode, Mode::Username);
+      assert!(status.username.value.is_empty());
+      assert!(!status.working);
+    }
+  }
+
+  #[tokio::test]
+  async fn vt_menu() {
+    let greeter = Arc::new(RwLock::new(Greeter::default()));
+
+    let result = handle(
+      greeter.clone(),
+      KeyEvent::new(KeyCode::F(4), KeyModifiers::empty()),
+      Ipc::new(),
+    )
+    .await;
+
+    {
+      let status = greeter.read().await;
+
+      assert!(result.is_ok());
+      assert_eq!(status.mode, Mode::Username);
+    }
+
+    {
+      let mut greeter = greeter.write().await;
+      greeter.vt_switch = true;
+    }
+
+    let result = handle(
+      greeter.clone(),
+      KeyEvent::new(KeyCode::F(4), KeyModifiers::empty()),
+      Ipc::new(),
+    )
+    .await;
+
+    {
+      let status = greeter.read().await;
+
+      assert!(result.is_ok());
+      assert_eq!(status.mode, Mode::Vts);
+      assert_eq!(status.previous_mode, Mode::Username);
+    }
+  }
+
+  #[tokio::test]
+  async fn vt_menu_new_session() {
+    let greeter = Arc::new(RwLock::new(Greeter::default()));
+
+    {
+      let mut greeter = greeter.write().await;
+      greeter.vt_switch = true;
+      greeter.username = MaskedString::from("apognu".to_string(), None);
+      greeter.previous_mode = Mode::Username;
+      greeter.mode = Mode::Vts;
+      greeter.vts = Menu {
+        title:    "VTs".into(),
+        options:  vec![Vt::resume(3, "apognu"), Vt::new_session()],
+        selected: 0,
+      };
+    }
+
+    let _ = handle(
+      greeter.clone(),
+      KeyEvent::new(KeyCode::Down, KeyModifiers::empty()),
+      Ipc::new(),
+    )
+    .await;
+    let result = handle(
+      greeter.clone(),
+      KeyEvent::new(KeyCode::Enter, KeyModifiers::empty()),
+      Ipc::new(),
+    )
+    .await;
+
+    {
+      let status = greeter.read().await;
+
+      assert!(result.is_ok());
+      assert_eq!(status.mode, Mode::Username);
+      assert_eq!(status.username.value, "apognu".to_string());
+      assert!(status.working);
+    }
+  }
+
+  #[tokio::test]
+  async fn layout_menu() {
+    let greeter = Arc::new(RwLock::new(Greeter::default()));
+    let layouts = vec![
+      KeyboardLayout {
+        keymap: "us".into(),
+        layout: "us".into(),
+        ..Default::default()
+      },
+      KeyboardLayout {
+        keymap:  "fr-bepo".into(),
+        layout:  "fr".into(),
+        variant: Some("bepo".into()),
+      },
+    ];
+
+    {
+      let mut greeter = greeter.write().await;
+      greeter.mode = Mode::Password;
+      greeter.layouts = Menu {
+        title:    "Layouts".into(),
+        options:  layouts.clone(),
+        selected: 0,
+      };
+      greeter.layout = Some(layouts[1].clone());
+    }
+
+    let _ = handle(
+      greeter.clone(),
+      KeyEvent::new(KeyCode::F(5), KeyModifiers::empty()),
+      Ipc::new(),
+    )
+    .await;
+
+    {
+      let status = greeter.read().await;
+
+      assert_eq!(status.mode, Mode::Layouts);
+      assert_eq!(status.previous_mode, Mode::Password);
+      assert_eq!(status.layouts.selected, 1);
+    }
+
+    let _ = handle(
+      greeter.clone(),
+      KeyEvent::new(KeyCode::Up, KeyModifiers::empty()),
+      Ipc::new(),
+    )
+    .await;
+    let result = handle(
+      greeter.clone(),
+      KeyEvent::new(KeyCode::Enter, KeyModifiers::empty()),
+      Ipc::new(),
+    )
+    .await;
+
+    {
+      let status = greeter.read().await;
+
+      assert!(result.is_ok());
+      assert_eq!(status.mode, Mode::Password);
+      assert_eq!(status.layout, Some(layouts[0].clone()));
+    }
+
+    // A keymap that could not be loaded leaves the layout as it was.
+    {
+      let mut greeter = greeter.write().await;
+      greeter.set_layout(layouts[1].clone(), Err("no such keymap".into()));
+
+      assert_eq!(greeter.layout, Some(layouts[0].clone()));
+      assert!(greeter.message.is_some());
+    }
+  }
+
   #[tokio::test]
   async fn ctrl_a_e() {
     let greeter = Arc::new(RwLock::new(Greeter::default()));
@@ -830,7 +1534,7 @@ mod test {
     {
       let status = greeter.read().await;
 
-      assert!(matches!(result, Ok(_)));
+      assert!(result.is_ok());
       assert_eq!(status.cursor_offset, -9);
     }
 
@@ -844,7 +1548,7 @@ mod test {
     {
       let status = greeter.read().await;
 
-      assert!(matches!(result, Ok(_)));
+      assert!(result.is_ok());
       assert_eq!(status.cursor_offset, 0);
     }
   }
@@ -0,0 +1,484 @@
+use std::{
+  env,
+  error::Error,
+  io::{self, Write},
+  mem,
+  sync::Arc,
+};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use nix::sys::termios::{LocalFlags, SetArg, tcgetattr, tcsetattr};
+use tokio::sync::RwLock;
+use zeroize::Zeroize;
+
+use crate::{
+  Greeter,
+  Mode,
+  config::LineMode,
+  ipc::Ipc,
+  keyboard,
+  ui::{
+    common::{
+      masked::MaskedString,
+      menu::{Menu, MenuItem},
+    },
+    get_plain_greeting,
+  },
+};
+
+// Smallest terminal the screen is drawn on.
+const MIN_WIDTH: u16 = 40;
+const MIN_HEIGHT: u16 = 10;
+
+// Terminals that cannot move the cursor around to draw the screen.
+const DUMB_TERMS: [&str; 2] = ["dumb", "vt100"];
+
+// Whether the conversation should be held line by line, as asked, or otherwise
+// because the terminal cannot draw the screen or is too small for it.
+pub fn wanted(mode: &LineMode) -> bool {
+  match mode {
+    LineMode::Always => true,
+    LineMode::Never => false,
+    LineMode::Auto => {
+      needs_lines(
+        env::var("TERM").ok().as_deref(),
+        crossterm::terminal::size().ok(),
+      )
+    },
+  }
+}
+
+fn needs_lines(term: Option<&str>, size: Option<(u16, u16)>) -> bool {
+  term.is_some_and(|term| DUMB_TERMS.contains(&term))
+    || size
+      .is_none_or(|(width, height)| width < MIN_WIDTH || height < MIN_HEIGHT)
+}
+
+// A conversation held line by line on a terminal left in cooked mode, like
+// getty does: questions are printed once when they are asked, and answered
+// with a line the terminal echoes and lets the user edit.
+pub struct Conversation {
+  // Turns the echo of the terminal on or off.
+  echo:    fn(bool),
+  // Whether the greeting was printed.
+  started: bool,
+  // The question printed last, not printed again until it is answered.
+  asked:   Option<Asked>,
+  // The message printed last.
+  message: Option<String>,
+  // Whether echo is off while a secret is typed.
+  hidden:  bool,
+}
+
+#[derive(Clone, PartialEq)]
+struct Asked {
+  mode:   Mode,
+  prompt: Option<String>,
+  secret: bool,
+}
+
+impl Conversation {
+  pub fn new(echo: fn(bool)) -> Conversation {
+    Conversation {
+      echo,
+      started: false,
+      asked: None,
+      message: None,
+      hidden: false,
+    }
+  }
+
+  // Prints whatever changed since the last time, then the question being
+  // asked, if it was not already.
+  pub fn print<W>(&mut self, greeter: &Greeter, out: &mut W) -> io::Result<()>
+  where
+    W: Write,
+  {
+    // The line break ending a secret was not echoed either.
+    if self.hidden && self.asked.is_none() {
+      self.hidden = false;
+
+      (self.echo)(true);
+      writeln!(out)?;
+    }
+
+    if !self.started {
+      self.started = true;
+
+      for line in get_plain_greeting(greeter) {
+        writeln!(out, "{line}")?;
+      }
+
+      writeln!(out, "{}", fl!("line_commands"))?;
+    }
+
+    if greeter.message != self.message {
+      // Informational messages are appended to the current one, so only the
+      // part that was not printed yet is new.
+      if let Some(message) = &greeter.message {
+        let new = match &self.message {
+          Some(old) => message.strip_prefix(old.as_str()).unwrap_or(message),
+          None => message,
+        };
+
+        for line in new.lines().map(str::trim_end) {
+          if !line.is_empty() {
+            writeln!(out, "{line}")?;
+          }
+        }
+      }
+
+      self.message = greeter.message.clone();
+    }
+
+    let asked = Asked {
+      mode:   greeter.mode,
+      prompt: greeter.prompt.clone(),
+      secret: greeter.asking_for_secret,
+    };
+
+    let waiting = greeter.working
+      || greeter.mode == Mode::Action
+      || (greeter.mode == Mode::Password && greeter.prompt.is_none());
+
+    if waiting || self.asked.as_ref() == Some(&asked) {
+      return out.flush();
+    }
+
+    match greeter.mode {
+      Mode::Username => write!(out, "{} ", fl!("username"))?,
+
+      Mode::Password => {
+        if greeter.asking_for_secret {
+          self.hidden = true;
+
+          (self.echo)(false);
+        }
+
+        write!(out, "{}", greeter.prompt.as_deref().unwrap_or_default())?;
+      },
+
+      Mode::Command => write!(out, "{} ", fl!("new_command"))?,
+
+      Mode::Users => print_menu(out, &greeter.users)?,
+      Mode::Sessions => print_menu(out, &greeter.sessions)?,
+      Mode::Power => print_menu(out, &greeter.powers)?,
+      Mode::Vts => print_menu(out, &greeter.vts)?,
+      Mode::Layouts => print_menu(out, &greeter.layouts)?,
+
+      Mode::Processing => writeln!(out, "{}", fl!("wait"))?,
+
+      Mode::Action => {},
+    }
+
+    self.asked = Some(asked);
+
+    out.flush()
+  }
+
+  // Answers the question being asked with a line that was typed.
+  //
+  // The line fills in the current entry, or picks an entry of a menu by its
+  // number, and is then validated like with the Enter key, so everything else
+  // goes exactly as it does on the screen.
+  pub async fn handle(
+    &mut self,
+    greeter: Arc<RwLock<Greeter>>,
+    mut line: String,
+    ipc: Ipc,
+  ) -> Result<(), Box<dyn Error>> {
+    let key = {
+      let mut greeter = greeter.write().await;
+
+      if greeter.working {
+        line.zeroize();
+
+        return Ok(());
+      }
+
+      // Whatever the answer, the question is printed again if it is still
+      // the one asked.
+      self.asked = None;
+
+      answer(&mut greeter, line)
+    };
+
+    if let Some(key) = key {
+      let input = KeyEvent::new(key, KeyModifiers::empty());
+
+      keyboard::handle(greeter, input, ipc).await?;
+    }
+
+    Ok(())
+  }
+}
+
+// Finds the key validating a line typed in the current mode, once it is filled
+// in. The line may be a secret, so it is moved where it belongs or wiped, and
+// never copied.
+fn answer(greeter: &mut Greeter, mut line: String) -> Option<KeyCode> {
+  line.truncate(line.trim_end_matches(['\r', '\n']).len());
+
+  let key = match greeter.mode {
+    Mode::Username => {
+      match line.trim() {
+        "/session" => Some(KeyCode::F(greeter.kb_sessions)),
+        "/power" => Some(KeyCode::F(greeter.kb_power)),
+        "/command" => Some(KeyCode::F(greeter.kb_command)),
+
+        username => {
+          greeter.username = MaskedString::from(username.to_string(), None);
+          greeter.cursor_offset = 0;
+
+          Some(KeyCode::Enter)
+        },
+      }
+    },
+
+    Mode::Command if line.is_empty() => Some(KeyCode::Esc),
+
+    Mode::Password | Mode::Command => {
+      greeter.buffer.zeroize();
+      greeter.buffer = mem::take(&mut line);
+      greeter.cursor_offset = 0;
+
+      Some(KeyCode::Enter)
+    },
+
+    // Nothing goes back without picking anything, as with Escape.
+    Mode::Users | Mode::Sessions | Mode::Power | Mode::Vts | Mode::Layouts
+      if line.trim().is_empty() =>
+    {
+      Some(KeyCode::Esc)
+    },
+
+    Mode::Users | Mode::Sessions | Mode::Power | Mode::Vts | Mode::Layouts => {
+      match line.trim().parse::<usize>() {
+        Ok(number) if number > 0 && greeter.select_entry(number - 1) => {
+          Some(KeyCode::Enter)
+        },
+
+        _ => None,
+      }
+    },
+
+    Mode::Action | Mode::Processing => None,
+  };
+
+  line.zeroize();
+
+  key
+}
+
+// Prints the title and numbered entries of a menu, then asks for a number.
+fn print_menu<W, T>(out: &mut W, menu: &Menu<T>) -> io::Result<()>
+where
+  W: Write,
+  T: MenuItem,
+{
+  writeln!(out, "{}", menu.title)?;
+
+  for (index, option) in menu.options.iter().enumerate() {
+    writeln!(out, "{:>3}) {}", index + 1, option.format())?;
+  }
+
+  write!(out, "{} ", fl!("line_choice"))
+}
+
+// Turns the echo of typed characters on or off, to hide secrets.
+pub fn set_echo(echo: bool) {
+  let stdin = io::stdin();
+
+  let result = tcgetattr(&stdin).and_then(|mut termios| {
+    termios.local_flags.set(LocalFlags::ECHO, echo);
+
+    tcsetattr(&stdin, SetArg::TCSANOW, &termios)
+  });
+
+  if let Err(err) = result {
+    tracing::warn!("could not set terminal echo to {echo}: {err}");
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use std::sync::Arc;
+
+  use crossterm::event::KeyCode;
+  use tokio::sync::RwLock;
+
+  use super::{Conversation, answer, needs_lines};
+  use crate::{
+    Greeter,
+    Mode,
+    ipc::Ipc,
+    ui::{
+      common::menu::Menu,
+      sessions::{Session, SessionSource},
+    },
+  };
+
+  fn printed(conversation: &mut Conversation, greeter: &Greeter) -> String {
+    let mut out = Vec::new();
+
+    conversation.print(greeter, &mut out).unwrap();
+
+    String::from_utf8(out).unwrap()
+  }
+
+  #[test]
+  fn wanted() {
+    let table = [
+      (Some("linux"), Some((80, 24)), false),
+      (None, Some((80, 24)), false),
+      (Some("dumb"), Some((80, 24)), true),
+      (Some("vt100"), Some((80, 24)), true),
+      (Some("xterm"), Some((20, 24)), true),
+      (Some("xterm"), Some((80, 5)), true),
+      (Some("linux"), Some((0, 0)), true),
+      (Some("linux"), None, true),
+    ];
+
+    for (term, size, wanted) in table {
+      assert_eq!(needs_lines(term, size), wanted, "{term:?} {size:?}");
+    }
+  }
+
+  #[tokio::test]
+  async fn conversation() {
+    let greeter = Arc::new(RwLock::new(Greeter::default()));
+    let mut conversation = Conversation::new(|_| {});
+
+    greeter.write().await.greeting = Some("Welcome".to_string());
+
+    let out = printed(&mut conversation, &*greeter.read().await);
+    let lines = out.lines().collect::<Vec<_>>();
+
+    assert_eq!(lines[0], "Welcome");
+    assert!(lines[1].contains("/session"));
+    assert_eq!(lines[2], "Username: ");
+
+    // Nothing is printed again until something happens.
+    assert_eq!(printed(&mut conversation, &*greeter.read().await), "");
+
+    conversation
+      .handle(greeter.clone(), "apognu".to_string(), Ipc::new())
+      .await
+      .unwrap();
+
+    {
+      let mut greeter = greeter.write().await;
+
+      assert_eq!(greeter.username.value, "apognu");
+      assert!(greeter.working);
+
+      // As greetd would answer.
+      greeter.working = false;
+      greeter.mode = Mode::Password;
+      greeter.asking_for_secret = true;
+      greeter.prompt = Some("Password: ".to_string());
+    }
+
+    assert_eq!(
+      printed(&mut conversation, &*greeter.read().await),
+      "Password: "
+    );
+
+    conversation
+      .handle(greeter.clone(), "password2".to_string(), Ipc::new())
+      .await
+      .unwrap();
+
+    {
+      let mut greeter = greeter.write().await;
+
+      assert!(greeter.working);
+      assert_eq!(greeter.buffer, "");
+
+      greeter.working = false;
+      greeter.message = Some("Authentication failed".to_string());
+    }
+
+    // The line break typed with the secret was not echoed, then the same
+    // question is asked again.
+    assert_eq!(
+      printed(&mut conversation, &*greeter.read().await),
+      "\nAuthentication failed\nPassword: "
+    );
+  }
+
+  #[test]
+  fn answer_secret() {
+    let mut greeter = Greeter::default();
+    greeter.mode = Mode::Password;
+    greeter.buffer = "previous".to_string();
+
+    let key = answer(&mut greeter, "password\r\n".to_string());
+
+    assert_eq!(key, Some(KeyCode::Enter));
+    assert_eq!(greeter.buffer, "password");
+  }
+
+  #[tokio::test]
+  async fn numbered_menu() {
+    let greeter = Arc::new(RwLock::new(Greeter::default()));
+    let mut conversation = Conversation::new(|_| {});
+
+    greeter.write().await.sessions = Menu::<Session> {
+      title:    "Sessions".to_string(),
+      options:  vec![
+        Session {
+          name: "Sway".to_string(),
+          ..Default::default()
+        },
+        Session {
+          name: "Plasma".to_string(),
+          ..Default::default()
+        },
+      ],
+      selected: 0,
+    };
+
+    printed(&mut conversation, &*greeter.read().await);
+
+    conversation
+      .handle(greeter.clone(), "/session".to_string(), Ipc::new())
+      .await
+      .unwrap();
+
+    assert_eq!(greeter.read().await.mode, Mode::Sessions);
+    assert_eq!(
+      printed(&mut conversation, &*greeter.read().await),
+      "Sessions\n  1) Sway\n  2) Plasma\nNumber: "
+    );
+
+    // A number that is not in the menu asks again.
+    conversation
+      .handle(greeter.clone(), "3".to_string(), Ipc::new())
+      .await
+      .unwrap();
+
+    assert_eq!(greeter.read().await.mode, Mode::Sessions);
+    assert!(
+      printed(&mut conversation, &*greeter.read().await)
+        .starts_with("Sessions")
+    );
+
+    conversation
+      .handle(greeter.clone(), "2".to_string(), Ipc::new())
+      .await
+      .unwrap();
+
+    {
+      let greeter = greeter.read().await;
+
+      assert_eq!(greeter.mode, Mode::Username);
+      assert!(matches!(greeter.session_source, SessionSource::Session(1)));
+    }
+
+    assert_eq!(
+      printed(&mut conversation, &*greeter.read().await),
+      "Username: "
+    );
+  }
+}
@@ -3,14 +3,22 @@
 #[macro_use]
 mod macros;
 
+mod battery;
 mod config;
 mod event;
 mod greeter;
+mod greeting;
 mod info;
 mod ipc;
+mod issue;
 mod keyboard;
+mod line;
+mod mouse;
+mod network;
 mod power;
 mod ui;
+mod username;
+mod vt;
 
 #[cfg(test)] mod integration;
 
@@ -19,6 +27,7 @@ use std::{error::Error, fs::OpenOptions, io, process, sync::Arc};
 #[cfg(not(test))]
 use crossterm::terminal::{EnterAlternateScreen, enable_raw_mode};
 use crossterm::{
+  event::DisableMouseCapture,
   execute,
   terminal::{LeaveAlternateScreen, disable_raw_mode},
 };
@@ -27,10 +36,16 @@ use greetd_ipc::Request;
 use power::PowerPostAction;
 use tokio::sync::RwLock;
 use tracing_appender::non_blocking::WorkerGuard;
-use tui::{Terminal, backend::CrosstermBackend};
+use tui::{
+  Terminal,
+  TerminalOptions,
+  Viewport,
+  backend::CrosstermBackend,
+  layout::Rect,
+};
 
 pub use self::greeter::*;
-use self::{event::Events, ipc::Ipc};
+use self::{event::Events, ipc::Ipc, line::Conversation};
 
 #[tokio::main]
 async fn main() {
@@ -38,6 +53,8 @@ async fn main() {
   let events = Events::new().await;
   let greeter = Greeter::new(events.sender()).await;
 
+  events.listen(greeter.line_mode);
+
   if let Err(error) = run(backend, greeter, events).await {
     if let Some(AuthStatus::Success) = error.downcast_ref::<AuthStatus>() {
       return;
@@ -57,18 +74,38 @@ where
 {
   tracing::info!("tuigreet started");
 
-  register_panic_handler();
+  let line_mode = greeter.line_mode;
+
+  register_panic_handler(line_mode);
 
   #[cfg(not(test))]
-  {
+  if !line_mode {
     enable_raw_mode()?;
     execute!(io::stdout(), EnterAlternateScreen)?;
+
+    greeter.apply_mouse();
   }
 
-  let mut terminal = Terminal::new(backend)?;
+  // Nothing is drawn when the conversation is held line by line, and the
+  // terminal may not even know its size.
+  let mut terminal = match line_mode {
+    true => {
+      Terminal::with_options(backend, TerminalOptions {
+        viewport: Viewport::Fixed(Rect::default()),
+      })?
+    },
+    false => Terminal::new(backend)?,
+  };
+
+  let mut conversation = Conversation::new(line::set_echo);
 
   #[cfg(not(test))]
-  terminal.clear()?;
+  if !line_mode {
+    terminal.clear()?;
+  }
+
+  greeter.apply_numlock();
+  greeter.save_keymap().await;
 
   let ipc = Ipc::new();
 
@@ -80,6 +117,8 @@ where
       greeter.username.value
     );
 
+    greeter.restore_user_layout();
+
     ipc
       .send(Request::CreateSession {
         username: greeter.username.value.clone(),
@@ -131,11 +170,53 @@ where
       return Err(status.into());
     }
 
+    // Every event may change what is shown, but drawing waits for pending ones
+    // to be handled, so a burst of them is drawn only once. Line by line, only
+    // what changed is printed.
+    if !events.pending() && line_mode {
+      conversation.print(&*greeter.read().await, &mut io::stdout())?;
+    } else if !events.pending() {
+      {
+        let mut greeter = greeter.write().await;
+
+        if greeter.should_idle() {
+          greeter.blank().await;
+        }
+
+        if !greeter.idle {
+          greeter.refresh_status();
+        }
+      }
+
+      ui::draw(greeter.clone(), &mut terminal).await?;
+      events.schedule(ui::next_render(&*greeter.read().await));
+    }
+
     match events.next().await {
-      Some(Event::Render) => ui::draw(greeter.clone(), &mut terminal).await?,
+      Some(Event::Render) => {},
+      // The whole screen is cleared and drawn again at its new size, instead of
+      // patching what the terminal may have wrapped or scrolled away.
+      Some(Event::Resize(width, height)) => {
+        tracing::info!("terminal resized to {width}x{height}");
+
+        terminal.resize(Rect::new(0, 0, width, height))?;
+      },
+      // The key or click waking the screen up is swallowed, so it does not
+      // reach a prompt the user could not see.
+      Some(Event::Key(_) | Event::Mouse(_)) if greeter.read().await.idle => {
+        greeter.write().await.wake();
+      },
       Some(Event::Key(key)) => {
         keyboard::handle(greeter.clone(), key, ipc.clone()).await?
       },
+      Some(Event::Mouse(mouse)) => {
+        mouse::handle(greeter.clone(), mouse, ipc.clone()).await?
+      },
+      Some(Event::Line(line)) => {
+        conversation
+          .handle(greeter.clone(), line, ipc.clone())
+          .await?
+      },
 
       Some(Event::Exit(status)) => {
         crate::exit(&mut *greeter.write().await, status).await;
@@ -145,7 +226,11 @@ where
         if let PowerPostAction::ClearScreen =
           power::run(&greeter, command).await
         {
-          execute!(io::stdout(), LeaveAlternateScreen)?;
+          if line_mode {
+            break;
+          }
+
+          execute!(io::stdout(), DisableMouseCapture, LeaveAlternateScreen)?;
           terminal.set_cursor_position((1, 1))?;
           terminal.clear()?;
           disable_raw_mode()?;
@@ -154,12 +239,32 @@ where
         }
       },
 
-      Some(Event::Refresh) => {
-        // Config was hot reloaded, force a render
-        ui::draw(greeter.clone(), &mut terminal).await?
+      Some(Event::Users(users)) => greeter.write().await.add_users(users),
+
+      Some(Event::UsersLoaded(listing)) => {
+        greeter.write().await.set_user_listing(listing)
+      },
+
+      Some(Event::Greeting(greeting)) => {
+        greeter.write().await.greeting = Some(greeting)
       },
 
-      _ => {},
+      Some(Event::StatusCommand(command, output)) => {
+        greeter
+          .write()
+          .await
+          .status_commands
+          .insert(command, output);
+      },
+
+      Some(Event::Layout(layout, result)) => {
+        greeter.write().await.set_layout(layout, result)
+      },
+
+      // Config was hot reloaded.
+      Some(Event::Refresh) => {},
+
+      None => {},
     }
   }
 
@@ -174,29 +279,44 @@ async fn exit(greeter: &mut Greeter, status: AuthStatus) {
     AuthStatus::Cancel | AuthStatus::Failure => Ipc::cancel(greeter).await,
   }
 
-  #[cfg(not(test))]
-  clear_screen();
+  greeter.restore_palette();
 
-  let _ = execute!(io::stdout(), LeaveAlternateScreen);
-  let _ = disable_raw_mode();
+  // The session started on this virtual terminal keeps the keymap of the layout
+  // it was logged in with.
+  if !matches!(status, AuthStatus::Success) {
+    greeter.restore_keymap().await;
+  }
+  restore_terminal(greeter.line_mode);
 
   greeter.exit = Some(status);
 }
 
-fn register_panic_handler() {
+fn register_panic_handler(line_mode: bool) {
   let hook = std::panic::take_hook();
 
   std::panic::set_hook(Box::new(move |info| {
-    #[cfg(not(test))]
-    clear_screen();
-
-    let _ = execute!(io::stdout(), LeaveAlternateScreen);
-    let _ = disable_raw_mode();
+    restore_terminal(line_mode);
 
     hook(info);
   }));
 }
 
+// Leaves the terminal as it was found. A terminal holding the conversation line
+// by line was never drawn on, and only needs its echo back.
+fn restore_terminal(line_mode: bool) {
+  if line_mode {
+    line::set_echo(true);
+
+    return;
+  }
+
+  #[cfg(not(test))]
+  clear_screen();
+
+  let _ = execute!(io::stdout(), DisableMouseCapture, LeaveAlternateScreen);
+  let _ = disable_raw_mode();
+}
+
 #[cfg(not(test))]
 pub fn clear_screen() {
   let backend = CrosstermBackend::new(io::stdout());
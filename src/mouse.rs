@@ -0,0 +1,283 @@
+use std::{error::Error, sync::Arc, time::Instant};
+
+use crossterm::event::{
+  KeyCode,
+  KeyEvent,
+  KeyModifiers,
+  MouseButton,
+  MouseEvent,
+  MouseEventKind,
+};
+use tokio::sync::RwLock;
+use tui::layout::Position;
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+use crate::{Greeter, Mode, ipc::Ipc, keyboard, ui::common::target::Target};
+
+// Act on mouse events, when the mouse is captured.
+//
+// Clicks and wheel turns are translated into the key presses they stand for,
+// which are then handled as if typed, so the mouse does exactly what the
+// keyboard does. Only moving the cursor within a text entry is done here.
+pub async fn handle(
+  greeter: Arc<RwLock<Greeter>>,
+  input: MouseEvent,
+  ipc: Ipc,
+) -> Result<(), Box<dyn Error>> {
+  let key = {
+    let mut greeter = greeter.write().await;
+
+    greeter.last_input = Instant::now();
+
+    if greeter.working {
+      return Ok(());
+    }
+
+    translate(&mut greeter, input)
+  };
+
+  if let Some(key) = key {
+    let input = KeyEvent::new(key, KeyModifiers::empty());
+
+    keyboard::handle(greeter, input, ipc).await?;
+  }
+
+  Ok(())
+}
+
+// Finds the key a mouse event stands for, if any.
+fn translate(greeter: &mut Greeter, input: MouseEvent) -> Option<KeyCode> {
+  let in_menu = matches!(
+    greeter.mode,
+    Mode::Users | Mode::Sessions | Mode::Power | Mode::Vts | Mode::Layouts
+  );
+
+  match input.kind {
+    MouseEventKind::ScrollUp if in_menu => Some(KeyCode::Up),
+    MouseEventKind::ScrollDown if in_menu => Some(KeyCode::Down),
+
+    MouseEventKind::Down(MouseButton::Left) => {
+      let position = Position::new(input.column, input.row);
+      let (area, target) = greeter
+        .targets
+        .iter()
+        .find(|(area, _)| area.contains(position))
+        .copied()?;
+
+      match target {
+        Target::Key(key) => Some(key),
+
+        Target::Entry(index) if in_menu => {
+          greeter.select_entry(index);
+
+          Some(KeyCode::Enter)
+        },
+
+        Target::Entry(_) => None,
+
+        // Going back to the username while answering a question means starting
+        // over, as with Escape.
+        Target::Field(Mode::Username) if greeter.mode == Mode::Password => {
+          Some(KeyCode::Esc)
+        },
+
+        // An empty username offers the user menu instead, opened with Enter.
+        Target::Field(Mode::Username)
+          if greeter.mode == Mode::Username
+            && greeter.user_menu
+            && greeter.username.value.is_empty() =>
+        {
+          Some(KeyCode::Enter)
+        },
+
+        Target::Field(mode) if mode == greeter.mode => {
+          move_cursor(greeter, input.column - area.x);
+
+          None
+        },
+
+        Target::Field(_) => None,
+      }
+    },
+
+    _ => None,
+  }
+}
+
+// Moves the cursor of the current text entry to the clicked column, counted
+// from the start of the entry. Hidden secrets have no column to move to.
+fn move_cursor(greeter: &mut Greeter, column: u16) {
+  let value = match greeter.mode {
+    Mode::Username => greeter.username.get(),
+    Mode::Password if !greeter.asking_for_secret => &greeter.buffer,
+    Mode::Command => &greeter.buffer,
+    _ => return,
+  };
+
+  let mut width = 0;
+  let total = value.graphemes(true).count();
+  let before = value
+    .graphemes(true)
+    .take_while(|grapheme| {
+      width += grapheme.width();
+      width <= usize::from(column)
+    })
+    .count();
+
+  greeter.cursor_offset = before as i16 - total as i16;
+}
+
+#[cfg(test)]
+mod test {
+  use std::sync::Arc;
+
+  use crossterm::event::{
+    KeyCode,
+    KeyModifiers,
+    MouseButton,
+    MouseEvent,
+    MouseEventKind,
+  };
+  use tokio::sync::RwLock;
+  use tui::layout::Rect;
+
+  use super::handle;
+  use crate::{
+    Greeter,
+    Mode,
+    ipc::Ipc,
+    ui::{
+      common::{masked::MaskedString, menu::Menu, target::Target},
+      sessions::Session,
+    },
+  };
+
+  fn click(column: u16, row: u16) -> MouseEvent {
+    MouseEvent {
+      kind: MouseEventKind::Down(MouseButton::Left),
+      column,
+      row,
+      modifiers: KeyModifiers::empty(),
+    }
+  }
+
+  fn scroll(kind: MouseEventKind) -> MouseEvent {
+    MouseEvent {
+      kind,
+      column: 0,
+      row: 0,
+      modifiers: KeyModifiers::empty(),
+    }
+  }
+
+  #[tokio::test]
+  async fn click_button() {
+    let greeter = Arc::new(RwLock::new(Greeter::default()));
+
+    {
+      let mut greeter = greeter.write().await;
+      greeter.mode = Mode::Username;
+      greeter.targets =
+        vec![(Rect::new(10, 20, 16, 1), Target::Key(KeyCode::F(3)))];
+    }
+
+    let result = handle(greeter.clone(), click(9, 20), Ipc::new()).await;
+
+    assert!(result.is_ok());
+    assert_eq!(greeter.read().await.mode, Mode::Username);
+
+    let result = handle(greeter.clone(), click(12, 20), Ipc::new()).await;
+
+    assert!(result.is_ok());
+    assert_eq!(greeter.read().await.mode, Mode::Sessions);
+  }
+
+  #[tokio::test]
+  async fn click_and_scroll_menu() {
+    let greeter = Arc::new(RwLock::new(Greeter::default()));
+
+    {
+      let mut greeter = greeter.write().await;
+      greeter.mode = Mode::Sessions;
+      greeter.previous_mode = Mode::Username;
+      greeter.sessions = Menu::<Session> {
+        title:    "Sessions".to_string(),
+        options:  vec![
+          Session {
+            name: "First".to_string(),
+            ..Default::default()
+          },
+          Session {
+            name: "Second".to_string(),
+            ..Default::default()
+          },
+          Session {
+            name: "Third".to_string(),
+            ..Default::default()
+          },
+        ],
+        selected: 0,
+      };
+    }
+
+    for (kind, selected) in [
+      (MouseEventKind::ScrollDown, 1),
+      (MouseEventKind::ScrollDown, 2),
+      (MouseEventKind::ScrollUp, 1),
+    ] {
+      let result = handle(greeter.clone(), scroll(kind), Ipc::new()).await;
+
+      assert!(result.is_ok());
+      assert_eq!(greeter.read().await.sessions.selected, selected);
+    }
+
+    greeter.write().await.targets = vec![
+      (Rect::new(5, 5, 10, 1), Target::Entry(0)),
+      (Rect::new(5, 6, 10, 1), Target::Entry(1)),
+      (Rect::new(5, 7, 10, 1), Target::Entry(2)),
+    ];
+
+    let result = handle(greeter.clone(), click(7, 7), Ipc::new()).await;
+
+    {
+      let status = greeter.read().await;
+
+      assert!(result.is_ok());
+      assert_eq!(status.mode, Mode::Username);
+      assert_eq!(status.sessions.selected, 2);
+      assert_eq!(status.session_source.label(&status), Some("Third"));
+    }
+
+    // Entries drawn for a menu that has since been closed do nothing.
+    let result = handle(greeter.clone(), click(7, 5), Ipc::new()).await;
+
+    {
+      let status = greeter.read().await;
+
+      assert!(result.is_ok());
+      assert_eq!(status.mode, Mode::Username);
+      assert_eq!(status.sessions.selected, 2);
+    }
+  }
+
+  #[tokio::test]
+  async fn click_field() {
+    let greeter = Arc::new(RwLock::new(Greeter::default()));
+
+    {
+      let mut greeter = greeter.write().await;
+      greeter.mode = Mode::Username;
+      greeter.username = MaskedString::from("apognu".to_string(), None);
+      greeter.targets =
+        vec![(Rect::new(10, 3, 20, 1), Target::Field(Mode::Username))];
+    }
+
+    for (column, offset) in [(10, -6), (12, -4), (16, 0), (29, 0)] {
+      let result = handle(greeter.clone(), click(column, 3), Ipc::new()).await;
+
+      assert!(result.is_ok());
+      assert_eq!(greeter.read().await.cursor_offset, offset);
+    }
+  }
+}
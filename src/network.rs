@@ -0,0 +1,364 @@
+use std::{collections::HashMap, fs, net::IpAddr, path::Path};
+
+use nix::{ifaddrs::getifaddrs, net::if_::InterfaceFlags};
+
+const SYS_NET_PATH: &str = "/sys/class/net";
+const PROC_NET_PATH: &str = "/proc/net";
+
+// Flags of interfaces and routes, as found in sysfs and procfs.
+const IFF_UP: u32 = 0x1;
+const IFF_LOOPBACK: u32 = 0x8;
+const RTF_UP: u32 = 0x1;
+const RTF_REJECT: u32 = 0x200;
+
+// Network interface other than the loopback one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Interface {
+  pub name:      String,
+  // Whether the interface is up and has a link.
+  pub up:        bool,
+  pub addresses: Vec<IpAddr>,
+}
+
+// Interfaces of the machine, and whether it can reach other networks.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NetworkStatus {
+  pub interfaces:    Vec<Interface>,
+  pub default_route: bool,
+}
+
+impl NetworkStatus {
+  pub fn read() -> NetworkStatus {
+    let mut status = NetworkStatus::read_from(
+      Path::new(SYS_NET_PATH),
+      Path::new(PROC_NET_PATH),
+    );
+    let mut addresses = get_addresses();
+
+    for interface in &mut status.interfaces {
+      interface.addresses =
+        addresses.remove(&interface.name).unwrap_or_default();
+    }
+
+    status
+  }
+
+  // Reads the interfaces from the given `/sys/class/net` directory and the
+  // routing tables from the given `/proc/net` directory, without their
+  // addresses.
+  pub fn read_from(sys_net: &Path, proc_net: &Path) -> NetworkStatus {
+    let mut interfaces: Vec<Interface> = fs::read_dir(sys_net)
+      .into_iter()
+      .flatten()
+      .flatten()
+      .filter_map(|entry| {
+        let path = entry.path();
+        let attribute = |name: &str| {
+          fs::read_to_string(path.join(name))
+            .map(|value| value.trim().to_string())
+            .unwrap_or_default()
+        };
+
+        let flags =
+          u32::from_str_radix(attribute("flags").trim_start_matches("0x"), 16)
+            .unwrap_or_default();
+
+        if flags & IFF_LOOPBACK != 0 {
+          return None;
+        }
+
+        // Virtual interfaces without a notion of link report an unknown state
+        // while they are up.
+        let up = match attribute("operstate").as_str() {
+          "up" => true,
+          "unknown" => flags & IFF_UP != 0 && attribute("carrier") == "1",
+          _ => false,
+        };
+
+        Some(Interface {
+          name: entry.file_name().to_string_lossy().into_owned(),
+          up,
+          addresses: Vec::new(),
+        })
+      })
+      .collect();
+
+    interfaces.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let default_route = fs::read_to_string(proc_net.join("route"))
+      .is_ok_and(|routes| has_default_route(&routes))
+      || fs::read_to_string(proc_net.join("ipv6_route"))
+        .is_ok_and(|routes| has_default_ipv6_route(&routes));
+
+    NetworkStatus {
+      interfaces,
+      default_route,
+    }
+  }
+
+  pub fn interface(&self, name: &str) -> Option<&Interface> {
+    self
+      .interfaces
+      .iter()
+      .find(|interface| interface.name == name)
+  }
+
+  // Interfaces that are up with their first address, noting when there is no
+  // route out of the local networks.
+  pub fn summary(&self) -> String {
+    let interfaces: Vec<String> = self
+      .interfaces
+      .iter()
+      .filter(|interface| interface.up)
+      .map(Interface::summary)
+      .collect();
+
+    match (interfaces.is_empty(), self.default_route) {
+      (true, _) => fl!("network_offline"),
+      (false, true) => interfaces.join(", "),
+      (false, false) => {
+        format!("{} ({})", interfaces.join(", "), fl!("network_no_route"))
+      },
+    }
+  }
+}
+
+impl Interface {
+  // Name of the interface, followed by its first IPv4 address, or by its first
+  // address if it only has IPv6 ones.
+  pub fn summary(&self) -> String {
+    let address = self
+      .addresses
+      .iter()
+      .find(|address| address.is_ipv4())
+      .or(self.addresses.first());
+
+    match address {
+      Some(address) => format!("{} {address}", self.name),
+      None => self.name.clone(),
+    }
+  }
+}
+
+// Whether the IPv4 routing table holds a usable route to any destination.
+fn has_default_route(routes: &str) -> bool {
+  routes.lines().skip(1).any(|line| {
+    let fields: Vec<&str> = line.split_whitespace().collect();
+
+    match fields.as_slice() {
+      [_, destination, _, flags, _, _, _, mask, ..] => {
+        *destination == "00000000"
+          && *mask == "00000000"
+          && route_flags(flags) & (RTF_UP | RTF_REJECT) == RTF_UP
+      },
+      _ => false,
+    }
+  })
+}
+
+// Whether the IPv6 routing table holds a usable route to any destination,
+// ignoring the unreachable routes the kernel adds on the loopback interface.
+fn has_default_ipv6_route(routes: &str) -> bool {
+  routes.lines().any(|line| {
+    let fields: Vec<&str> = line.split_whitespace().collect();
+
+    match fields.as_slice() {
+      [destination, prefix, _, _, _, _, _, _, flags, device] => {
+        destination.chars().all(|c| c == '0')
+          && *prefix == "00"
+          && *device != "lo"
+          && route_flags(flags) & (RTF_UP | RTF_REJECT) == RTF_UP
+      },
+      _ => false,
+    }
+  })
+}
+
+fn route_flags(flags: &str) -> u32 {
+  u32::from_str_radix(flags, 16).unwrap_or_default()
+}
+
+// Addresses of every interface, by interface name.
+fn get_addresses() -> HashMap<String, Vec<IpAddr>> {
+  let mut addresses: HashMap<String, Vec<IpAddr>> = HashMap::new();
+
+  for entry in getifaddrs().into_iter().flatten() {
+    let address = entry.address.and_then(|address| {
+      address
+        .as_sockaddr_in()
+        .map(|addr| IpAddr::V4(addr.ip()))
+        .or_else(|| address.as_sockaddr_in6().map(|addr| IpAddr::V6(addr.ip())))
+    });
+
+    if let Some(address) = address {
+      addresses
+        .entry(entry.interface_name)
+        .or_default()
+        .push(address);
+    }
+  }
+
+  addresses
+}
+
+// First address of the provided interface, or of any interface that is up
+// other than the loopback one.
+pub fn get_address(interface: Option<&str>, ipv6: bool) -> Option<IpAddr> {
+  getifaddrs().ok()?.find_map(|entry| {
+    match interface {
+      Some(interface) if entry.interface_name != interface => return None,
+      None
+        if !entry.flags.contains(InterfaceFlags::IFF_UP)
+          || entry.flags.contains(InterfaceFlags::IFF_LOOPBACK) =>
+      {
+        return None;
+      },
+      _ => {},
+    }
+
+    let address = entry.address?;
+
+    match ipv6 {
+      false => address.as_sockaddr_in().map(|addr| IpAddr::V4(addr.ip())),
+      true => address.as_sockaddr_in6().map(|addr| IpAddr::V6(addr.ip())),
+    }
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{fs, path::Path};
+
+  use tempfile::tempdir;
+
+  use super::{Interface, NetworkStatus};
+
+  const ROUTE_HEADER: [&str; 11] = [
+    "Iface",
+    "Destination",
+    "Gateway",
+    "Flags",
+    "RefCnt",
+    "Use",
+    "Metric",
+    "Mask",
+    "MTU",
+    "Window",
+    "IRTT",
+  ];
+  const LOCAL_ROUTE: [&str; 11] = [
+    "enp3s0", "0000A8C0", "00000000", "0001", "0", "0", "100", "00FFFFFF", "0",
+    "0", "0",
+  ];
+  const DEFAULT_ROUTE: [&str; 11] = [
+    "enp3s0", "00000000", "0100A8C0", "0003", "0", "0", "100", "00000000", "0",
+    "0", "0",
+  ];
+
+  fn routes(routes: &[[&str; 11]]) -> String {
+    routes.iter().map(|route| route.join("\t") + "\n").collect()
+  }
+
+  // Link-local route, and the unreachable default route of the loopback
+  // interface.
+  fn ipv6_routes() -> String {
+    let zero = "0".repeat(32);
+    let link_local = format!("FE80{}", "0".repeat(28));
+
+    [
+      format!("{link_local} 40 {zero} 00 {zero} 00000100 1 0 00000001 enp3s0"),
+      format!("{zero} 00 {zero} 00 {zero} FFFFFFFF 1 0 00200200 lo"),
+    ]
+    .join("\n")
+  }
+
+  fn interface(root: &Path, name: &str, attributes: &[(&str, &str)]) {
+    let dir = root.join(name);
+    fs::create_dir(&dir).unwrap();
+
+    for (attribute, value) in attributes {
+      fs::write(dir.join(attribute), format!("{value}\n")).unwrap();
+    }
+  }
+
+  #[test]
+  fn interfaces_and_routes() {
+    let sys = tempdir().unwrap();
+    let proc = tempdir().unwrap();
+
+    interface(sys.path(), "lo", &[
+      ("operstate", "unknown"),
+      ("flags", "0x9"),
+    ]);
+    interface(sys.path(), "wlan0", &[
+      ("operstate", "down"),
+      ("flags", "0x1003"),
+    ]);
+    interface(sys.path(), "enp3s0", &[
+      ("operstate", "up"),
+      ("flags", "0x1003"),
+    ]);
+    interface(sys.path(), "tun0", &[
+      ("operstate", "unknown"),
+      ("flags", "0x1091"),
+      ("carrier", "1"),
+    ]);
+
+    fs::write(
+      proc.path().join("route"),
+      routes(&[ROUTE_HEADER, LOCAL_ROUTE]),
+    )
+    .unwrap();
+    fs::write(proc.path().join("ipv6_route"), ipv6_routes()).unwrap();
+
+    let status = NetworkStatus::read_from(sys.path(), proc.path());
+    let up: Vec<(&str, bool)> = status
+      .interfaces
+      .iter()
+      .map(|interface| (interface.name.as_str(), interface.up))
+      .collect();
+
+    assert_eq!(up, vec![("enp3s0", true), ("tun0", true), ("wlan0", false)]);
+    assert!(!status.default_route);
+
+    fs::write(
+      proc.path().join("route"),
+      routes(&[ROUTE_HEADER, LOCAL_ROUTE, DEFAULT_ROUTE]),
+    )
+    .unwrap();
+
+    assert!(NetworkStatus::read_from(sys.path(), proc.path()).default_route);
+  }
+
+  #[test]
+  fn summaries() {
+    let mut status = NetworkStatus {
+      interfaces:    vec![
+        Interface {
+          name:      "enp3s0".to_string(),
+          up:        true,
+          addresses: vec![
+            "fe80::1".parse().unwrap(),
+            "192.168.1.20".parse().unwrap(),
+          ],
+        },
+        Interface {
+          name:      "wlan0".to_string(),
+          up:        false,
+          addresses: vec![],
+        },
+      ],
+      default_route: true,
+    };
+
+    assert_eq!(status.summary(), "enp3s0 192.168.1.20");
+
+    status.default_route = false;
+
+    assert_eq!(status.summary(), "enp3s0 192.168.1.20 (no route)");
+
+    status.interfaces[0].up = false;
+
+    assert_eq!(status.summary(), "offline");
+  }
+}
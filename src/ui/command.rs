@@ -3,12 +3,14 @@ use std::error::Error;
 use tui::{
   layout::{Constraint, Direction, Layout, Rect},
   text::Span,
-  widgets::{Block, BorderType, Borders, Paragraph},
+  widgets::{Block, Paragraph},
 };
+use unicode_width::UnicodeWidthStr;
 
-use super::common::style::Themed;
+use super::common::{style::Themed, target::Target};
 use crate::{
   Greeter,
+  Mode,
   ui::{Frame, prompt_value, util::*},
 };
 
@@ -28,16 +30,16 @@ pub fn draw_with_area(
   let frame = Rect::new(
     x + container_padding,
     y + container_padding,
-    width - container_padding,
-    height - container_padding,
+    width.saturating_sub(container_padding),
+    height.saturating_sub(container_padding),
   );
 
   let block = Block::default()
-    .title(titleize(&fl!("title_command")))
+    .title(titleize(greeter, &fl!("title_command")))
     .title_style(theme.of(&[Themed::Title]))
     .style(theme.of(&[Themed::Container]))
-    .borders(Borders::ALL)
-    .border_type(BorderType::Plain)
+    .borders(theme.borders())
+    .border_set(greeter.glyphs.border_set(theme.border_type()))
     .border_style(theme.of(&[Themed::Border]));
 
   f.render_widget(block, container);
@@ -59,22 +61,25 @@ pub fn draw_with_area(
   let command_value =
     Paragraph::new(command_value_text).style(theme.of(&[Themed::Input]));
 
-  f.render_widget(command_label, chunks[0]);
-  f.render_widget(
-    command_value,
-    Rect::new(
-      1 + chunks[0].x + fl!("new_command").chars().count() as u16,
-      chunks[0].y,
-      get_input_width(greeter, width, &Some(fl!("new_command"))),
-      1,
-    ),
+  let command_area = Rect::new(
+    1 + chunks[0].x + fl!("new_command").width() as u16,
+    chunks[0].y,
+    get_input_width(width, &Some(fl!("new_command"))),
+    1,
   );
 
+  f.render_widget(command_label, chunks[0]);
+  f.render_widget(command_value, command_area);
+
+  greeter
+    .targets
+    .push((command_area, Target::Field(Mode::Command)));
+
   let new_command = greeter.buffer.clone();
-  let offset = get_cursor_offset(greeter, new_command.chars().count());
+  let offset = get_cursor_offset(greeter, &new_command);
 
   Ok((
-    2 + cursor.x + fl!("new_command").chars().count() as u16 + offset as u16,
+    2 + cursor.x + fl!("new_command").width() as u16 + offset,
     cursor.y + 1,
   ))
 }
@@ -0,0 +1,375 @@
+use std::{env, fs};
+
+use tui::style::Color;
+
+use super::style::Theme;
+use crate::config::ColorDepthMode;
+
+pub type Rgb = (u8, u8, u8);
+
+// A console palette, as read and written by the GIO_CMAP and PIO_CMAP ioctls.
+pub type Palette = [Rgb; 16];
+
+// Default palette of the Linux console, which the 16 named colors refer to.
+pub const VGA_PALETTE: Palette = [
+  (0x00, 0x00, 0x00),
+  (0xAA, 0x00, 0x00),
+  (0x00, 0xAA, 0x00),
+  (0xAA, 0x55, 0x00),
+  (0x00, 0x00, 0xAA),
+  (0xAA, 0x00, 0xAA),
+  (0x00, 0xAA, 0xAA),
+  (0xAA, 0xAA, 0xAA),
+  (0x55, 0x55, 0x55),
+  (0xFF, 0x55, 0x55),
+  (0x55, 0xFF, 0x55),
+  (0xFF, 0xFF, 0x55),
+  (0x55, 0x55, 0xFF),
+  (0xFF, 0x55, 0xFF),
+  (0x55, 0xFF, 0xFF),
+  (0xFF, 0xFF, 0xFF),
+];
+
+const NAMED_COLORS: [Color; 16] = [
+  Color::Black,
+  Color::Red,
+  Color::Green,
+  Color::Yellow,
+  Color::Blue,
+  Color::Magenta,
+  Color::Cyan,
+  Color::Gray,
+  Color::DarkGray,
+  Color::LightRed,
+  Color::LightGreen,
+  Color::LightYellow,
+  Color::LightBlue,
+  Color::LightMagenta,
+  Color::LightCyan,
+  Color::White,
+];
+
+// Palette entries given to the theme colors when programming the console. The
+// console can only use the first eight as backgrounds, and the black, gray and
+// white entries are kept for the default text and background.
+const BACKGROUND_SLOTS: [usize; 6] = [1, 2, 3, 4, 5, 6];
+const FOREGROUND_SLOTS: [usize; 6] = [9, 10, 11, 12, 13, 14];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum ColorDepth {
+  #[default]
+  TrueColor,
+  Indexed,
+  Basic,
+}
+
+impl ColorDepth {
+  pub fn from_mode(mode: &ColorDepthMode) -> ColorDepth {
+    match mode {
+      ColorDepthMode::Auto => ColorDepth::detect(),
+      ColorDepthMode::Truecolor => ColorDepth::TrueColor,
+      ColorDepthMode::Indexed => ColorDepth::Indexed,
+      ColorDepthMode::Basic => ColorDepth::Basic,
+    }
+  }
+
+  // Guesses the colors supported by the terminal from the environment. The
+  // Linux console only renders the 16 colors of its palette, whatever TERM
+  // says.
+  pub fn detect() -> ColorDepth {
+    ColorDepth::from_env(
+      env::var("TERM").ok().as_deref(),
+      env::var("COLORTERM").ok().as_deref(),
+      is_vt(),
+    )
+  }
+
+  fn from_env(
+    term: Option<&str>,
+    colorterm: Option<&str>,
+    vt: bool,
+  ) -> ColorDepth {
+    let term = term.unwrap_or_default();
+
+    if vt || term == "linux" {
+      ColorDepth::Basic
+    } else if matches!(colorterm, Some("truecolor" | "24bit"))
+      || term.ends_with("-direct")
+    {
+      ColorDepth::TrueColor
+    } else if term.contains("256color") {
+      ColorDepth::Indexed
+    } else {
+      ColorDepth::Basic
+    }
+  }
+}
+
+// Whether we are drawing on a Linux virtual terminal.
+pub fn is_vt() -> bool {
+  fs::read_link("/proc/self/fd/0").is_ok_and(|path| {
+    path
+      .to_str()
+      .and_then(|path| path.strip_prefix("/dev/tty"))
+      .is_some_and(|vt| {
+        !vt.is_empty() && vt.bytes().all(|c| c.is_ascii_digit())
+      })
+  })
+}
+
+// Converts a color to one the terminal can display.
+pub fn downgrade(color: Color, depth: ColorDepth) -> Color {
+  match (depth, to_rgb(color)) {
+    (ColorDepth::TrueColor, _) | (_, None) => color,
+    (ColorDepth::Indexed, Some(rgb)) => {
+      match color {
+        Color::Indexed(_) => color,
+        _ => Color::Indexed(nearest_indexed(rgb)),
+      }
+    },
+    (ColorDepth::Basic, Some(rgb)) => NAMED_COLORS[nearest(&VGA_PALETTE, rgb)],
+  }
+}
+
+// Reprograms entries of the console palette to the colors of the theme, and
+// points the theme at them. Entries the theme already uses by name are left
+// alone. Colors that do not fit are approximated with the resulting palette.
+pub fn program_palette(theme: Theme, base: &Palette) -> (Theme, Palette) {
+  let mut palette = *base;
+  let mut assigned: Vec<(Rgb, usize)> = Vec::new();
+
+  let (backgrounds, foregrounds) = theme_colors(&theme);
+  let named = named_slots(&theme);
+  let free = |slot: &usize| !named.contains(slot);
+  let mut background_slots = BACKGROUND_SLOTS.iter().copied().filter(free);
+  let mut foreground_slots = FOREGROUND_SLOTS.iter().copied().filter(free);
+
+  for (rgb, background) in backgrounds
+    .into_iter()
+    .map(|rgb| (rgb, true))
+    .chain(foregrounds.into_iter().map(|rgb| (rgb, false)))
+  {
+    if assigned.iter().any(|(color, _)| *color == rgb) {
+      continue;
+    }
+
+    let slot = match background {
+      true => background_slots.next(),
+      false => foreground_slots.next().or_else(|| background_slots.next()),
+    };
+
+    if let Some(slot) = slot {
+      palette[slot] = rgb;
+      assigned.push((rgb, slot));
+    }
+  }
+
+  let theme = theme.map_colors(|color| {
+    match to_rgb(color) {
+      Some(rgb) => {
+        let slot = assigned
+          .iter()
+          .find(|(color, _)| *color == rgb)
+          .map_or_else(|| nearest(&palette, rgb), |(_, slot)| *slot);
+
+        NAMED_COLORS[slot]
+      },
+      None => color,
+    }
+  });
+
+  (theme, palette)
+}
+
+// Lists the distinct colors of the theme that are not palette entries, used as
+// backgrounds and as foregrounds.
+fn theme_colors(theme: &Theme) -> (Vec<Rgb>, Vec<Rgb>) {
+  let mut backgrounds = Vec::new();
+  let mut foregrounds = Vec::new();
+
+  for style in theme.styles() {
+    for (color, colors) in
+      [(style.bg, &mut backgrounds), (style.fg, &mut foregrounds)]
+    {
+      if let Some(rgb) = color.and_then(to_rgb)
+        && !colors.contains(&rgb)
+      {
+        colors.push(rgb);
+      }
+    }
+  }
+
+  (backgrounds, foregrounds)
+}
+
+// Lists the entries of the 16 colors palette the theme refers to by name.
+fn named_slots(theme: &Theme) -> Vec<usize> {
+  theme
+    .styles()
+    .flat_map(|style| [style.bg, style.fg])
+    .flatten()
+    .filter_map(|color| NAMED_COLORS.iter().position(|named| *named == color))
+    .collect()
+}
+
+// Value of the colors that do not refer to an entry of the 16 colors palette.
+fn to_rgb(color: Color) -> Option<Rgb> {
+  const LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+  match color {
+    Color::Rgb(r, g, b) => Some((r, g, b)),
+    Color::Indexed(index @ 16..=231) => {
+      let index = index - 16;
+
+      Some((
+        LEVELS[usize::from(index / 36)],
+        LEVELS[usize::from(index / 6 % 6)],
+        LEVELS[usize::from(index % 6)],
+      ))
+    },
+    Color::Indexed(index @ 232..=255) => {
+      let level = 8 + (index - 232) * 10;
+
+      Some((level, level, level))
+    },
+    _ => None,
+  }
+}
+
+// Closest entry of the 256 colors palette, out of its color cube and grayscale
+// ramp.
+fn nearest_indexed((r, g, b): Rgb) -> u8 {
+  let level = |value: u8| {
+    match value {
+      0..48 => 0,
+      48..115 => 1,
+      _ => (value - 35) / 40,
+    }
+  };
+
+  let cube = 16 + 36 * level(r) + 6 * level(g) + level(b);
+
+  let average = (u16::from(r) + u16::from(g) + u16::from(b)) / 3;
+  let gray = match average {
+    0..8 => 232,
+    238.. => 255,
+    _ => 232 + ((average - 8) / 10) as u8,
+  };
+
+  [cube, gray]
+    .into_iter()
+    .min_by_key(|index| {
+      distance(to_rgb(Color::Indexed(*index)).unwrap(), (r, g, b))
+    })
+    .unwrap_or(cube)
+}
+
+fn nearest(palette: &Palette, rgb: Rgb) -> usize {
+  (0..palette.len())
+    .min_by_key(|index| distance(palette[*index], rgb))
+    .unwrap_or(0)
+}
+
+fn distance(a: Rgb, b: Rgb) -> u32 {
+  let channel = |a: u8, b: u8| (i32::from(a) - i32::from(b)).pow(2) as u32;
+
+  channel(a.0, b.0) + channel(a.1, b.1) + channel(a.2, b.2)
+}
+
+#[cfg(test)]
+mod tests {
+  use tui::style::Color;
+
+  use super::{ColorDepth, VGA_PALETTE, downgrade, program_palette};
+  use crate::ui::common::style::{Theme, Themed};
+
+  #[test]
+  fn detect_color_depth() {
+    let detect = ColorDepth::from_env;
+
+    assert_eq!(detect(Some("linux"), None, false), ColorDepth::Basic);
+    assert_eq!(
+      detect(Some("xterm-256color"), Some("truecolor"), true),
+      ColorDepth::Basic
+    );
+    assert_eq!(
+      detect(Some("xterm-256color"), Some("truecolor"), false),
+      ColorDepth::TrueColor
+    );
+    assert_eq!(
+      detect(Some("xterm-direct"), None, false),
+      ColorDepth::TrueColor
+    );
+    assert_eq!(
+      detect(Some("tmux-256color"), None, false),
+      ColorDepth::Indexed
+    );
+    assert_eq!(detect(Some("vt100"), None, false), ColorDepth::Basic);
+    assert_eq!(detect(None, None, false), ColorDepth::Basic);
+  }
+
+  #[test]
+  fn downgrade_colors() {
+    let nord = Color::Rgb(0x88, 0xC0, 0xD0);
+
+    assert_eq!(downgrade(nord, ColorDepth::TrueColor), nord);
+    assert_eq!(downgrade(nord, ColorDepth::Indexed), Color::Indexed(110));
+    assert_eq!(downgrade(nord, ColorDepth::Basic), Color::Gray);
+
+    assert_eq!(
+      downgrade(Color::Rgb(255, 60, 60), ColorDepth::Basic),
+      Color::LightRed
+    );
+    assert_eq!(
+      downgrade(Color::Rgb(30, 30, 30), ColorDepth::Indexed),
+      Color::Indexed(234)
+    );
+    assert_eq!(
+      downgrade(Color::Indexed(196), ColorDepth::Basic),
+      Color::Red
+    );
+    assert_eq!(downgrade(Color::Blue, ColorDepth::Basic), Color::Blue);
+    assert_eq!(downgrade(Color::Reset, ColorDepth::Indexed), Color::Reset);
+  }
+
+  #[test]
+  fn palette_from_theme() {
+    let theme = Theme::parse(
+      "container=#2e3440;text=#d8dee9;border=#2e3440;prompt=green;input=#\
+       eceff4",
+    );
+
+    let (theme, palette) = program_palette(theme, &VGA_PALETTE);
+
+    assert_eq!(theme.of(&[Themed::Container]).bg, Some(Color::Red));
+    assert_eq!(palette[1], (0x2E, 0x34, 0x40));
+    assert_eq!(
+      theme.of(&[Themed::Border]).fg,
+      Some(Color::Red),
+      "colors used as background and foreground share a slot"
+    );
+    assert_eq!(theme.of(&[Themed::Text]).fg, Some(Color::LightRed));
+    assert_eq!(palette[9], (0xD8, 0xDE, 0xE9));
+    assert_eq!(theme.of(&[Themed::Input]).fg, Some(Color::LightGreen));
+    assert_eq!(theme.of(&[Themed::Prompt]).fg, Some(Color::Green));
+    assert_eq!(palette[2], VGA_PALETTE[2]);
+    assert_eq!(palette[0], VGA_PALETTE[0]);
+  }
+
+  #[test]
+  fn palette_keeps_named_colors() {
+    let theme =
+      Theme::parse("container=#2e3440;border=red;text=#d8dee9;prompt=lightred");
+
+    let (theme, palette) = program_palette(theme, &VGA_PALETTE);
+
+    assert_eq!(theme.of(&[Themed::Border]).fg, Some(Color::Red));
+    assert_eq!(palette[1], VGA_PALETTE[1]);
+    assert_eq!(theme.of(&[Themed::Container]).bg, Some(Color::Green));
+    assert_eq!(palette[2], (0x2E, 0x34, 0x40));
+    assert_eq!(theme.of(&[Themed::Prompt]).fg, Some(Color::LightRed));
+    assert_eq!(palette[9], VGA_PALETTE[9]);
+    assert_eq!(theme.of(&[Themed::Text]).fg, Some(Color::LightGreen));
+    assert_eq!(palette[10], (0xD8, 0xDE, 0xE9));
+  }
+}
@@ -0,0 +1,78 @@
+// Block font the big clock is drawn with. Each glyph is a grid of pixels, and
+// each pixel is two cells wide so digits look about as wide as they are tall.
+
+pub const FONT_HEIGHT: u16 = 5;
+
+const PIXEL_WIDTH: usize = 2;
+
+fn glyph(c: char) -> Option<[&'static str; FONT_HEIGHT as usize]> {
+  let glyph = match c {
+    '0' => ["###", "#.#", "#.#", "#.#", "###"],
+    '1' => ["##.", ".#.", ".#.", ".#.", "###"],
+    '2' => ["###", "..#", "###", "#..", "###"],
+    '3' => ["###", "..#", "###", "..#", "###"],
+    '4' => ["#.#", "#.#", "###", "..#", "..#"],
+    '5' => ["###", "#..", "###", "..#", "###"],
+    '6' => ["###", "#..", "###", "#.#", "###"],
+    '7' => ["###", "..#", "..#", "..#", "..#"],
+    '8' => ["###", "#.#", "###", "#.#", "###"],
+    '9' => ["###", "#.#", "###", "..#", "###"],
+    ':' => [".", "#", ".", "#", "."],
+    '.' => [".", ".", ".", ".", "#"],
+    '-' => ["...", "...", "###", "...", "..."],
+    '/' => ["..#", "..#", ".#.", "#..", "#.."],
+    ' ' => [".", ".", ".", ".", "."],
+    _ => return None,
+  };
+
+  Some(glyph)
+}
+
+// Renders the text with the block font, filling pixels with the provided
+// symbol. Characters missing from the font are skipped, and every line has
+// the same width.
+pub fn render(text: &str, fill: &str) -> Vec<String> {
+  let glyphs: Vec<_> = text.chars().filter_map(glyph).collect();
+
+  (0..usize::from(FONT_HEIGHT))
+    .map(|row| {
+      glyphs
+        .iter()
+        .map(|glyph| {
+          glyph[row]
+            .chars()
+            .map(|pixel| {
+              match pixel {
+                '#' => fill.repeat(PIXEL_WIDTH),
+                _ => " ".repeat(PIXEL_WIDTH),
+              }
+            })
+            .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::render;
+
+  #[test]
+  fn render_digits() {
+    assert_eq!(render("1:0", "#"), vec![
+      "####      ######",
+      "  ##   ## ##  ##",
+      "  ##      ##  ##",
+      "  ##   ## ##  ##",
+      "######    ######",
+    ]);
+  }
+
+  #[test]
+  fn skip_unknown_characters() {
+    assert_eq!(render("7PM", "█"), render("7", "█"));
+    assert!(render("", "█").iter().all(String::is_empty));
+  }
+}
@@ -0,0 +1,187 @@
+use std::env;
+
+use tui::{symbols::border, widgets::BorderType};
+
+use crate::config::GlyphsMode;
+
+// Borders made of characters any terminal can display.
+const ASCII_BORDER: border::Set = border::Set {
+  top_left:          "+",
+  top_right:         "+",
+  bottom_left:       "+",
+  bottom_right:      "+",
+  vertical_left:     "|",
+  vertical_right:    "|",
+  horizontal_top:    "-",
+  horizontal_bottom: "-",
+};
+
+const ASCII_DOUBLE_BORDER: border::Set = border::Set {
+  horizontal_top: "=",
+  horizontal_bottom: "=",
+  ..ASCII_BORDER
+};
+
+// Indicators the UI can draw next to a label.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Indicator {
+  Caps,
+  Num,
+  Scroll,
+}
+
+// Set of symbols the UI is drawn with, depending on what the console font can
+// display.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Glyphs {
+  #[default]
+  Unicode,
+  Ascii,
+  NerdFont,
+}
+
+impl Glyphs {
+  pub fn from_mode(mode: &GlyphsMode) -> Glyphs {
+    match mode {
+      GlyphsMode::Auto => Glyphs::detect(),
+      GlyphsMode::Unicode => Glyphs::Unicode,
+      GlyphsMode::Ascii => Glyphs::Ascii,
+      GlyphsMode::Nerdfont => Glyphs::NerdFont,
+    }
+  }
+
+  // Guesses whether box-drawing characters can be displayed from the charset
+  // of the locale, following the precedence of the locale variables.
+  pub fn detect() -> Glyphs {
+    let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+      .into_iter()
+      .filter_map(|name| env::var(name).ok())
+      .find(|value| !value.is_empty());
+
+    Glyphs::from_locale(locale.as_deref())
+  }
+
+  // Without any locale set, the C locale applies, whose charset is ASCII.
+  fn from_locale(locale: Option<&str>) -> Glyphs {
+    let Some(locale) = locale else {
+      return Glyphs::Ascii;
+    };
+
+    let charset = locale
+      .split_once('.')
+      .map(|(_, charset)| charset.split('@').next().unwrap_or_default());
+
+    match charset.map(|charset| charset.to_lowercase().replace('-', "")) {
+      Some(charset) if charset == "utf8" => Glyphs::Unicode,
+      _ => Glyphs::Ascii,
+    }
+  }
+
+  pub fn border_set(self, border_type: BorderType) -> border::Set {
+    match (self, border_type) {
+      (Glyphs::Ascii, BorderType::Double) => ASCII_DOUBLE_BORDER,
+      (Glyphs::Ascii, _) => ASCII_BORDER,
+      (_, border_type) => BorderType::border_symbols(border_type),
+    }
+  }
+
+  // Symbols surrounding the title of a container, drawn over its border.
+  pub fn title(self) -> (&'static str, &'static str) {
+    match self {
+      Glyphs::Unicode => (" ", " "),
+      Glyphs::Ascii => ("[ ", " ]"),
+      Glyphs::NerdFont => ("\u{e0b7} ", " \u{e0b5}"),
+    }
+  }
+
+  // Symbol separating a status bar key from the action it triggers.
+  pub fn button(self) -> &'static str {
+    match self {
+      Glyphs::Unicode | Glyphs::Ascii => " ",
+      Glyphs::NerdFont => " \u{f0da} ",
+    }
+  }
+
+  // Symbol filling the pixels of the big clock digits.
+  pub fn block(self) -> &'static str {
+    match self {
+      Glyphs::Unicode | Glyphs::NerdFont => "\u{2588}",
+      Glyphs::Ascii => "#",
+    }
+  }
+
+  // Icon drawn before the battery label, following its charge, if any.
+  pub fn battery(self, capacity: u8, charging: bool) -> &'static str {
+    const LEVELS: [&str; 11] = [
+      "\u{f0083} ",
+      "\u{f007a} ",
+      "\u{f007b} ",
+      "\u{f007c} ",
+      "\u{f007d} ",
+      "\u{f007e} ",
+      "\u{f007f} ",
+      "\u{f0080} ",
+      "\u{f0081} ",
+      "\u{f0082} ",
+      "\u{f0079} ",
+    ];
+
+    match (self, charging) {
+      (Glyphs::NerdFont, true) => "\u{f0084} ",
+      (Glyphs::NerdFont, false) => LEVELS[usize::from(capacity.min(100) / 10)],
+      _ => "",
+    }
+  }
+
+  // Icon drawn before the label of an indicator, if any.
+  pub fn indicator(self, indicator: Indicator) -> &'static str {
+    match (self, indicator) {
+      (Glyphs::NerdFont, Indicator::Caps) => "\u{f0632} ",
+      (Glyphs::NerdFont, Indicator::Num) => "\u{f03a0} ",
+      (Glyphs::NerdFont, Indicator::Scroll) => "\u{f033e} ",
+      (..) => "",
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use tui::{symbols::border, widgets::BorderType};
+
+  use super::Glyphs;
+
+  #[test]
+  fn detect_from_locale() {
+    let detect = Glyphs::from_locale;
+
+    assert_eq!(detect(None), Glyphs::Ascii);
+    assert_eq!(detect(Some("en_US.UTF-8")), Glyphs::Unicode);
+    assert_eq!(detect(Some("de_DE.utf8@euro")), Glyphs::Unicode);
+    assert_eq!(detect(Some("C.UTF-8")), Glyphs::Unicode);
+    assert_eq!(detect(Some("C")), Glyphs::Ascii);
+    assert_eq!(detect(Some("POSIX")), Glyphs::Ascii);
+    assert_eq!(detect(Some("en_US")), Glyphs::Ascii);
+    assert_eq!(detect(Some("fr_FR.ISO-8859-1")), Glyphs::Ascii);
+  }
+
+  #[test]
+  fn border_sets() {
+    assert_eq!(
+      Glyphs::Unicode.border_set(BorderType::Rounded),
+      border::ROUNDED
+    );
+    assert_eq!(
+      Glyphs::NerdFont.border_set(BorderType::Plain),
+      border::PLAIN
+    );
+
+    let ascii = Glyphs::Ascii.border_set(BorderType::Rounded);
+
+    assert_eq!(ascii.top_left, "+");
+    assert_eq!(ascii.horizontal_top, "-");
+    assert_eq!(
+      Glyphs::Ascii.border_set(BorderType::Double).horizontal_top,
+      "="
+    );
+  }
+}
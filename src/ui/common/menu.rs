@@ -2,12 +2,14 @@ use std::{borrow::Cow, error::Error};
 
 use tui::{
   prelude::Rect,
-  style::{Modifier, Style},
   text::Span,
-  widgets::{Block, BorderType, Borders, Paragraph},
+  widgets::{Block, Paragraph},
 };
 
-use super::style::Themed;
+use super::{
+  style::{Theme, Themed},
+  target::Target,
+};
 use crate::{
   Greeter,
   ui::{
@@ -48,21 +50,23 @@ where
 
     let container = Rect::new(x, y, width, height);
 
-    let title = Span::from(titleize(&self.title));
+    let title = Span::from(titleize(greeter, &self.title));
     let block = Block::default()
       .title(title)
       .title_style(theme.of(&[Themed::Title]))
       .style(theme.of(&[Themed::Container]))
-      .borders(Borders::ALL)
-      .border_type(BorderType::Plain)
+      .borders(theme.borders())
+      .border_set(greeter.glyphs.border_set(theme.border_type()))
       .border_style(theme.of(&[Themed::Border]));
 
-    for (index, option) in self.options.iter().enumerate() {
+    let frames = self.entry_areas(greeter, area);
+
+    for (index, (option, frame)) in self.options.iter().zip(frames).enumerate()
+    {
       let name = option.format();
-      let name = format!("{:1$}", name, greeter.width() as usize - 4);
+      let name = format!("{:1$}", name, width.saturating_sub(4) as usize);
 
-      let frame = Rect::new(x + 2, y + 2 + index as u16, width - 4, 1);
-      let option_text = self.get_option(name, index);
+      let option_text = self.get_option(theme, name, index);
       let option = Paragraph::new(option_text);
 
       f.render_widget(option, frame);
@@ -73,15 +77,38 @@ where
     Ok((1, 1))
   }
 
-  fn get_option<'g, S>(&self, name: S, index: usize) -> Span<'g>
+  // Where each entry can be clicked, within the provided area.
+  pub fn targets(&self, greeter: &Greeter, area: Rect) -> Vec<(Rect, Target)> {
+    self
+      .entry_areas(greeter, area)
+      .enumerate()
+      .filter(|(_, frame)| !frame.is_empty())
+      .map(|(index, frame)| (frame, Target::Entry(index)))
+      .collect()
+  }
+
+  // Areas the entries are drawn in, one per line, clipped to the container.
+  fn entry_areas(
+    &self,
+    greeter: &Greeter,
+    area: Rect,
+  ) -> impl Iterator<Item = Rect> {
+    let (x, y, width, height) =
+      get_rect_bounds(greeter, area, self.options.len());
+    let container = Rect::new(x, y, width, height);
+
+    (0..self.options.len()).map(move |index| {
+      Rect::new(x + 2, y + 2 + index as u16, width.saturating_sub(4), 1)
+        .intersection(container)
+    })
+  }
+
+  fn get_option<'g, S>(&self, theme: &Theme, name: S, index: usize) -> Span<'g>
   where
     S: Into<String>,
   {
     if self.selected == index {
-      Span::styled(
-        name.into(),
-        Style::default().add_modifier(Modifier::REVERSED),
-      )
+      Span::styled(name.into(), theme.of(&[Themed::Selected]))
     } else {
       Span::from(name.into())
     }
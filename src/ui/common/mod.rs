@@ -1,3 +1,7 @@
+pub mod color;
+pub mod font;
+pub mod glyphs;
 pub mod masked;
 pub mod menu;
 pub mod style;
+pub mod target;
@@ -1,8 +1,11 @@
-use std::str::FromStr;
+use std::{collections::BTreeMap, str::FromStr};
 
-use tui::style::{Color, Style};
+use tui::{
+  style::{Color, Modifier, Style},
+  widgets::{BorderType, Borders},
+};
 
-#[derive(Clone)]
+#[derive(Clone, Copy)]
 enum Component {
   Bg,
   Fg,
@@ -19,20 +22,28 @@ pub enum Themed {
   Input,
   Action,
   ActionButton,
+  Selected,
+  BatteryLow,
+  Segment(&'static str),
 }
 
-#[derive(Default)]
+#[derive(Default, Clone)]
 pub struct Theme {
-  container: Option<(Component, Color)>,
-  time:      Option<(Component, Color)>,
-  text:      Option<(Component, Color)>,
-  border:    Option<(Component, Color)>,
-  title:     Option<(Component, Color)>,
-  greet:     Option<(Component, Color)>,
-  prompt:    Option<(Component, Color)>,
-  input:     Option<(Component, Color)>,
-  action:    Option<(Component, Color)>,
-  button:    Option<(Component, Color)>,
+  container:   Option<Style>,
+  time:        Option<Style>,
+  text:        Option<Style>,
+  border:      Option<Style>,
+  title:       Option<Style>,
+  greet:       Option<Style>,
+  prompt:      Option<Style>,
+  input:       Option<Style>,
+  action:      Option<Style>,
+  button:      Option<Style>,
+  selected:    Option<Style>,
+  battery_low: Option<Style>,
+  segments:    BTreeMap<String, Style>,
+  border_type: BorderType,
+  borderless:  bool,
 }
 
 impl Theme {
@@ -45,18 +56,45 @@ impl Theme {
     let mut style = Theme::default();
 
     for (key, value) in directives {
-      if let Ok(color) = Color::from_str(value) {
+      if key == "border_type" {
+        match value.trim() {
+          "none" => style.borderless = true,
+          value => {
+            if let Some(border_type) = parse_border_type(value) {
+              style.border_type = border_type;
+              style.borderless = false;
+            }
+          },
+        }
+
+        continue;
+      }
+
+      let component = match key {
+        "container" => Bg,
+        _ => Fg,
+      };
+
+      if let Some(parsed) = parse_style(value, component) {
+        if let Some(segment) = key.strip_prefix("status.") {
+          style.segments.insert(segment.to_string(), parsed);
+
+          continue;
+        }
+
         match key {
-          "container" => style.container = Some((Bg, color)),
-          "time" => style.time = Some((Fg, color)),
-          "text" => style.text = Some((Fg, color)),
-          "border" => style.border = Some((Fg, color)),
-          "title" => style.title = Some((Fg, color)),
-          "greet" => style.greet = Some((Fg, color)),
-          "prompt" => style.prompt = Some((Fg, color)),
-          "input" => style.input = Some((Fg, color)),
-          "action" => style.action = Some((Fg, color)),
-          "button" => style.button = Some((Fg, color)),
+          "container" => style.container = Some(parsed),
+          "time" => style.time = Some(parsed),
+          "text" => style.text = Some(parsed),
+          "border" => style.border = Some(parsed),
+          "title" => style.title = Some(parsed),
+          "greet" => style.greet = Some(parsed),
+          "prompt" => style.prompt = Some(parsed),
+          "input" => style.input = Some(parsed),
+          "action" => style.action = Some(parsed),
+          "button" => style.button = Some(parsed),
+          "selected" => style.selected = Some(parsed),
+          "battery_low" => style.battery_low = Some(parsed),
           _ => {},
         }
       }
@@ -84,31 +122,256 @@ impl Theme {
       .fold(Style::default(), |style, target| self.apply(style, target))
   }
 
+  pub fn borders(&self) -> Borders {
+    match self.borderless {
+      true => Borders::NONE,
+      false => Borders::ALL,
+    }
+  }
+
+  pub fn border_type(&self) -> BorderType {
+    self.border_type
+  }
+
+  // Every style of the theme, whether configured or inherited.
+  pub fn styles(&self) -> impl Iterator<Item = &Style> {
+    [
+      &self.container,
+      &self.time,
+      &self.text,
+      &self.border,
+      &self.title,
+      &self.greet,
+      &self.prompt,
+      &self.input,
+      &self.action,
+      &self.button,
+      &self.selected,
+      &self.battery_low,
+    ]
+    .into_iter()
+    .flatten()
+    .chain(self.segments.values())
+  }
+
+  // Replaces the colors of every style of the theme.
+  pub fn map_colors<F>(mut self, f: F) -> Theme
+  where
+    F: Fn(Color) -> Color,
+  {
+    for style in [
+      &mut self.container,
+      &mut self.time,
+      &mut self.text,
+      &mut self.border,
+      &mut self.title,
+      &mut self.greet,
+      &mut self.prompt,
+      &mut self.input,
+      &mut self.action,
+      &mut self.button,
+      &mut self.selected,
+      &mut self.battery_low,
+    ]
+    .into_iter()
+    .flatten()
+    .chain(self.segments.values_mut())
+    {
+      style.fg = style.fg.map(&f);
+      style.bg = style.bg.map(&f);
+    }
+
+    self
+  }
+
   fn apply(&self, style: Style, target: &Themed) -> Style {
     use Themed::*;
 
-    let color = match target {
-      Container => &self.container,
-      Time => &self.time,
-      Text => &self.text,
-      Border => &self.border,
-      Title => &self.title,
-      Greet => &self.greet,
-      Prompt => &self.prompt,
-      Input => &self.input,
-      Action => &self.action,
-      ActionButton => &self.button,
+    // Some elements are set apart by a modifier unless the theme removes it.
+    let style = match target {
+      Prompt => style.add_modifier(Modifier::BOLD),
+      ActionButton | Selected => style.add_modifier(Modifier::REVERSED),
+      BatteryLow => style.fg(Color::Red).add_modifier(Modifier::BOLD),
+      _ => style,
     };
 
-    match color {
-      Some((component, color)) => {
-        match component {
-          Component::Fg => style.fg(*color),
-          Component::Bg => style.bg(*color),
-        }
-      },
+    let themed = match target {
+      Container => self.container.as_ref(),
+      Time => self.time.as_ref(),
+      Text => self.text.as_ref(),
+      Border => self.border.as_ref(),
+      Title => self.title.as_ref(),
+      Greet => self.greet.as_ref(),
+      Prompt => self.prompt.as_ref(),
+      Input => self.input.as_ref(),
+      Action => self.action.as_ref(),
+      ActionButton => self.button.as_ref(),
+      Selected => self.selected.as_ref(),
+      BatteryLow => self.battery_low.as_ref(),
+      Segment(name) => self.segments.get(*name),
+    };
 
+    match themed {
+      Some(themed) => style.patch(*themed),
       None => style,
     }
   }
 }
+
+// Parses a whitespace-separated list of colors and modifiers. A bare color
+// applies to the default component of the element, `fg:` and `bg:` prefixes
+// select one explicitly, and modifiers prefixed with `!` are removed.
+fn parse_style(value: &str, component: Component) -> Option<Style> {
+  let mut style = Style::default();
+
+  for token in value.split_whitespace() {
+    if let Some(modifier) = token.strip_prefix('!').and_then(parse_modifier) {
+      style = style.remove_modifier(modifier);
+    } else if let Some(modifier) = parse_modifier(token) {
+      style = style.add_modifier(modifier);
+    } else if let Some(color) = token.strip_prefix("fg:") {
+      style = style.fg(Color::from_str(color).ok()?);
+    } else if let Some(color) = token.strip_prefix("bg:") {
+      style = style.bg(Color::from_str(color).ok()?);
+    } else {
+      let color = Color::from_str(token).ok()?;
+
+      style = match component {
+        Component::Fg => style.fg(color),
+        Component::Bg => style.bg(color),
+      };
+    }
+  }
+
+  Some(style)
+}
+
+fn parse_modifier(token: &str) -> Option<Modifier> {
+  match token {
+    "bold" => Some(Modifier::BOLD),
+    "italic" => Some(Modifier::ITALIC),
+    "underline" | "underlined" => Some(Modifier::UNDERLINED),
+    "dim" => Some(Modifier::DIM),
+    "reverse" | "reversed" => Some(Modifier::REVERSED),
+    _ => None,
+  }
+}
+
+fn parse_border_type(value: &str) -> Option<BorderType> {
+  match value {
+    "plain" => Some(BorderType::Plain),
+    "rounded" => Some(BorderType::Rounded),
+    "double" => Some(BorderType::Double),
+    "thick" => Some(BorderType::Thick),
+    _ => None,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use tui::{
+    style::{Color, Modifier, Style},
+    widgets::{BorderType, Borders},
+  };
+
+  use super::{Theme, Themed};
+
+  #[test]
+  fn colors_and_modifiers() {
+    let theme =
+      Theme::parse("text=cyan italic;container=fg:white bg:black;prompt=!bold");
+
+    assert_eq!(
+      theme.of(&[Themed::Text]),
+      Style::default()
+        .fg(Color::Cyan)
+        .add_modifier(Modifier::ITALIC)
+    );
+    assert_eq!(
+      theme.of(&[Themed::Time]),
+      theme.of(&[Themed::Text]),
+      "time should fall back to text"
+    );
+    assert_eq!(
+      theme.of(&[Themed::Container]),
+      Style::default().fg(Color::White).bg(Color::Black)
+    );
+    assert!(
+      !theme
+        .of(&[Themed::Prompt])
+        .add_modifier
+        .contains(Modifier::BOLD)
+    );
+  }
+
+  #[test]
+  fn default_modifiers() {
+    let theme = Theme::parse("selected=bg:blue !reverse;button=yellow");
+
+    assert!(
+      theme
+        .of(&[Themed::Prompt])
+        .add_modifier
+        .contains(Modifier::BOLD)
+    );
+    assert_eq!(
+      theme.of(&[Themed::ActionButton]),
+      Style::default()
+        .fg(Color::Yellow)
+        .add_modifier(Modifier::REVERSED)
+    );
+    assert_eq!(
+      theme.of(&[Themed::Selected]).bg,
+      Some(Color::Blue),
+      "selected style should be configurable"
+    );
+    assert!(
+      !theme
+        .of(&[Themed::Selected])
+        .add_modifier
+        .contains(Modifier::REVERSED)
+    );
+  }
+
+  #[test]
+  fn invalid_values_are_ignored() {
+    let theme = Theme::parse("text=notacolor;border=red blinking");
+
+    assert_eq!(theme.of(&[Themed::Text]), Style::default());
+    assert_eq!(theme.of(&[Themed::Border]), Style::default());
+  }
+
+  #[test]
+  fn segment_styles() {
+    let theme = Theme::parse("action=blue;status.hostname=green bold");
+
+    assert_eq!(
+      theme.of(&[Themed::Action, Themed::Segment("hostname")]),
+      Style::default()
+        .fg(Color::Green)
+        .add_modifier(Modifier::BOLD)
+    );
+    assert_eq!(
+      theme.of(&[Themed::Action, Themed::Segment("uptime")]),
+      theme.of(&[Themed::Action]),
+      "unstyled segments should keep the action style"
+    );
+  }
+
+  #[test]
+  fn border_types() {
+    let theme = Theme::default();
+
+    assert_eq!(theme.borders(), Borders::ALL);
+    assert_eq!(theme.border_type(), BorderType::Plain);
+
+    let theme = Theme::parse("border_type=rounded");
+
+    assert_eq!(theme.borders(), Borders::ALL);
+    assert_eq!(theme.border_type(), BorderType::Rounded);
+
+    let theme = Theme::parse("border_type=none");
+
+    assert_eq!(theme.borders(), Borders::NONE);
+  }
+}
@@ -0,0 +1,14 @@
+use crossterm::event::KeyCode;
+
+use crate::Mode;
+
+// Something drawn on the screen that reacts to a mouse click.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Target {
+  // A button of the status bar, standing for the key it hints at.
+  Key(KeyCode),
+  // An entry of the menu being shown, by index.
+  Entry(usize),
+  // A text entry of the prompt, by the mode it is edited in.
+  Field(Mode),
+}
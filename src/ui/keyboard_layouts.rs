@@ -0,0 +1,49 @@
+use std::borrow::Cow;
+
+use crate::{config::KeyboardLayoutConfig, ui::common::menu::MenuItem};
+
+#[derive(SmartDefault, Debug, Clone, PartialEq)]
+pub struct KeyboardLayout {
+  // Console keymap loaded when the layout is selected.
+  pub keymap:  String,
+  // XKB layout and variant exported to the session.
+  pub layout:  String,
+  pub variant: Option<String>,
+}
+
+impl KeyboardLayout {
+  // Layout as shown in the status bar, with its variant if any.
+  pub fn label(&self) -> String {
+    match self.variant {
+      Some(ref variant) => format!("{} ({variant})", self.layout),
+      None => self.layout.clone(),
+    }
+  }
+
+  // Environment telling compositors to use the same layout.
+  pub fn env(&self) -> Vec<String> {
+    let mut env = vec![format!("XKB_DEFAULT_LAYOUT={}", self.layout)];
+
+    if let Some(ref variant) = self.variant {
+      env.push(format!("XKB_DEFAULT_VARIANT={variant}"));
+    }
+
+    env
+  }
+}
+
+impl From<&KeyboardLayoutConfig> for KeyboardLayout {
+  fn from(config: &KeyboardLayoutConfig) -> KeyboardLayout {
+    KeyboardLayout {
+      keymap:  config.keymap.clone(),
+      layout:  config.layout.clone().unwrap_or(config.keymap.clone()),
+      variant: config.variant.clone(),
+    }
+  }
+}
+
+impl MenuItem for KeyboardLayout {
+  fn format(&self) -> Cow<'_, str> {
+    Cow::Borrowed(&self.keymap)
+  }
+}
@@ -0,0 +1,256 @@
+use std::borrow::Cow;
+
+use tui::layout::{Constraint, Direction, Layout, Rect};
+
+use super::widgets::big_clock_height;
+use crate::{
+  Greeter,
+  config::{
+    LayoutDirection,
+    LayoutNode,
+    LayoutSize,
+    LayoutWidget,
+    WidgetPosition,
+  },
+};
+
+// Returns the layout tree of the main screen, either as configured, or built
+// from the time and status bar positions.
+pub fn get_layout(greeter: &Greeter) -> Cow<'_, LayoutNode> {
+  match greeter
+    .loaded_config
+    .as_ref()
+    .and_then(|config| config.layout.tree.as_ref())
+  {
+    Some(tree) => Cow::Borrowed(tree),
+    None => Cow::Owned(default_layout(greeter)),
+  }
+}
+
+// Whether the layout of the main screen draws the provided widget on its own.
+pub fn has_widget(greeter: &Greeter, widget: LayoutWidget) -> bool {
+  greeter
+    .loaded_config
+    .as_ref()
+    .and_then(|config| config.layout.tree.as_ref())
+    .is_some_and(|tree| tree.contains(widget))
+}
+
+// Splits the area between the widgets of the layout tree, in the order they
+// appear in it.
+pub fn get_placements(
+  layout: &LayoutNode,
+  area: Rect,
+) -> Vec<(LayoutWidget, Rect)> {
+  let mut placements = Vec::new();
+
+  place(layout, area, &mut placements);
+
+  placements
+}
+
+fn place(
+  node: &LayoutNode,
+  area: Rect,
+  placements: &mut Vec<(LayoutWidget, Rect)>,
+) {
+  if let Some(widget) = node.widget {
+    placements.push((widget, area));
+    return;
+  }
+
+  if node.children.is_empty() {
+    return;
+  }
+
+  let direction = match node.direction {
+    LayoutDirection::Vertical => Direction::Vertical,
+    LayoutDirection::Horizontal => Direction::Horizontal,
+  };
+
+  let chunks = Layout::default()
+    .direction(direction)
+    .constraints(node.children.iter().map(|child| constraint(child.size)))
+    .split(area);
+
+  for (child, chunk) in node.children.iter().zip(chunks.iter()) {
+    place(child, *chunk, placements);
+  }
+}
+
+fn constraint(size: LayoutSize) -> Constraint {
+  match size {
+    LayoutSize::Length(length) => Constraint::Length(length),
+    LayoutSize::Percentage(percentage) => Constraint::Percentage(percentage),
+    LayoutSize::Min(min) => Constraint::Min(min),
+    LayoutSize::Max(max) => Constraint::Max(max),
+    LayoutSize::Fill(weight) => Constraint::Fill(weight),
+  }
+}
+
+// Builds the historical layout: the clocks and status bar stacked above or
+// below the prompt container, within the window padding.
+fn default_layout(greeter: &Greeter) -> LayoutNode {
+  let (time_position, status_position, big_clock_position) =
+    match greeter.loaded_config {
+      Some(ref config) => {
+        (
+          config.layout.widgets.time_position.clone(),
+          config.layout.widgets.status_position.clone(),
+          config.layout.widgets.big_clock_position.clone(),
+        )
+      },
+      None => {
+        (
+          WidgetPosition::Default,
+          WidgetPosition::Default,
+          WidgetPosition::Default,
+        )
+      },
+    };
+
+  let row = |widget: Option<LayoutWidget>, size: LayoutSize| {
+    LayoutNode {
+      widget,
+      size,
+      ..Default::default()
+    }
+  };
+
+  let padding = LayoutSize::Length(greeter.window_padding());
+  let line = LayoutSize::Length(1);
+  let big_clock = LayoutSize::Length(big_clock_height(greeter));
+
+  let mut children = vec![row(None, padding)];
+
+  if greeter.big_clock
+    && matches!(
+      big_clock_position,
+      WidgetPosition::Default | WidgetPosition::Top
+    )
+  {
+    children.push(row(Some(LayoutWidget::BigClock), big_clock));
+  }
+
+  if greeter.time
+    && !matches!(
+      time_position,
+      WidgetPosition::Hidden | WidgetPosition::Bottom
+    )
+  {
+    children.push(row(Some(LayoutWidget::Clock), line));
+  }
+
+  if matches!(status_position, WidgetPosition::Top) {
+    children.push(row(Some(LayoutWidget::StatusBar), line));
+  }
+
+  children.push(row(Some(LayoutWidget::Prompt), LayoutSize::Min(1)));
+
+  if matches!(
+    status_position,
+    WidgetPosition::Default | WidgetPosition::Bottom
+  ) {
+    children.push(row(Some(LayoutWidget::StatusBar), line));
+  }
+
+  if greeter.time && matches!(time_position, WidgetPosition::Bottom) {
+    children.push(row(Some(LayoutWidget::Clock), line));
+  }
+
+  if greeter.big_clock && matches!(big_clock_position, WidgetPosition::Bottom) {
+    children.push(row(Some(LayoutWidget::BigClock), big_clock));
+  }
+
+  children.push(row(None, padding));
+
+  LayoutNode {
+    children,
+    ..Default::default()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use tui::layout::Rect;
+
+  use super::{get_layout, get_placements};
+  use crate::{
+    Greeter,
+    config::{Config, LayoutWidget, WidgetPosition},
+  };
+
+  #[test]
+  fn default_layout_follows_widget_positions() {
+    let mut greeter = Greeter::default();
+    greeter.config = Greeter::options().parse(&[""]).ok();
+    greeter.time = true;
+
+    let mut config = Config::default();
+    config.layout.widgets.time_position = WidgetPosition::Bottom;
+    config.layout.widgets.status_position = WidgetPosition::Top;
+    greeter.loaded_config = Some(config);
+
+    let placements =
+      get_placements(&get_layout(&greeter), Rect::new(0, 0, 80, 24));
+
+    assert_eq!(placements, vec![
+      (LayoutWidget::StatusBar, Rect::new(0, 0, 80, 1)),
+      (LayoutWidget::Prompt, Rect::new(0, 1, 80, 22)),
+      (LayoutWidget::Clock, Rect::new(0, 23, 80, 1)),
+    ]);
+  }
+
+  #[test]
+  fn default_layout_with_big_clock() {
+    let mut greeter = Greeter::default();
+    greeter.config = Greeter::options().parse(&[""]).ok();
+    greeter.time = true;
+    greeter.big_clock = true;
+    greeter.big_clock_date = Some("%A".to_string());
+
+    let placements =
+      get_placements(&get_layout(&greeter), Rect::new(0, 0, 80, 24));
+
+    assert_eq!(placements, vec![
+      (LayoutWidget::BigClock, Rect::new(0, 0, 80, 7)),
+      (LayoutWidget::Clock, Rect::new(0, 7, 80, 1)),
+      (LayoutWidget::Prompt, Rect::new(0, 8, 80, 15)),
+      (LayoutWidget::StatusBar, Rect::new(0, 23, 80, 1)),
+    ]);
+  }
+
+  #[test]
+  fn configured_layout_splits_columns() {
+    let mut greeter = Greeter::default();
+    greeter.config = Greeter::options().parse(&[""]).ok();
+
+    let config: Config = toml::from_str(
+      r#"
+        [layout.tree]
+        direction = "horizontal"
+
+        [[layout.tree.children]]
+        widget = "logo"
+        size = "25%"
+
+        [[layout.tree.children]]
+        children = [
+          { widget = "prompt" },
+          { widget = "system_info", size = "1" },
+        ]
+      "#,
+    )
+    .unwrap();
+    greeter.loaded_config = Some(config);
+
+    let placements =
+      get_placements(&get_layout(&greeter), Rect::new(0, 0, 80, 24));
+
+    assert_eq!(placements, vec![
+      (LayoutWidget::Logo, Rect::new(0, 0, 20, 24)),
+      (LayoutWidget::Prompt, Rect::new(20, 0, 60, 23)),
+      (LayoutWidget::SystemInfo, Rect::new(20, 23, 60, 1)),
+    ]);
+  }
+}
@@ -0,0 +1,310 @@
+use tui::{
+  text::{Line, Text},
+  widgets::Paragraph,
+};
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+use super::{
+  common::menu::{Menu, MenuItem},
+  prompt::get_answer_value,
+  status::key_hints,
+};
+use crate::{
+  Greeter,
+  Mode,
+  ui::{
+    Frame,
+    util::{get_cursor_offset, get_plain_greeting},
+  },
+};
+
+// What was said so far on a linear screen.
+//
+// Once a question is answered or a menu closed, it is summed up in a line that
+// stays on screen, followed by the messages that came in response, so that
+// changes are announced by new lines instead of text rewritten in place.
+#[derive(Default)]
+pub struct Transcript {
+  // Lines of the conversation that are over, oldest first.
+  lines: Vec<String>,
+  // What was asked when the screen was last drawn, and the line summing it up.
+  last:  (Asked, Option<String>),
+}
+
+// What the screen is asking for, which changes when a question is answered.
+#[derive(Default, Clone, PartialEq)]
+struct Asked {
+  mode:    Mode,
+  prompt:  Option<String>,
+  message: Option<String>,
+}
+
+// The lines of the question being asked, with the cursor within them as a line
+// index and a column.
+struct Asking {
+  lines:   Vec<String>,
+  summary: Option<String>,
+  cursor:  Option<(usize, usize)>,
+}
+
+impl Transcript {
+  fn record(&mut self, asked: Asked, summary: Option<String>) {
+    let (last, said) = std::mem::take(&mut self.last);
+
+    if last != asked {
+      self
+        .lines
+        .extend(said.filter(|said| !said.trim().is_empty()));
+
+      // Informational messages are appended to the current one, so only the
+      // part that was not shown yet is new.
+      if let Some(message) = &asked.message {
+        let new = match &last.message {
+          Some(old) => message.strip_prefix(old.as_str()).unwrap_or(message),
+          None => message,
+        };
+
+        self.lines.extend(
+          new
+            .lines()
+            .map(str::trim_end)
+            .filter(|line| !line.is_empty())
+            .map(String::from),
+        );
+      }
+    }
+
+    self.last = (asked, summary);
+  }
+}
+
+// Draws the screen as plain lines of text from its top left corner, without
+// borders, colors or anything changing on its own, for screen readers and
+// braille displays.
+pub fn draw(greeter: &mut Greeter, f: &mut Frame) {
+  let area = f.area();
+  let width = usize::from(area.width);
+  let height = usize::from(area.height);
+
+  let asked = Asked {
+    mode:    greeter.mode,
+    prompt:  greeter.prompt.clone(),
+    message: greeter.message.clone(),
+  };
+
+  let asking = ask(greeter);
+
+  greeter.transcript.record(asked, asking.summary.clone());
+
+  let header = header(greeter);
+  let rows = |lines: &[String]| -> usize {
+    lines.iter().map(|line| wrap(line, width).len()).sum()
+  };
+
+  // Once the screen is full, older lines are dropped until half of it is free
+  // again, rather than one by one, so the whole screen is not written again for
+  // every new line.
+  let fixed = rows(&header) + rows(&asking.lines);
+
+  if fixed + rows(&greeter.transcript.lines) > height {
+    while !greeter.transcript.lines.is_empty()
+      && fixed + rows(&greeter.transcript.lines) > height / 2
+    {
+      greeter.transcript.lines.remove(0);
+    }
+  }
+
+  let before = header.iter().chain(&greeter.transcript.lines);
+  let offset = before
+    .clone()
+    .map(|line| wrap(line, width).len())
+    .sum::<usize>();
+
+  let cursor = asking.cursor.map(|(line, column)| {
+    let above = rows(&asking.lines[..line]);
+
+    (
+      column % width.max(1),
+      offset + above + column / width.max(1),
+    )
+  });
+
+  let mut screen = before
+    .chain(&asking.lines)
+    .flat_map(|line| wrap(line, width))
+    .collect::<Vec<_>>();
+
+  // What is asked is always kept in sight, even on a screen too small for it.
+  let skip = screen.len().saturating_sub(height);
+  screen.drain(..skip);
+
+  let text = Text::from(screen.into_iter().map(Line::from).collect::<Vec<_>>());
+
+  f.render_widget(Paragraph::new(text), area);
+
+  if !greeter.working
+    && !greeter.done
+    && let Some((x, y)) = cursor
+    && let Some(y) = y.checked_sub(skip)
+  {
+    f.set_cursor_position((area.x + x as u16, area.y + y as u16));
+  }
+}
+
+// Lines always shown at the top of the screen: the greeting and the keys that
+// can be pressed.
+fn header(greeter: &Greeter) -> Vec<String> {
+  let mut lines = get_plain_greeting(greeter);
+  let hints = key_hints(greeter, &greeter.theme)
+    .into_iter()
+    .map(|(span, _)| span.content)
+    .collect::<String>();
+
+  lines.push(hints);
+
+  lines
+}
+
+// Lines of what is being asked in the current mode.
+fn ask(greeter: &mut Greeter) -> Asking {
+  let mut asking = match greeter.mode {
+    Mode::Username
+      if greeter.user_menu && greeter.username.value.is_empty() =>
+    {
+      Asking {
+        lines:   vec![fl!("select_user")],
+        summary: None,
+        cursor:  Some((0, 0)),
+      }
+    },
+
+    Mode::Username => {
+      let username = greeter.username.get().to_string();
+      let offset = usize::from(get_cursor_offset(greeter, &username));
+      let label = fl!("username");
+
+      Asking {
+        cursor:  Some((0, label.width() + 1 + offset)),
+        lines:   vec![format!("{label} {username}")],
+        summary: Some(format!("{label} {username}")),
+      }
+    },
+
+    Mode::Password => {
+      let prompt = greeter.prompt.clone().unwrap_or_default();
+      let answer = get_answer_value(greeter);
+      let offset = usize::from(get_cursor_offset(greeter, &answer));
+
+      let (shown, offset) =
+        match greeter.asking_for_secret && !greeter.secret_display.show() {
+          true => (String::new(), 0),
+          false => (answer, offset),
+        };
+
+      let mut line = format!("{prompt}{shown}");
+
+      // Warn about Caps Lock while a secret is typed, since it cannot be seen
+      // in the answer.
+      if greeter.asking_for_secret && greeter.locks.caps {
+        line = format!("{line} ({})", fl!("status_caps"));
+      }
+
+      Asking {
+        lines:   vec![line],
+        summary: Some(format!("{prompt}{shown}")),
+        cursor:  Some((0, prompt.width() + offset)),
+      }
+    },
+
+    Mode::Command => {
+      let command = greeter.buffer.clone();
+      let offset = usize::from(get_cursor_offset(greeter, &command));
+      let label = fl!("new_command");
+
+      Asking {
+        cursor:  Some((0, label.width() + 1 + offset)),
+        lines:   vec![format!("{label} {command}")],
+        summary: Some(format!("{label} {command}")),
+      }
+    },
+
+    Mode::Users => menu(&greeter.users),
+    Mode::Sessions => menu(&greeter.sessions),
+    Mode::Power => menu(&greeter.powers),
+    Mode::Vts => menu(&greeter.vts),
+    Mode::Layouts => menu(&greeter.layouts),
+
+    Mode::Action => {
+      Asking {
+        lines:   Vec::new(),
+        summary: None,
+        cursor:  None,
+      }
+    },
+
+    Mode::Processing => {
+      Asking {
+        lines:   vec![fl!("wait")],
+        summary: None,
+        cursor:  None,
+      }
+    },
+  };
+
+  if greeter.working && greeter.mode != Mode::Processing {
+    asking.lines.push(fl!("wait"));
+  }
+
+  asking
+}
+
+// Lines of a menu: its title, then one entry per line with the selected one
+// marked, where the cursor is.
+fn menu<T>(menu: &Menu<T>) -> Asking
+where
+  T: MenuItem,
+{
+  let mut lines = vec![menu.title.clone()];
+
+  lines.extend(menu.options.iter().enumerate().map(|(index, option)| {
+    match index == menu.selected {
+      true => format!("> {}", option.format()),
+      false => format!("  {}", option.format()),
+    }
+  }));
+
+  let summary = menu
+    .options
+    .get(menu.selected)
+    .map(|option| format!("{}: {}", menu.title, option.format()));
+
+  Asking {
+    lines,
+    summary,
+    cursor: Some((1 + menu.selected, 0)),
+  }
+}
+
+// Splits a line into rows of the provided width, at any grapheme.
+fn wrap(line: &str, width: usize) -> Vec<String> {
+  let mut rows = vec![String::new()];
+  let mut used = 0;
+
+  for grapheme in line.graphemes(true) {
+    let grapheme_width = grapheme.width();
+
+    if used + grapheme_width > width && used > 0 {
+      rows.push(String::new());
+      used = 0;
+    }
+
+    if let Some(row) = rows.last_mut() {
+      row.push_str(grapheme);
+    }
+
+    used += grapheme_width;
+  }
+
+  rows
+}
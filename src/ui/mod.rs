@@ -1,69 +1,57 @@
 mod command;
 pub mod common;
 mod i18n;
+pub mod keyboard_layouts;
+mod layout;
+mod linear;
 pub mod power;
 mod processing;
 mod prompt;
 pub mod sessions;
+mod status;
 #[cfg(test)] mod tests;
 pub mod users;
 mod util;
+pub mod vts;
+mod widgets;
 
 use std::{
   borrow::Cow,
   error::Error,
   io::{self, Write},
   sync::Arc,
+  time::Duration,
 };
 
 use chrono::prelude::*;
-use sessions::SessionSource;
 use tokio::sync::RwLock;
 use tui::{
   Frame as CrosstermFrame,
   Terminal,
-  layout::{Alignment, Constraint, Direction, Layout},
-  style::Modifier,
-  text::{Line, Span},
+  layout::{Alignment, Rect},
+  text::Span,
   widgets::Paragraph,
 };
-use util::buttonize;
 
-use self::common::style::{Theme, Themed};
-pub use self::i18n::MESSAGES;
+use self::common::{
+  color::is_vt,
+  style::{Theme, Themed},
+};
+pub use self::{i18n::MESSAGES, linear::Transcript, util::get_plain_greeting};
 use crate::{
   Greeter,
+  IdleScreen,
   Mode,
-  config::WidgetPosition,
-  info::capslock_status,
+  config::{LayoutWidget, StatusSegment},
   ui::util::should_hide_cursor,
 };
 
-const STATUSBAR_LEFT_INDEX: usize = 1;
-const STATUSBAR_RIGHT_INDEX: usize = 2;
+// How often status that changes on its own, like the battery charge, is read
+// again while it is shown.
+const STATUS_POLL_INTERVAL: Duration = Duration::from_secs(2);
 
 pub(super) type Frame<'a> = CrosstermFrame<'a>;
 
-enum Button {
-  Command,
-  Session,
-  Power,
-  Other,
-}
-
-/// Get widget position from config
-fn get_widget_position(greeter: &Greeter, widget_name: &str) -> WidgetPosition {
-  if let Some(ref config) = greeter.loaded_config {
-    match widget_name {
-      "time" => config.layout.widgets.time_position.clone(),
-      "status" => config.layout.widgets.status_position.clone(),
-      _ => WidgetPosition::Default,
-    }
-  } else {
-    WidgetPosition::Default
-  }
-}
-
 pub async fn draw<B>(
   greeter: Arc<RwLock<Greeter>>,
   terminal: &mut Terminal<B>,
@@ -75,146 +63,45 @@ where
   let hide_cursor = should_hide_cursor(&greeter);
 
   terminal.draw(|f| {
-    let theme = &greeter.theme;
-    let size = f.area();
-    let time_position = get_widget_position(&greeter, "time");
-    let status_position = get_widget_position(&greeter, "status");
-
-    // Dynamic layout
-    let mut constraints = vec![];
-    let mut time_slot = None;
-    let mut status_slot = None;
-
-    // Top padding
-    constraints.push(Constraint::Length(greeter.window_padding()));
-
-    // Time at top (default behavior)
-    if greeter.time
-      && !matches!(
-        time_position,
-        WidgetPosition::Hidden | WidgetPosition::Bottom
-      )
-    {
-      time_slot = Some(constraints.len());
-      constraints.push(Constraint::Length(1));
-    }
-
-    // Status at top (if configured)
-    if matches!(status_position, WidgetPosition::Top) {
-      status_slot = Some(constraints.len());
-      constraints.push(Constraint::Length(1));
-    }
+    greeter.targets.clear();
 
-    // Main content area
-    let main_slot = constraints.len();
-    constraints.push(Constraint::Min(1));
-
-    // Status at bottom (default behavior)
-    if matches!(
-      status_position,
-      WidgetPosition::Default | WidgetPosition::Bottom
-    ) {
-      status_slot = Some(constraints.len());
-      constraints.push(Constraint::Length(1));
-    }
+    if greeter.idle {
+      widgets::draw_idle(&greeter, f);
 
-    // Time at bottom (if configured)
-    if greeter.time && matches!(time_position, WidgetPosition::Bottom) {
-      time_slot = Some(constraints.len());
-      constraints.push(Constraint::Length(1));
+      return;
     }
 
-    // Bottom padding
-    constraints.push(Constraint::Length(greeter.window_padding()));
-
-    let chunks = Layout::default().constraints(constraints).split(size);
+    if greeter.screen_reader {
+      linear::draw(&mut greeter, f);
 
-    // Render time widget if enabled and not hidden
-    if let Some(slot) = time_slot {
-      let time_text = Span::from(get_time(&greeter));
-      let time = Paragraph::new(time_text)
-        .alignment(Alignment::Center)
-        .style(theme.of(&[Themed::Time]));
-
-      f.render_widget(time, chunks[slot]);
+      return;
     }
 
-    // Render status bar if not hidden
-    if let Some(slot) = status_slot {
-      let status_block_size_right = 1
-        + greeter.window_padding()
-        + fl!("status_caps").chars().count() as u16;
-      let status_block_size_left =
-        (size.width - greeter.window_padding()) - status_block_size_right;
-
-      let status_chunks = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints(
-          [
-            Constraint::Length(greeter.window_padding()),
-            Constraint::Length(status_block_size_left),
-            Constraint::Length(status_block_size_right),
-            Constraint::Length(greeter.window_padding()),
-          ]
-          .as_ref(),
-        )
-        .split(chunks[slot]);
-
-      let session_source_label = match greeter.session_source {
-        SessionSource::Session(_) => fl!("status_session"),
-        _ => fl!("status_command"),
-      };
-
-      let session_source =
-        greeter.session_source.label(&greeter).unwrap_or("-");
-
-      let status_left_text = Line::from(vec![
-        status_label(theme, "ESC"),
-        status_value(&greeter, theme, Button::Other, fl!("action_reset")),
-        Span::from(" "),
-        status_label(theme, format!("F{}", greeter.kb_command)),
-        status_value(&greeter, theme, Button::Command, fl!("action_command")),
-        Span::from(" "),
-        status_label(theme, format!("F{}", greeter.kb_sessions)),
-        status_value(&greeter, theme, Button::Session, fl!("action_session")),
-        Span::from(" "),
-        status_label(theme, format!("F{}", greeter.kb_power)),
-        status_value(&greeter, theme, Button::Power, fl!("action_power")),
-        Span::from(" "),
-        status_label(theme, session_source_label),
-        status_value(&greeter, theme, Button::Other, session_source),
-      ]);
-      let status_left = Paragraph::new(status_left_text);
-
-      f.render_widget(status_left, status_chunks[STATUSBAR_LEFT_INDEX]);
-
-      if capslock_status() {
-        let status_right_text = status_label(theme, fl!("status_caps"));
-        let status_right =
-          Paragraph::new(status_right_text).alignment(Alignment::Right);
-
-        f.render_widget(status_right, status_chunks[STATUSBAR_RIGHT_INDEX]);
+    let placements =
+      layout::get_placements(&layout::get_layout(&greeter), f.area());
+
+    let mut cursor = None;
+
+    for (widget, area) in placements {
+      match widget {
+        LayoutWidget::Prompt => cursor = draw_main(&mut greeter, f, area),
+        LayoutWidget::Clock => draw_time(&greeter, f, area),
+        LayoutWidget::BigClock => widgets::draw_big_clock(&greeter, f, area),
+        LayoutWidget::StatusBar => {
+          let targets = status::draw_status_bar(&greeter, f, area);
+
+          greeter.targets.extend(targets);
+        },
+        LayoutWidget::Greeting => widgets::draw_greeting(&greeter, f, area),
+        LayoutWidget::SystemInfo => {
+          widgets::draw_system_info(&greeter, f, area)
+        },
+        LayoutWidget::Logo => widgets::draw_logo(&greeter, f, area),
+        LayoutWidget::Battery => widgets::draw_battery(&greeter, f, area),
+        LayoutWidget::Network => widgets::draw_network(&greeter, f, area),
       }
     }
 
-    // Get the main content area and create a sub-frame for content drawing
-    let main_area = chunks[main_slot];
-
-    let cursor = match greeter.mode {
-      Mode::Command => {
-        self::command::draw_with_area(&mut greeter, f, main_area).ok()
-      },
-      Mode::Sessions => {
-        greeter.sessions.draw_with_area(&greeter, f, main_area).ok()
-      },
-      Mode::Power => greeter.powers.draw_with_area(&greeter, f, main_area).ok(),
-      Mode::Users => greeter.users.draw_with_area(&greeter, f, main_area).ok(),
-      Mode::Processing => {
-        self::processing::draw_with_area(&mut greeter, f, main_area).ok()
-      },
-      _ => self::prompt::draw_with_area(&mut greeter, f, main_area).ok(),
-    };
-
     if !hide_cursor && let Some(cursor) = cursor {
       f.set_cursor_position((cursor.0 - 1, cursor.1 - 1));
     }
@@ -225,6 +112,45 @@ where
   Ok(())
 }
 
+// Draws the prompt container, or the menu or popup replacing it in the current
+// mode, and returns where the cursor should be.
+fn draw_main(
+  greeter: &mut Greeter,
+  f: &mut Frame,
+  area: Rect,
+) -> Option<(u16, u16)> {
+  let targets = match greeter.mode {
+    Mode::Sessions => greeter.sessions.targets(greeter, area),
+    Mode::Power => greeter.powers.targets(greeter, area),
+    Mode::Users => greeter.users.targets(greeter, area),
+    Mode::Vts => greeter.vts.targets(greeter, area),
+    Mode::Layouts => greeter.layouts.targets(greeter, area),
+    _ => Vec::new(),
+  };
+
+  greeter.targets.extend(targets);
+
+  match greeter.mode {
+    Mode::Command => self::command::draw_with_area(greeter, f, area).ok(),
+    Mode::Sessions => greeter.sessions.draw_with_area(greeter, f, area).ok(),
+    Mode::Power => greeter.powers.draw_with_area(greeter, f, area).ok(),
+    Mode::Users => greeter.users.draw_with_area(greeter, f, area).ok(),
+    Mode::Vts => greeter.vts.draw_with_area(greeter, f, area).ok(),
+    Mode::Layouts => greeter.layouts.draw_with_area(greeter, f, area).ok(),
+    Mode::Processing => self::processing::draw_with_area(greeter, f, area).ok(),
+    _ => self::prompt::draw_with_area(greeter, f, area).ok(),
+  }
+}
+
+fn draw_time(greeter: &Greeter, f: &mut Frame, area: Rect) {
+  let time_text = Span::from(get_time(greeter));
+  let time = Paragraph::new(time_text)
+    .alignment(Alignment::Center)
+    .style(greeter.theme.of(&[Themed::Time]));
+
+  f.render_widget(time, area);
+}
+
 fn get_time(greeter: &Greeter) -> String {
   let format = match &greeter.time_format {
     Some(format) => Cow::Borrowed(format),
@@ -236,48 +162,70 @@ fn get_time(greeter: &Greeter) -> String {
     .to_string()
 }
 
-fn status_label<'s, S>(theme: &Theme, text: S) -> Span<'s>
-where
-  S: Into<String>,
-{
-  Span::styled(
-    text.into(),
-    theme
-      .of(&[Themed::ActionButton])
-      .add_modifier(Modifier::REVERSED),
-  )
-}
+// Time left until the screen needs to be drawn again on its own, for a clock to
+// tick, for polled status to be read again or for the screen to be blanked.
+// Nothing is drawn in the meantime unless something happens.
+pub fn next_render(greeter: &Greeter) -> Option<Duration> {
+  if greeter.idle {
+    return match greeter.idle_screen {
+      IdleScreen::Clock => Some(until_next(60)),
+      IdleScreen::Blank => None,
+    };
+  }
 
-fn status_value<'s, S>(
-  greeter: &Greeter,
-  theme: &Theme,
-  button: Button,
-  text: S,
-) -> Span<'s>
-where
-  S: Into<String>,
-{
-  let relevant_mode = match button {
-    Button::Command => Mode::Command,
-    Button::Session => Mode::Sessions,
-    Button::Power => Mode::Power,
-
-    _ => {
-      return Span::from(buttonize(&text.into()))
-        .style(theme.of(&[Themed::Action]));
-    },
+  // Plain lines for screen readers leave the layout out, so nothing in it
+  // changes on its own.
+  let layout = layout::get_layout(greeter);
+  let widget =
+    |widget: LayoutWidget| !greeter.screen_reader && layout.contains(widget);
+  let segment = |segment: StatusSegment| {
+    widget(LayoutWidget::StatusBar) && status::has_segment(greeter, &segment)
   };
 
-  let style = match greeter.mode == relevant_mode {
-    true => {
-      theme
-        .of(&[Themed::ActionButton])
-        .add_modifier(Modifier::REVERSED)
-    },
-    false => theme.of(&[Themed::Action]),
-  };
+  let clock = widget(LayoutWidget::Clock)
+    || widget(LayoutWidget::BigClock)
+    || segment(StatusSegment::Clock);
+
+  let polled = widget(LayoutWidget::Battery)
+    || widget(LayoutWidget::Network)
+    || [
+      StatusSegment::Battery,
+      StatusSegment::Network,
+      StatusSegment::Uptime,
+    ]
+    .into_iter()
+    .any(segment);
+
+  // Lock keys do not send anything when pressed, so their state is polled as
+  // well, on a VT since it cannot be read anywhere else.
+  let locks = is_vt()
+    && ((greeter.mode == Mode::Password && greeter.asking_for_secret)
+      || [
+        StatusSegment::CapsLock,
+        StatusSegment::NumLock,
+        StatusSegment::ScrollLock,
+      ]
+      .into_iter()
+      .any(segment));
+
+  [
+    clock.then(|| until_next(1)),
+    (polled || locks).then_some(STATUS_POLL_INTERVAL),
+    greeter.until_idle(),
+  ]
+  .into_iter()
+  .flatten()
+  .min()
+}
+
+// Time left until the local time reaches the next multiple of the provided
+// number of seconds.
+fn until_next(seconds: u32) -> Duration {
+  let now = Local::now();
+  let elapsed =
+    Duration::new(u64::from(now.second() % seconds), now.nanosecond());
 
-  Span::from(buttonize(&text.into())).style(style)
+  Duration::from_secs(u64::from(seconds)).saturating_sub(elapsed)
 }
 
 fn prompt_value<'s, S>(theme: &Theme, text: Option<S>) -> Span<'s>
@@ -285,12 +233,7 @@ where
   S: Into<String>,
 {
   match text {
-    Some(text) => {
-      Span::styled(
-        text.into(),
-        theme.of(&[Themed::Prompt]).add_modifier(Modifier::BOLD),
-      )
-    },
+    Some(text) => Span::styled(text.into(), theme.of(&[Themed::Prompt])),
     None => Span::from(""),
   }
 }
@@ -3,7 +3,7 @@ use std::error::Error;
 use tui::{
   layout::{Alignment, Constraint, Direction, Layout, Rect},
   text::Span,
-  widgets::{Block, BorderType, Borders, Paragraph},
+  widgets::{Block, Paragraph},
 };
 
 use crate::{
@@ -16,25 +16,21 @@ pub fn draw_with_area(
   f: &mut Frame,
   area: Rect,
 ) -> Result<(u16, u16), Box<dyn Error>> {
-  let size = area;
-
-  let width = greeter.width();
-  let height: u16 = get_height(greeter) + 1;
-  let x = (size.width - width) / 2;
-  let y = (size.height - height) / 2;
+  let theme = &greeter.theme;
+  let (x, y, width, height) = get_rect_bounds(greeter, area, 1);
 
   let container = Rect::new(x, y, width, height);
   let container_padding = greeter.container_padding();
   let frame = Rect::new(
     x + container_padding,
     y + container_padding,
-    width - (2 * container_padding),
-    height - (2 * container_padding),
+    width.saturating_sub(2 * container_padding),
+    height.saturating_sub(2 * container_padding),
   );
 
   let block = Block::default()
-    .borders(Borders::ALL)
-    .border_type(BorderType::Plain);
+    .borders(theme.borders())
+    .border_set(greeter.glyphs.border_set(theme.border_type()));
 
   let constraints = [Constraint::Length(1)];
 
@@ -3,11 +3,14 @@ use std::error::Error;
 use rand::{Rng, SeedableRng, prelude::StdRng};
 use tui::{
   layout::{Alignment, Constraint, Direction, Layout, Rect},
-  text::Span,
-  widgets::{Block, BorderType, Borders, Paragraph},
+  style::{Modifier, Style},
+  text::{Line, Span},
+  widgets::{Block, Paragraph},
 };
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
 
-use super::common::style::Themed;
+use super::common::{glyphs::Indicator, style::Themed, target::Target};
 use crate::{
   GreetAlign,
   Greeter,
@@ -43,28 +46,28 @@ pub fn draw_with_area(
   let frame = Rect::new(
     x + container_padding,
     y + container_padding,
-    width - (2 * container_padding),
-    height - (2 * container_padding),
+    width.saturating_sub(2 * container_padding),
+    height.saturating_sub(2 * container_padding),
   );
 
-  let hostname = Span::from(titleize(&fl!(
-    "title_authenticate",
-    hostname = get_hostname()
-  )));
+  let hostname = Span::from(titleize(
+    greeter,
+    &fl!("title_authenticate", hostname = get_hostname()),
+  ));
   let block = Block::default()
     .title(hostname)
     .title_style(theme.of(&[Themed::Title]))
     .style(theme.of(&[Themed::Container]))
-    .borders(Borders::ALL)
-    .border_type(BorderType::Plain)
+    .borders(theme.borders())
+    .border_set(greeter.glyphs.border_set(theme.border_type()))
     .border_style(theme.of(&[Themed::Border]));
 
   f.render_widget(block, container);
 
   let (message, message_height) =
-    get_message_height(greeter, container_padding, 1);
+    get_message_height(greeter, width, container_padding, 1);
   let (greeting, greeting_height) =
-    get_greeting_height(greeter, container_padding, 0);
+    get_greeting_height(greeter, width, container_padding, 0);
 
   let should_display_answer = greeter.mode == Mode::Password;
 
@@ -105,25 +108,42 @@ pub fn draw_with_area(
   };
 
   let username = greeter.username.get();
-  let username_value_text = Span::from(username);
-  let username_value =
-    Paragraph::new(username_value_text).style(theme.of(&[Themed::Input]));
+  let mut username_value_text = vec![Span::from(username)];
+
+  // Show the offered completion as dimmed text after the typed username, when
+  // the cursor is there.
+  if greeter.mode == Mode::Username
+    && greeter.cursor_offset == 0
+    && let Some((_, completion, _)) = greeter.username_completion()
+  {
+    username_value_text.push(Span::styled(
+      completion,
+      Style::default().add_modifier(Modifier::DIM),
+    ));
+  }
+
+  let username_value = Paragraph::new(Line::from(username_value_text))
+    .style(theme.of(&[Themed::Input]));
+
+  // Text entries that can be clicked, which are only known once drawn.
+  let mut targets = Vec::new();
 
   match greeter.mode {
     Mode::Username | Mode::Password | Mode::Action => {
       f.render_widget(username_label, chunks[USERNAME_INDEX]);
 
       if !greeter.user_menu || !greeter.username.value.is_empty() {
-        f.render_widget(
-          username_value,
-          Rect::new(
-            1 + chunks[USERNAME_INDEX].x
-              + fl!("username").chars().count() as u16,
-            chunks[USERNAME_INDEX].y,
-            get_input_width(greeter, width, &Some(fl!("username"))),
-            1,
-          ),
+        let username_area = Rect::new(
+          1 + chunks[USERNAME_INDEX].x + fl!("username").width() as u16,
+          chunks[USERNAME_INDEX].y,
+          get_input_width(width, &Some(fl!("username"))),
+          1,
         );
+
+        f.render_widget(username_value, username_area);
+        targets.push((username_area, Target::Field(Mode::Username)));
+      } else {
+        targets.push((chunks[USERNAME_INDEX], Target::Field(Mode::Username)));
       }
 
       let answer_text = if greeter.working {
@@ -139,44 +159,42 @@ pub fn draw_with_area(
       {
         f.render_widget(answer_label, chunks[ANSWER_INDEX]);
 
+        let answer_area = Rect::new(
+          chunks[ANSWER_INDEX].x + greeter.prompt_width() as u16,
+          chunks[ANSWER_INDEX].y,
+          get_input_width(width, &greeter.prompt),
+          1,
+        );
+
         if !greeter.asking_for_secret || greeter.secret_display.show() {
-          let value = match (greeter.asking_for_secret, &greeter.secret_display)
-          {
-            (true, SecretDisplay::Character(pool)) => {
-              if pool.chars().count() == 1 {
-                pool.repeat(greeter.buffer.chars().count())
-              } else {
-                let mut rng = StdRng::seed_from_u64(0);
-
-                greeter
-                  .buffer
-                  .chars()
-                  .map(|_| {
-                    pool
-                      .chars()
-                      .nth(rng.random_range(0..pool.chars().count()))
-                      .unwrap()
-                  })
-                  .collect()
-              }
-            },
-
-            _ => greeter.buffer.clone(),
-          };
-
-          let answer_value_text = Span::from(value);
+          let answer_value_text = Span::from(get_answer_value(greeter));
           let answer_value =
             Paragraph::new(answer_value_text).style(theme.of(&[Themed::Input]));
 
-          f.render_widget(
-            answer_value,
-            Rect::new(
-              chunks[ANSWER_INDEX].x + greeter.prompt_width() as u16,
-              chunks[ANSWER_INDEX].y,
-              get_input_width(greeter, width, &greeter.prompt),
-              1,
+          f.render_widget(answer_value, answer_area);
+        }
+
+        if greeter.mode == Mode::Password {
+          targets.push((answer_area, Target::Field(Mode::Password)));
+        }
+
+        // Warn about Caps Lock while a secret is typed, since it cannot be
+        // seen in the answer.
+        if greeter.mode == Mode::Password
+          && greeter.asking_for_secret
+          && greeter.locks.caps
+        {
+          let warning = Paragraph::new(Span::styled(
+            format!(
+              "{}{}",
+              greeter.glyphs.indicator(Indicator::Caps),
+              fl!("status_caps")
             ),
-          );
+            theme.of(&[Themed::ActionButton, Themed::Segment("caps_lock")]),
+          ))
+          .alignment(Alignment::Right);
+
+          f.render_widget(warning, chunks[ANSWER_INDEX]);
         }
       }
 
@@ -185,7 +203,7 @@ pub fn draw_with_area(
 
         f.render_widget(
           message,
-          Rect::new(x, y + height, width, message_height),
+          Rect::new(x, y + height, width, message_height).intersection(area),
         );
       }
     },
@@ -193,20 +211,22 @@ pub fn draw_with_area(
     _ => {},
   }
 
+  greeter.targets.extend(targets);
+
   match greeter.mode {
     Mode::Username => {
-      let username_length = greeter.username.get().chars().count();
-      let offset = get_cursor_offset(greeter, username_length);
+      let username = greeter.username.get().to_string();
+      let offset = get_cursor_offset(greeter, &username);
 
       Ok((
-        2 + cursor.x + fl!("username").chars().count() as u16 + offset as u16,
+        2 + cursor.x + fl!("username").width() as u16 + offset,
         USERNAME_INDEX as u16 + cursor.y,
       ))
     },
 
     Mode::Password => {
-      let answer_length = greeter.buffer.chars().count();
-      let offset = get_cursor_offset(greeter, answer_length);
+      let answer = get_answer_value(greeter);
+      let offset = get_cursor_offset(greeter, &answer);
 
       if greeter.asking_for_secret && !greeter.secret_display.show() {
         Ok((
@@ -215,7 +235,7 @@ pub fn draw_with_area(
         ))
       } else {
         Ok((
-          1 + cursor.x + greeter.prompt_width() as u16 + offset as u16,
+          1 + cursor.x + greeter.prompt_width() as u16 + offset,
           ANSWER_INDEX as u16 + prompt_padding + cursor.y - 1,
         ))
       }
@@ -224,3 +244,30 @@ pub fn draw_with_area(
     _ => Ok((1, 1)),
   }
 }
+
+// Returns the answer as it should be displayed, with one character of the
+// configured pool per grapheme when it is a secret.
+pub(super) fn get_answer_value(greeter: &Greeter) -> String {
+  match (greeter.asking_for_secret, &greeter.secret_display) {
+    (true, SecretDisplay::Character(pool)) => {
+      let graphemes = greeter.buffer.graphemes(true).count();
+
+      if pool.chars().count() == 1 {
+        pool.repeat(graphemes)
+      } else {
+        let mut rng = StdRng::seed_from_u64(0);
+
+        (0..graphemes)
+          .map(|_| {
+            pool
+              .chars()
+              .nth(rng.random_range(0..pool.chars().count()))
+              .unwrap()
+          })
+          .collect()
+      }
+    },
+
+    _ => greeter.buffer.clone(),
+  }
+}
@@ -0,0 +1,394 @@
+use std::time::Duration;
+
+use crossterm::event::KeyCode;
+use tui::{
+  layout::{Alignment, Constraint, Direction, Layout, Rect},
+  text::{Line, Span},
+  widgets::Paragraph,
+};
+
+use super::{
+  Frame,
+  common::{
+    glyphs::Indicator,
+    style::{Theme, Themed},
+    target::Target,
+  },
+  get_time,
+  sessions::SessionSource,
+  util::buttonize,
+  widgets::{battery_label, battery_text},
+};
+use crate::{
+  Greeter,
+  Mode,
+  config::{StatusBarConfig, StatusSegment},
+  info::{get_hostname, get_kernel_release, get_keyboard_layout, get_uptime},
+  network::get_address,
+};
+
+const STATUSBAR_LEFT_INDEX: usize = 1;
+const STATUSBAR_RIGHT_INDEX: usize = 2;
+
+enum Button {
+  Command,
+  Session,
+  Power,
+  Vts,
+  Layouts,
+  Other,
+}
+
+// Draws the status bar and returns where its buttons are.
+pub fn draw_status_bar(
+  greeter: &Greeter,
+  f: &mut Frame,
+  area: Rect,
+) -> Vec<(Rect, Target)> {
+  let default_config = StatusBarConfig::default();
+  let config = match greeter.loaded_config {
+    Some(ref config) => &config.status_bar,
+    None => &default_config,
+  };
+
+  let spans_left = segments_spans(greeter, &config.left);
+  let spans_right = segments_spans(greeter, &config.right);
+
+  let status_left =
+    Line::from_iter(spans_left.iter().map(|(span, _)| span.clone()));
+  let status_right =
+    Line::from_iter(spans_right.iter().map(|(span, _)| span.clone()));
+
+  let status_block_size_right = match status_right.width() {
+    0 => 0,
+    width => 1 + width as u16,
+  };
+
+  let status_chunks = Layout::default()
+    .direction(Direction::Horizontal)
+    .constraints(
+      [
+        Constraint::Length(greeter.window_padding()),
+        Constraint::Min(0),
+        Constraint::Length(status_block_size_right),
+        Constraint::Length(greeter.window_padding()),
+      ]
+      .as_ref(),
+    )
+    .split(area);
+
+  f.render_widget(
+    Paragraph::new(status_left),
+    status_chunks[STATUSBAR_LEFT_INDEX],
+  );
+  let left = status_chunks[STATUSBAR_LEFT_INDEX];
+  let right = status_chunks[STATUSBAR_RIGHT_INDEX];
+  let right_start =
+    right.x + right.width.saturating_sub(status_right.width() as u16);
+
+  f.render_widget(
+    Paragraph::new(status_right).alignment(Alignment::Right),
+    right,
+  );
+
+  let mut targets = spans_targets(&spans_left, left, left.x);
+  targets.extend(spans_targets(&spans_right, right, right_start));
+
+  targets
+}
+
+// Areas of the spans that can be clicked, when drawn one after the other from
+// the provided column.
+fn spans_targets(
+  spans: &[(Span, Option<Target>)],
+  area: Rect,
+  start: u16,
+) -> Vec<(Rect, Target)> {
+  let mut targets = Vec::new();
+  let mut x = start;
+
+  for (span, target) in spans {
+    let width = span.width() as u16;
+
+    if let Some(target) = target {
+      targets
+        .push((Rect::new(x, area.y, width, 1).intersection(area), *target));
+    }
+
+    x = x.saturating_add(width);
+  }
+
+  targets
+}
+
+// Whether either side of the status bar shows the provided segment.
+pub fn has_segment(greeter: &Greeter, segment: &StatusSegment) -> bool {
+  let default_config = StatusBarConfig::default();
+  let config = match greeter.loaded_config {
+    Some(ref config) => &config.status_bar,
+    None => &default_config,
+  };
+
+  config.left.contains(segment) || config.right.contains(segment)
+}
+
+// Spans of every segment of one side of the status bar, leaving out those that
+// have nothing to show, along with what clicking them does.
+fn segments_spans<'s>(
+  greeter: &Greeter,
+  segments: &[StatusSegment],
+) -> Vec<(Span<'s>, Option<Target>)> {
+  let mut spans = Vec::new();
+
+  for segment in segments {
+    let segment_spans = segment_spans(greeter, segment);
+
+    if segment_spans.is_empty() {
+      continue;
+    }
+
+    if !spans.is_empty() {
+      spans.push((Span::from(" "), None));
+    }
+
+    spans.extend(segment_spans);
+  }
+
+  spans
+}
+
+fn segment_spans<'s>(
+  greeter: &Greeter,
+  segment: &StatusSegment,
+) -> Vec<(Span<'s>, Option<Target>)> {
+  let theme = &greeter.theme;
+  let name = segment.name();
+
+  let info = |text: Option<String>| {
+    match text.filter(|text| !text.is_empty()) {
+      Some(text) => {
+        vec![Span::styled(
+          text,
+          theme.of(&[Themed::Action, Themed::Segment(name)]),
+        )]
+      },
+      None => vec![],
+    }
+  };
+
+  let labelled = |label: String, text: Option<String>| {
+    match text.filter(|text| !text.is_empty()) {
+      Some(text) => {
+        vec![
+          status_label(theme, label),
+          status_value(greeter, theme, Button::Other, name, text),
+        ]
+      },
+      None => vec![],
+    }
+  };
+
+  let indicator = |active: bool, indicator: Indicator, label: String| {
+    match active {
+      true => {
+        vec![Span::styled(
+          format!("{}{label}", greeter.glyphs.indicator(indicator)),
+          theme.of(&[Themed::ActionButton, Themed::Segment(name)]),
+        )]
+      },
+      false => vec![],
+    }
+  };
+
+  let spans = match segment {
+    StatusSegment::KeyHints => return key_hints(greeter, theme),
+
+    StatusSegment::Session => {
+      let session_source_label = match greeter.session_source {
+        SessionSource::Session(_) => fl!("status_session"),
+        _ => fl!("status_command"),
+      };
+
+      let session_source = greeter.session_source.label(greeter).unwrap_or("-");
+
+      labelled(session_source_label, Some(session_source.to_string()))
+    },
+
+    StatusSegment::Hostname => info(Some(get_hostname())),
+    StatusSegment::Kernel => info(Some(get_kernel_release())),
+    StatusSegment::Ip => {
+      info(
+        get_address(None, false)
+          .or_else(|| get_address(None, true))
+          .map(|address| address.to_string()),
+      )
+    },
+    StatusSegment::KeyboardLayout => {
+      let layout = match greeter.layout {
+        Some(ref layout) => Some(layout.label()),
+        None => get_keyboard_layout(),
+      };
+
+      labelled(fl!("status_layout"), layout)
+    },
+
+    StatusSegment::CapsLock => {
+      indicator(greeter.locks.caps, Indicator::Caps, fl!("status_caps"))
+    },
+    StatusSegment::NumLock => {
+      indicator(greeter.locks.num, Indicator::Num, fl!("status_num"))
+    },
+    StatusSegment::ScrollLock => {
+      indicator(
+        greeter.locks.scroll,
+        Indicator::Scroll,
+        fl!("status_scroll"),
+      )
+    },
+
+    StatusSegment::Battery => {
+      let Some(ref power) = greeter.power_supply else {
+        return vec![];
+      };
+
+      let mut style = theme.of(&[Themed::Action, Themed::Segment(name)]);
+
+      if power.is_low(greeter.battery_low) {
+        style = style.patch(theme.of(&[Themed::BatteryLow]));
+      }
+
+      vec![
+        status_label(theme, battery_label(greeter, power)),
+        Span::styled(buttonize(greeter, &battery_text(power)), style),
+      ]
+    },
+    StatusSegment::Network => {
+      labelled(fl!("status_network"), Some(greeter.network.summary()))
+    },
+    StatusSegment::Uptime => {
+      labelled(fl!("status_uptime"), get_uptime().map(format_uptime))
+    },
+    StatusSegment::Clock => info(Some(get_time(greeter))),
+
+    StatusSegment::Command(command) => {
+      info(greeter.status_commands.get(command).cloned())
+    },
+  };
+
+  spans.into_iter().map(|span| (span, None)).collect()
+}
+
+// Keys opening the menus, and resetting the prompt, which can be clicked as
+// well.
+pub(super) fn key_hints<'s>(
+  greeter: &Greeter,
+  theme: &Theme,
+) -> Vec<(Span<'s>, Option<Target>)> {
+  let name = StatusSegment::KeyHints.name();
+
+  let hint = |label: String, button: Button, key: KeyCode, action: String| {
+    let target = Some(Target::Key(key));
+
+    vec![
+      (status_label(theme, label), target),
+      (status_value(greeter, theme, button, name, action), target),
+    ]
+  };
+
+  let fkey = |key: u8| (format!("F{key}"), KeyCode::F(key));
+
+  let mut hints = vec![hint(
+    "ESC".to_string(),
+    Button::Other,
+    KeyCode::Esc,
+    fl!("action_reset"),
+  )];
+
+  let (label, key) = fkey(greeter.kb_command);
+  hints.push(hint(label, Button::Command, key, fl!("action_command")));
+
+  let (label, key) = fkey(greeter.kb_sessions);
+  hints.push(hint(label, Button::Session, key, fl!("action_session")));
+
+  let (label, key) = fkey(greeter.kb_power);
+  hints.push(hint(label, Button::Power, key, fl!("action_power")));
+
+  if greeter.vt_switch {
+    let (label, key) = fkey(greeter.kb_vts);
+    hints.push(hint(label, Button::Vts, key, fl!("action_vts")));
+  }
+
+  if !greeter.layouts.options.is_empty() {
+    let (label, key) = fkey(greeter.kb_layouts);
+    hints.push(hint(label, Button::Layouts, key, fl!("action_layouts")));
+  }
+
+  hints.join(&(Span::from(" "), None))
+}
+
+fn format_uptime(uptime: Duration) -> String {
+  let minutes = uptime.as_secs() / 60;
+  let (days, hours, minutes) =
+    (minutes / 1440, minutes / 60 % 24, minutes % 60);
+
+  match (days, hours) {
+    (0, 0) => format!("{minutes}m"),
+    (0, _) => format!("{hours}h {minutes}m"),
+    _ => format!("{days}d {hours}h {minutes}m"),
+  }
+}
+
+fn status_label<'s, S>(theme: &Theme, text: S) -> Span<'s>
+where
+  S: Into<String>,
+{
+  Span::styled(text.into(), theme.of(&[Themed::ActionButton]))
+}
+
+fn status_value<'s, S>(
+  greeter: &Greeter,
+  theme: &Theme,
+  button: Button,
+  segment: &'static str,
+  text: S,
+) -> Span<'s>
+where
+  S: Into<String>,
+{
+  let relevant_mode = match button {
+    Button::Command => Mode::Command,
+    Button::Session => Mode::Sessions,
+    Button::Power => Mode::Power,
+    Button::Vts => Mode::Vts,
+    Button::Layouts => Mode::Layouts,
+
+    _ => {
+      return Span::from(buttonize(greeter, &text.into()))
+        .style(theme.of(&[Themed::Action, Themed::Segment(segment)]));
+    },
+  };
+
+  let style = match greeter.mode == relevant_mode {
+    true => theme.of(&[Themed::ActionButton, Themed::Selected]),
+    false => theme.of(&[Themed::Action, Themed::Segment(segment)]),
+  };
+
+  Span::from(buttonize(greeter, &text.into())).style(style)
+}
+
+#[cfg(test)]
+mod tests {
+  use std::time::Duration;
+
+  use super::format_uptime;
+
+  #[test]
+  fn uptime() {
+    assert_eq!(format_uptime(Duration::from_secs(59)), "0m");
+    assert_eq!(format_uptime(Duration::from_secs(3 * 3600 + 120)), "3h 2m");
+    assert_eq!(
+      format_uptime(Duration::from_secs(2 * 86400 + 5 * 3600 + 7 * 60)),
+      "2d 5h 7m"
+    );
+  }
+}
@@ -1,26 +1,50 @@
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use tokio::sync::RwLock;
 use tui::{
   Terminal,
   buffer::Buffer,
   layout::{Constraint, Direction, Layout, Rect},
-  style::Color,
+  style::{Color, Modifier},
 };
+use unicode_width::UnicodeWidthStr;
 
 use crate::{
   Greeter,
+  IdleScreen,
   Mode,
-  config::{Config, ThemeConfig, WidgetPosition},
+  battery::{BatteryStatus, PowerSupply},
+  config::{
+    ColorDepthMode,
+    Config,
+    GlyphsMode,
+    StatusSegment,
+    WidgetPosition,
+  },
   integration::common::backend::TestBackend,
+  ipc::Ipc,
+  keyboard,
+  network::{Interface, NetworkStatus},
   ui,
-  ui::common::style::{Theme, Themed},
+  ui::{
+    common::{
+      color::ColorDepth,
+      glyphs::Glyphs,
+      masked::MaskedString,
+      menu::Menu,
+    },
+    sessions::Session,
+    users::User,
+  },
 };
 
 /// Create a test greeter with default configuration
 fn test_greeter() -> Arc<RwLock<Greeter>> {
   let mut greeter = Greeter::default();
   greeter.working = false;
+  greeter.color_depth = ColorDepth::TrueColor;
+  greeter.glyphs = Glyphs::Unicode;
   // Initialize config to avoid unwrap panics
   greeter.config = Greeter::options().parse(&[""]).ok();
   Arc::new(RwLock::new(greeter))
@@ -242,6 +266,45 @@ async fn test_username_prompt_renders() {
   assert!(found_username, "Username prompt should be rendered");
 }
 
+#[tokio::test]
+async fn test_username_completion_renders_dimmed() {
+  let greeter = test_greeter();
+  {
+    let mut g = greeter.write().await;
+    g.mode = Mode::Username;
+    g.username.value = "apo".into();
+    g.users.options = vec![User {
+      username: "apognu".into(),
+      name:     None,
+    }];
+  }
+
+  let buffer = render_ui(greeter.clone(), 80, 24).await;
+
+  let position = (0..24).find_map(|y| {
+    let line = get_line(&buffer, y, 80);
+
+    line
+      .find("apognu")
+      .map(|index| (line[..index].chars().count() as u16, y))
+  });
+
+  let (x, y) = position.expect("Username completion should be rendered");
+
+  assert!(!buffer[(x + 2, y)].modifier.contains(Modifier::DIM));
+  assert!(buffer[(x + 3, y)].modifier.contains(Modifier::DIM));
+
+  // Nothing is offered while the cursor is within the username.
+  greeter.write().await.cursor_offset = -1;
+
+  let buffer = render_ui(greeter, 80, 24).await;
+
+  assert!(
+    (0..24).all(|y| !get_line(&buffer, y, 80).contains("apognu")),
+    "Username completion should not be rendered before the cursor"
+  );
+}
+
 #[tokio::test]
 async fn test_password_prompt_renders() {
   let greeter = test_greeter();
@@ -434,6 +497,7 @@ async fn test_theme_border_color_applied() {
 
     let mut config = Config::default();
     config.theme.border = Some("red".to_string());
+    config.display.glyphs = GlyphsMode::Unicode;
 
     g.loaded_config = Some(config.clone());
     g.apply_config(&config);
@@ -598,6 +662,8 @@ async fn test_theme_hex_colors_applied() {
 
     let mut config = Config::default();
     config.theme.border = Some("#ff0000".to_string()); // Red in hex
+    config.display.color_depth = ColorDepthMode::Truecolor;
+    config.display.glyphs = GlyphsMode::Unicode;
 
     g.loaded_config = Some(config.clone());
     g.apply_config(&config);
@@ -635,6 +701,40 @@ async fn test_theme_hex_colors_applied() {
   );
 }
 
+#[tokio::test]
+async fn test_theme_colors_follow_color_depth() {
+  for (depth, expected) in [
+    (ColorDepthMode::Truecolor, Color::Rgb(255, 0, 0)),
+    (ColorDepthMode::Indexed, Color::Indexed(196)),
+    (ColorDepthMode::Basic, Color::Red),
+  ] {
+    let greeter = test_greeter();
+    {
+      let mut g = greeter.write().await;
+      g.mode = Mode::Username;
+
+      let mut config = Config::default();
+      config.theme.border = Some("#ff0000".to_string());
+      config.display.color_depth = depth.clone();
+      config.display.glyphs = GlyphsMode::Unicode;
+
+      g.loaded_config = Some(config.clone());
+      g.apply_config(&config);
+      g.apply_theme_config(&config.theme, None);
+    }
+
+    let buffer = render_ui(greeter, 80, 24).await;
+
+    let border = buffer
+      .content()
+      .iter()
+      .find(|cell| cell.symbol() == "┌")
+      .expect("Border should be rendered");
+
+    assert_eq!(border.fg, expected, "{depth:?}");
+  }
+}
+
 #[tokio::test]
 async fn test_theme_multiple_colors_applied() {
   let greeter = test_greeter();
@@ -690,6 +790,7 @@ async fn test_cli_theme_overrides_config_theme() {
 
     let mut config = Config::default();
     config.theme.border = Some("red".to_string());
+    config.display.glyphs = GlyphsMode::Unicode;
 
     // CLI theme overrides with blue
     g.loaded_config = Some(config.clone());
@@ -780,3 +881,692 @@ async fn test_theme_with_time_widget() {
     "Theme color (lightred) should be applied somewhere in the UI"
   );
 }
+
+/// Render UI and return the buffer along with the cursor position
+async fn render_ui_with_cursor(
+  greeter: Arc<RwLock<Greeter>>,
+  width: u16,
+  height: u16,
+) -> (Buffer, (u16, u16)) {
+  let (backend, buffer, _rx) = TestBackend::new(width, height);
+  let mut terminal = Terminal::new(backend).unwrap();
+
+  ui::draw(greeter.clone(), &mut terminal).await.unwrap();
+
+  let cursor = terminal.get_cursor_position().unwrap();
+  let locked_buffer = buffer.lock().unwrap();
+
+  (locked_buffer.clone(), (cursor.x, cursor.y))
+}
+
+/// Send key presses to the greeter
+async fn press_keys(greeter: &Arc<RwLock<Greeter>>, keys: &[KeyCode]) {
+  for key in keys {
+    keyboard::handle(
+      greeter.clone(),
+      KeyEvent::new(*key, KeyModifiers::empty()),
+      Ipc::new(),
+    )
+    .await
+    .unwrap();
+  }
+}
+
+/// Find the column right after a label, and the line it is on
+fn find_after_label(buffer: &Buffer, label: &str) -> (u16, u16) {
+  (0..buffer.area.height)
+    .find_map(|y| {
+      let line = get_line(buffer, y, buffer.area.width);
+
+      line.find(label).map(|index| {
+        let x = line[..index].chars().count() + label.width();
+
+        (x as u16, y)
+      })
+    })
+    .expect("Label should be rendered")
+}
+
+#[tokio::test]
+async fn test_username_cursor_uses_display_width() {
+  let greeter = test_greeter();
+
+  press_keys(&greeter, &[KeyCode::Char('名'), KeyCode::Char('前')]).await;
+
+  let (buffer, cursor) = render_ui_with_cursor(greeter.clone(), 80, 24).await;
+  let (x, y) = find_after_label(&buffer, "Username:");
+
+  assert_eq!(buffer[(x + 1, y)].symbol(), "名");
+  assert_eq!(cursor, (x + 1 + 4, y));
+
+  press_keys(&greeter, &[KeyCode::Left]).await;
+
+  let (_, cursor) = render_ui_with_cursor(greeter.clone(), 80, 24).await;
+
+  assert_eq!(cursor, (x + 1 + 2, y));
+
+  press_keys(&greeter, &[KeyCode::Char('e')]).await;
+
+  assert_eq!(greeter.read().await.username.value, "名e前");
+
+  let (_, cursor) = render_ui_with_cursor(greeter.clone(), 80, 24).await;
+
+  assert_eq!(cursor, (x + 1 + 3, y));
+}
+
+#[tokio::test]
+async fn test_username_edits_whole_graphemes() {
+  let greeter = test_greeter();
+
+  press_keys(&greeter, &[
+    KeyCode::Char('e'),
+    KeyCode::Char('\u{301}'),
+    KeyCode::Char('x'),
+    KeyCode::Left,
+    KeyCode::Backspace,
+  ])
+  .await;
+
+  assert_eq!(greeter.read().await.username.value, "x");
+
+  // The cursor is still before "x", where the combining mark joins the "a".
+  press_keys(&greeter, &[KeyCode::Char('a'), KeyCode::Char('\u{308}')]).await;
+
+  assert_eq!(greeter.read().await.username.value, "a\u{308}x");
+
+  let (buffer, cursor) = render_ui_with_cursor(greeter.clone(), 80, 24).await;
+  let (x, y) = find_after_label(&buffer, "Username:");
+
+  assert_eq!(cursor, (x + 1 + 1, y));
+
+  press_keys(&greeter, &[KeyCode::Left, KeyCode::Delete]).await;
+
+  assert_eq!(greeter.read().await.username.value, "x");
+
+  let (_, cursor) = render_ui_with_cursor(greeter.clone(), 80, 24).await;
+
+  assert_eq!(cursor, (x + 1, y));
+}
+
+#[tokio::test]
+async fn test_password_and_command_cursor_use_display_width() {
+  let greeter = test_greeter();
+  {
+    let mut g = greeter.write().await;
+    g.mode = Mode::Password;
+    g.username.value = "apognu".into();
+    g.prompt = Some("Password: ".into());
+    g.asking_for_secret = false;
+  }
+
+  press_keys(&greeter, &[KeyCode::Char('👍'), KeyCode::Char('a')]).await;
+
+  let (buffer, cursor) = render_ui_with_cursor(greeter.clone(), 80, 24).await;
+  let (x, y) = find_after_label(&buffer, "Password: ");
+
+  assert_eq!(cursor, (x + 3, y));
+
+  {
+    let mut g = greeter.write().await;
+    g.mode = Mode::Command;
+    g.buffer = String::new();
+    g.cursor_offset = 0;
+  }
+
+  press_keys(&greeter, &[KeyCode::Char('日'), KeyCode::Char('本')]).await;
+
+  let (buffer, cursor) = render_ui_with_cursor(greeter.clone(), 80, 24).await;
+  let (x, y) = find_after_label(&buffer, "New command:");
+
+  assert_eq!(cursor, (x + 1 + 4, y));
+}
+
+#[tokio::test]
+async fn test_layout_tree_logo_beside_prompt() {
+  let greeter = test_greeter();
+  {
+    let mut g = greeter.write().await;
+    g.mode = Mode::Username;
+    g.greeting = Some("Welcome aboard".to_string());
+    g.logo = Some("/\\_/\\\n( o.o )\n > ^ <".to_string());
+
+    let config: Config = toml::from_str(
+      r#"
+        [layout.tree]
+        direction = "horizontal"
+        children = [
+          { widget = "logo", size = "30" },
+          { children = [
+            { widget = "greeting", size = "2" },
+            { widget = "prompt" },
+            { widget = "status_bar", size = "1" },
+          ] },
+        ]
+      "#,
+    )
+    .unwrap();
+    g.loaded_config = Some(config);
+  }
+
+  let buffer = render_ui(greeter, 110, 24).await;
+
+  let (logo_x, logo_y) = find_after_label(&buffer, "( o.o )");
+  assert!(logo_x <= 30, "Logo should be drawn in the left column");
+  assert!(
+    logo_y > 0 && logo_y < 23,
+    "Logo should be vertically centered"
+  );
+
+  let (username_x, _) = find_after_label(&buffer, "Username:");
+  assert!(
+    username_x > 30,
+    "Prompt should be drawn in the right column"
+  );
+
+  let greeting_lines: Vec<u16> = (0..24)
+    .filter(|y| get_line(&buffer, *y, 110).contains("Welcome aboard"))
+    .collect();
+  assert_eq!(
+    greeting_lines,
+    vec![0],
+    "Greeting should only be drawn by its own widget"
+  );
+
+  let status_line = get_line(&buffer, 23, 110);
+  assert!(status_line[..30].trim().is_empty());
+  assert!(status_line.contains("ESC"));
+}
+
+#[tokio::test]
+async fn test_theme_selected_style_and_border_type() {
+  let greeter = test_greeter();
+  {
+    let mut g = greeter.write().await;
+    g.mode = Mode::Users;
+    g.users.options = vec![
+      User {
+        username: "apognu".into(),
+        name:     None,
+      },
+      User {
+        username: "notashelf".into(),
+        name:     None,
+      },
+    ];
+    g.users.selected = 1;
+
+    let mut config = Config::default();
+    config.theme.selected = Some("bg:blue italic !reverse".to_string());
+    config.theme.border_type = Some("rounded".to_string());
+
+    g.loaded_config = Some(config.clone());
+    g.apply_theme_config(&config.theme, None);
+  }
+
+  let buffer = render_ui(greeter, 80, 24).await;
+
+  let (x, y) = find_after_label(&buffer, "notashelf");
+  let selected = &buffer[(x - 1, y)];
+
+  assert_eq!(selected.bg, Color::Blue);
+  assert!(selected.modifier.contains(Modifier::ITALIC));
+  assert!(!selected.modifier.contains(Modifier::REVERSED));
+
+  let (x, y) = find_after_label(&buffer, "apognu");
+  assert_eq!(buffer[(x - 1, y)].bg, Color::Reset);
+
+  let corners: Vec<&str> = (0..24)
+    .flat_map(|y| (0..80).map(move |x| (x, y)))
+    .map(|position| buffer[position].symbol())
+    .filter(|symbol| ["╭", "╮", "╰", "╯", "┌"].contains(symbol))
+    .collect();
+
+  assert_eq!(corners, vec!["╭", "╮", "╰", "╯"]);
+}
+
+#[tokio::test]
+async fn test_ascii_glyphs() {
+  let greeter = test_greeter();
+  {
+    let mut g = greeter.write().await;
+    g.mode = Mode::Username;
+
+    let mut config = Config::default();
+    config.display.glyphs = GlyphsMode::Ascii;
+    config.theme.border_type = Some("rounded".to_string());
+
+    g.loaded_config = Some(config.clone());
+    g.apply_config(&config);
+    g.apply_theme_config(&config.theme, None);
+  }
+
+  let buffer = render_ui(greeter, 80, 24).await;
+
+  let symbols: Vec<&str> = (0..24)
+    .flat_map(|y| (0..80).map(move |x| (x, y)))
+    .map(|position| buffer[position].symbol())
+    .collect();
+
+  assert!(
+    symbols.iter().all(|symbol| symbol.is_ascii()),
+    "only ASCII characters should be drawn"
+  );
+
+  let (x, y) = find_after_label(&buffer, "+[ ");
+
+  assert!(get_line(&buffer, y, 80)[x as usize..].contains(" ]-"));
+}
+
+#[tokio::test]
+async fn test_big_clock_widget() {
+  let greeter = test_greeter();
+  {
+    let mut g = greeter.write().await;
+    g.big_clock = true;
+    g.big_clock_format = Some("12:34".to_string());
+    g.big_clock_date = Some("on the wall".to_string());
+  }
+
+  let buffer = render_ui(greeter.clone(), 80, 24).await;
+
+  let lines: Vec<String> = (0..24).map(|y| get_line(&buffer, y, 80)).collect();
+  let top = lines
+    .iter()
+    .position(|line| line.contains("████   ██████    ██████ ██  ██"))
+    .expect("Big clock digits should be rendered");
+
+  assert!(lines[top + 1].contains("  ██       ██ ██     ██ ██  ██"));
+  assert!(lines[top + 6].contains("on the wall"));
+
+  // The time is written as text when the digits do not fit.
+  let buffer = render_ui(greeter, 20, 24).await;
+  let lines: Vec<String> = (0..24).map(|y| get_line(&buffer, y, 20)).collect();
+
+  assert!(lines.iter().any(|line| line.trim() == "12:34"));
+}
+
+#[tokio::test]
+async fn test_greeting_from_file() {
+  let dir = tempfile::tempdir().unwrap();
+  let path = dir.path().join("motd");
+  std::fs::write(&path, "\x1b[1;31mMaintenance\x1b[0m tonight").unwrap();
+
+  let greeter = test_greeter();
+  {
+    let mut g = greeter.write().await;
+
+    let mut config = Config::default();
+    config.display.greeting_file = Some(path.to_string_lossy().to_string());
+
+    g.apply_config(&config);
+  }
+
+  let buffer = render_ui(greeter, 80, 24).await;
+  let (x, y) = find_after_label(&buffer, "Maintenance tonight");
+
+  let cell = &buffer[(x - "Maintenance tonight".len() as u16, y)];
+
+  assert_eq!(cell.fg, Color::Red);
+  assert!(cell.modifier.contains(Modifier::BOLD));
+}
+
+#[tokio::test]
+async fn test_status_bar_segments() {
+  let greeter = test_greeter();
+  {
+    let mut g = greeter.write().await;
+
+    let mut config = Config::default();
+    config.status_bar.left = vec![StatusSegment::Command("whoami".into())];
+    config.status_bar.right = vec![
+      StatusSegment::Command("missing".into()),
+      StatusSegment::Command("date".into()),
+    ];
+    config
+      .theme
+      .status
+      .insert("command".to_string(), "green".to_string());
+
+    g.status_commands
+      .insert("whoami".to_string(), "on call: ops".to_string());
+    g.status_commands
+      .insert("date".to_string(), "week 42".to_string());
+
+    g.loaded_config = Some(config.clone());
+    g.apply_theme_config(&config.theme, None);
+  }
+
+  let buffer = render_ui(greeter, 80, 24).await;
+  let status_line = get_line(&buffer, 23, 80);
+
+  assert!(!status_line.contains("ESC"));
+  assert!(status_line.starts_with("on call: ops"));
+  assert!(status_line.trim_end().ends_with("week 42"));
+  assert_eq!(status_line.trim_end().len(), 80);
+
+  let (x, y) = find_after_label(&buffer, "week 42");
+
+  assert_eq!(buffer[(x - 1, y)].fg, Color::Green);
+}
+
+#[tokio::test]
+async fn test_battery_low_warning() {
+  let greeter = test_greeter();
+  {
+    let mut g = greeter.write().await;
+
+    let mut config = Config::default();
+    config.status_bar.right = vec![StatusSegment::Battery];
+    config.display.battery_low = Some(10);
+
+    g.power_supply = Some(PowerSupply {
+      capacity:  9,
+      status:    BatteryStatus::Discharging,
+      ac_online: false,
+    });
+
+    g.loaded_config = Some(config.clone());
+    g.apply_config(&config);
+  }
+
+  let buffer = render_ui(greeter.clone(), 80, 24).await;
+  let (x, y) = find_after_label(&buffer, "BAT 9%");
+
+  assert_eq!(buffer[(x - 1, y)].fg, Color::Red);
+
+  {
+    let mut g = greeter.write().await;
+
+    g.power_supply = Some(PowerSupply {
+      capacity:  9,
+      status:    BatteryStatus::Charging,
+      ac_online: true,
+    });
+  }
+
+  let buffer = render_ui(greeter, 80, 24).await;
+  let (x, y) = find_after_label(&buffer, "BAT 9% charging");
+
+  assert_ne!(buffer[(x - 1, y)].fg, Color::Red);
+}
+
+#[tokio::test]
+async fn test_network_segment() {
+  let greeter = test_greeter();
+  {
+    let mut g = greeter.write().await;
+
+    let mut config = Config::default();
+    config.status_bar.left = vec![StatusSegment::Network];
+
+    g.network = NetworkStatus {
+      interfaces:    vec![Interface {
+        name:      "enp3s0".to_string(),
+        up:        true,
+        addresses: vec!["192.168.1.20".parse().unwrap()],
+      }],
+      default_route: false,
+    };
+
+    g.loaded_config = Some(config.clone());
+  }
+
+  let buffer = render_ui(greeter, 80, 24).await;
+  let status_line = get_line(&buffer, 23, 80);
+
+  assert!(status_line.starts_with("NET enp3s0 192.168.1.20 (no route)"));
+}
+
+#[tokio::test]
+async fn test_caps_lock_password_warning() {
+  let greeter = test_greeter();
+  {
+    let mut g = greeter.write().await;
+    g.mode = Mode::Password;
+    g.username.value = "testuser".to_string();
+    g.prompt = Some("Password: ".into());
+    g.asking_for_secret = true;
+    g.locks.caps = true;
+  }
+
+  let buffer = render_ui(greeter.clone(), 80, 24).await;
+  let (_, y) = find_after_label(&buffer, "Password: ");
+
+  assert!(get_line(&buffer, y, 80).contains("CAPS LOCK"));
+
+  greeter.write().await.asking_for_secret = false;
+
+  let buffer = render_ui(greeter, 80, 24).await;
+  let (_, y) = find_after_label(&buffer, "Password: ");
+
+  assert!(!get_line(&buffer, y, 80).contains("CAPS LOCK"));
+}
+
+#[tokio::test]
+async fn test_idle_clock_screen() {
+  let greeter = test_greeter();
+  {
+    let mut g = greeter.write().await;
+    g.idle = true;
+    g.idle_screen = IdleScreen::Clock;
+  }
+
+  let buffer = render_ui(greeter, 80, 24).await;
+  let text: String = (0..24)
+    .map(|y| get_line(&buffer, y, 80))
+    .collect::<Vec<_>>()
+    .join("\n");
+
+  let time = text.trim();
+
+  assert_eq!(time.len(), 5, "Only the time should be drawn: {text}");
+  assert_eq!(time.chars().nth(2), Some(':'));
+  assert!(!text.contains("Username"));
+}
+
+#[tokio::test]
+async fn test_next_render() {
+  let greeter = test_greeter();
+
+  assert_eq!(ui::next_render(&*greeter.read().await), None);
+
+  greeter.write().await.time = true;
+
+  let next = ui::next_render(&*greeter.read().await);
+  assert!(next.is_some_and(|next| next <= Duration::from_secs(1)));
+
+  // Plain lines for screen readers have no clock to tick.
+  greeter.write().await.screen_reader = true;
+
+  assert_eq!(ui::next_render(&*greeter.read().await), None);
+
+  {
+    let mut g = greeter.write().await;
+    g.screen_reader = false;
+    g.time = false;
+    g.idle_timeout = Some(Duration::from_secs(30));
+  }
+
+  let next = ui::next_render(&*greeter.read().await);
+  assert!(next.is_some_and(|next| next > Duration::from_secs(25)));
+  assert!(next.is_some_and(|next| next <= Duration::from_secs(30)));
+
+  {
+    let mut g = greeter.write().await;
+    g.idle = true;
+    g.idle_screen = IdleScreen::Blank;
+  }
+
+  assert_eq!(ui::next_render(&*greeter.read().await), None);
+
+  greeter.write().await.idle_screen = IdleScreen::Clock;
+
+  let next = ui::next_render(&*greeter.read().await);
+  assert!(next.is_some_and(|next| next <= Duration::from_secs(60)));
+}
+
+#[tokio::test]
+async fn test_greeting_reflows_on_narrow_terminal() {
+  let greeter = test_greeter();
+  {
+    let mut g = greeter.write().await;
+    g.greeting = Some(
+      ["Lorem ipsum dolor sit amet,", "consectetur adipiscing elit"].join(" "),
+    );
+  }
+
+  let buffer = render_ui(greeter, 30, 24).await;
+  let text: String = (0..24)
+    .map(|y| get_line(&buffer, y, 30))
+    .collect::<Vec<_>>()
+    .join("\n");
+
+  assert!(text.contains("Lorem"), "Greeting should be shown: {text}");
+  assert!(text.contains("elit"), "Greeting should be wrapped: {text}");
+  assert!(text.contains("Username:"), "Prompt should be shown: {text}");
+}
+
+#[tokio::test]
+async fn test_tiny_terminal() {
+  let modes = [
+    Mode::Username,
+    Mode::Password,
+    Mode::Sessions,
+    Mode::Command,
+  ];
+
+  for mode in modes {
+    for (width, height) in [(20, 5), (10, 3), (1, 1)] {
+      let greeter = test_greeter();
+      {
+        let mut g = greeter.write().await;
+        g.mode = mode;
+        g.prompt = Some("Password:".to_string());
+        g.greeting = Some("Welcome".to_string());
+        g.message = Some("Authentication failed".to_string());
+      }
+
+      // Drawing must not underflow, however little room is left.
+      render_ui(greeter, width, height).await;
+    }
+  }
+}
+
+#[tokio::test]
+async fn test_screen_reader_lines() {
+  let greeter = test_greeter();
+  {
+    let mut g = greeter.write().await;
+    g.screen_reader = true;
+    g.greeting = Some("Welcome".to_string());
+    g.username = MaskedString::from("apognu".to_string(), None);
+  }
+
+  let lines = |buffer: Buffer| -> Vec<String> {
+    (0..buffer.area.height)
+      .map(|y| {
+        get_line(&buffer, y, buffer.area.width)
+          .trim_end()
+          .to_string()
+      })
+      .filter(|line| !line.is_empty())
+      .collect()
+  };
+
+  let screen = lines(render_ui(greeter.clone(), 60, 12).await);
+
+  assert_eq!(screen[0], "Welcome");
+  assert!(
+    screen[1].contains("F3"),
+    "Key hints should follow: {screen:?}"
+  );
+  assert_eq!(screen[2..], ["Username: apognu"]);
+
+  {
+    let mut g = greeter.write().await;
+    g.mode = Mode::Password;
+    g.prompt = Some("Password: ".to_string());
+    g.asking_for_secret = true;
+    g.message = Some("Hello there".to_string());
+    g.buffer = "hunter2".to_string();
+  }
+
+  let screen = lines(render_ui(greeter.clone(), 60, 12).await);
+
+  assert_eq!(screen[2..], [
+    "Username: apognu",
+    "Hello there",
+    "Password:"
+  ]);
+
+  {
+    let mut g = greeter.write().await;
+    g.previous_mode = Mode::Password;
+    g.mode = Mode::Sessions;
+    g.sessions = Menu::<Session> {
+      title:    "Sessions".to_string(),
+      options:  vec![
+        Session {
+          name: "Sway".to_string(),
+          ..Default::default()
+        },
+        Session {
+          name: "Plasma".to_string(),
+          ..Default::default()
+        },
+      ],
+      selected: 1,
+    };
+  }
+
+  let screen = lines(render_ui(greeter.clone(), 60, 12).await);
+
+  assert_eq!(screen[2..], [
+    "Username: apognu",
+    "Hello there",
+    "Password:",
+    "Sessions",
+    "  Sway",
+    "> Plasma"
+  ]);
+
+  greeter.write().await.mode = Mode::Password;
+
+  let screen = lines(render_ui(greeter.clone(), 60, 12).await);
+
+  // The secret was never shown, and is not kept either.
+  assert_eq!(screen[2..], [
+    "Username: apognu",
+    "Hello there",
+    "Password:",
+    "Sessions: Plasma",
+    "Password:"
+  ]);
+}
+
+#[tokio::test]
+async fn test_screen_reader_pages() {
+  let greeter = test_greeter();
+  greeter.write().await.screen_reader = true;
+
+  for attempt in 0..10 {
+    {
+      let mut g = greeter.write().await;
+      g.mode = Mode::Username;
+      g.message = Some(format!("Attempt {attempt} failed"));
+    }
+
+    let buffer = render_ui(greeter.clone(), 40, 8).await;
+    let text: String = (0..8)
+      .map(|y| get_line(&buffer, y, 40))
+      .collect::<Vec<_>>()
+      .join("\n");
+
+    // The latest message and the prompt are always in sight.
+    assert!(
+      text.contains(&format!("Attempt {attempt} failed")),
+      "{text}"
+    );
+    assert!(text.contains("Username:"), "{text}");
+  }
+}
@@ -2,12 +2,41 @@ use std::borrow::Cow;
 
 use super::common::menu::MenuItem;
 
+// How the user menu gets populated.
+#[derive(SmartDefault, Debug, Copy, Clone, PartialEq)]
+pub enum UserListing {
+  // Users are being enumerated in the background.
+  Loading,
+  // All users within the configured UID range were found.
+  #[default]
+  Complete,
+  // Users could not all be enumerated, and are looked up as they are typed.
+  Lookup,
+}
+
 #[derive(Default, Clone)]
 pub struct User {
   pub username: String,
   pub name:     Option<String>,
 }
 
+impl User {
+  // Returns what is left of this user's login or full name after the provided
+  // text, if either starts with it, ignoring case. The flag tells whether the
+  // full name was matched.
+  pub fn complete(&self, prefix: &str) -> Option<(&str, bool)> {
+    if let Some(rest) = strip_prefix_ignore_case(&self.username, prefix) {
+      return Some((rest, false));
+    }
+
+    self
+      .name
+      .as_deref()
+      .and_then(|name| strip_prefix_ignore_case(name, prefix))
+      .map(|rest| (rest, true))
+  }
+}
+
 impl MenuItem for User {
   fn format(&self) -> Cow<'_, str> {
     match &self.name {
@@ -16,3 +45,40 @@ impl MenuItem for User {
     }
   }
 }
+
+fn strip_prefix_ignore_case<'a>(
+  text: &'a str,
+  prefix: &str,
+) -> Option<&'a str> {
+  let mut chars = text.char_indices();
+
+  for p in prefix.chars() {
+    match chars.next() {
+      Some((_, c)) if c.to_lowercase().eq(p.to_lowercase()) => {},
+      _ => return None,
+    }
+  }
+
+  match chars.next() {
+    Some((index, _)) => Some(&text[index..]),
+    None => Some(""),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::User;
+
+  #[test]
+  fn complete_login_and_name() {
+    let user = User {
+      username: "apognu".into(),
+      name:     Some("Antoine Popineau".into()),
+    };
+
+    assert_eq!(user.complete("apo"), Some(("gnu", false)));
+    assert_eq!(user.complete("APOGNU"), Some(("", false)));
+    assert_eq!(user.complete("antoine p"), Some(("opineau", true)));
+    assert_eq!(user.complete("bob"), None);
+  }
+}
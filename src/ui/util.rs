@@ -4,22 +4,32 @@ use tui::{
   text::Text,
   widgets::{Paragraph, Wrap},
 };
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+use crate::{
+  Greeter,
+  Mode,
+  config::{ContainerAnchor, LayoutWidget},
+  ui::layout::has_widget,
+};
 
-use crate::{Greeter, Mode};
+pub fn titleize(greeter: &Greeter, message: &str) -> String {
+  let (left, right) = greeter.glyphs.title();
 
-pub fn titleize(message: &str) -> String {
-  format!(" {message} ")
+  format!("{left}{message}{right}")
 }
 
-pub fn buttonize(message: &str) -> String {
-  format!(" {message}")
+pub fn buttonize(greeter: &Greeter, message: &str) -> String {
+  format!("{}{message}", greeter.glyphs.button())
 }
 
 // Determinew whether the cursor should be shown or hidden from the current
 // mode and configuration. Usually, we will show the cursor only when expecting
 // text entries from the user.
 pub fn should_hide_cursor(greeter: &Greeter) -> bool {
-  greeter.working
+  greeter.idle
+    || greeter.working
     || greeter.done
     || (greeter.user_menu
       && greeter.mode == Mode::Username
@@ -28,12 +38,14 @@ pub fn should_hide_cursor(greeter: &Greeter) -> bool {
     || greeter.mode == Mode::Users
     || greeter.mode == Mode::Sessions
     || greeter.mode == Mode::Power
+    || greeter.mode == Mode::Vts
+    || greeter.mode == Mode::Layouts
     || greeter.mode == Mode::Processing
     || greeter.mode == Mode::Action
 }
 
 // Computes the height of the main window where we display content, depending on
-// the mode and spacing configuration.
+// the mode and spacing configuration, and the width the greeting is wrapped at.
 //
 // +------------------------+
 // |                        | <- container padding
@@ -43,8 +55,8 @@ pub fn should_hide_cursor(greeter: &Greeter) -> bool {
 // | Password:              | <- password if prompt == Some(_)
 // |                        | <- container padding
 // +------------------------+
-pub fn get_height(greeter: &Greeter) -> u16 {
-  let (_, greeting_height) = get_greeting_height(greeter, 1, 0);
+pub fn get_height(greeter: &Greeter, width: u16) -> u16 {
+  let (_, greeting_height) = get_greeting_height(greeter, width, 1, 0);
   let container_padding = greeter.container_padding();
   let prompt_padding = greeter.prompt_padding();
 
@@ -58,100 +70,138 @@ pub fn get_height(greeter: &Greeter) -> u16 {
         None => (2 * container_padding) + 1,
       }
     },
-    Mode::Users | Mode::Sessions | Mode::Power | Mode::Processing => {
-      2 * container_padding
-    },
+    Mode::Users
+    | Mode::Sessions
+    | Mode::Power
+    | Mode::Vts
+    | Mode::Layouts
+    | Mode::Processing => 2 * container_padding,
   };
 
   match greeter.mode {
-    Mode::Command | Mode::Sessions | Mode::Power | Mode::Processing => initial,
+    Mode::Command
+    | Mode::Sessions
+    | Mode::Power
+    | Mode::Vts
+    | Mode::Layouts
+    | Mode::Processing => initial,
     _ => initial + greeting_height,
   }
 }
 
-// Get the coordinates and size of the main window area, from the terminal size,
-// and the content we need to display.
+// Get the coordinates and size of the main window area, from the area given to
+// it, the content we need to display, and the configured anchoring.
 pub fn get_rect_bounds(
   greeter: &Greeter,
   area: Rect,
   items: usize,
 ) -> (u16, u16, u16, u16) {
-  let width = greeter.width();
-  let height: u16 = get_height(greeter) + items as u16;
+  let layout = greeter.loaded_config.as_ref().map(|config| &config.layout);
+  let anchor = layout.map(|layout| layout.anchor).unwrap_or_default();
+  let (offset_x, offset_y) =
+    layout.map_or((0, 0), |layout| (layout.offset_x, layout.offset_y));
+
+  // The container never outgrows the area, and its content is measured at the
+  // width it was clamped to, so text is wrapped again when the terminal
+  // shrinks.
+  let width = match layout.and_then(|layout| layout.width_percent) {
+    Some(percent) => percentage(area.width, percent),
+    None => greeter.width(),
+  }
+  .min(area.width);
 
-  let x = if width < area.width {
-    (area.width - width) / 2
-  } else {
-    0
-  };
-  let y = if height < area.height {
-    (area.height - height) / 2
-  } else {
-    0
-  };
+  let content_height = get_height(greeter, width) + items as u16;
 
-  let (x, width) = if (x + width) >= area.width {
-    (0, area.width)
-  } else {
-    (x, width)
-  };
-  let (y, height) = if (y + height) >= area.height {
-    (0, area.height)
-  } else {
-    (y, height)
+  let height = match layout.and_then(|layout| layout.height_percent) {
+    Some(percent) => percentage(area.height, percent).max(content_height),
+    None => content_height,
+  }
+  .min(area.height);
+
+  // Each anchor sits at the start, middle or end of either axis, expressed in
+  // halves of the free space.
+  let (horizontal, vertical) = match anchor {
+    ContainerAnchor::TopLeft => (0, 0),
+    ContainerAnchor::Top => (1, 0),
+    ContainerAnchor::TopRight => (2, 0),
+    ContainerAnchor::Left => (0, 1),
+    ContainerAnchor::Center => (1, 1),
+    ContainerAnchor::Right => (2, 1),
+    ContainerAnchor::BottomLeft => (0, 2),
+    ContainerAnchor::Bottom => (1, 2),
+    ContainerAnchor::BottomRight => (2, 2),
   };
 
-  (x, y, width, height)
+  let x = anchor_position(area.width - width, horizontal, offset_x);
+  let y = anchor_position(area.height - height, vertical, offset_y);
+
+  (area.x + x, area.y + y, width, height)
+}
+
+fn percentage(total: u16, percent: u16) -> u16 {
+  (u32::from(total) * u32::from(percent.min(100)) / 100) as u16
+}
+
+// Places a container within the free space along an axis, keeping it inside
+// the area whatever the offset.
+fn anchor_position(free: u16, halves: u16, offset: i16) -> u16 {
+  let position = i32::from(free) * i32::from(halves) / 2 + i32::from(offset);
+
+  position.clamp(0, i32::from(free)) as u16
 }
 
 // Computes the size of a text entry, from the container width and, if
 // applicable, the prompt length.
-pub fn get_input_width(
-  greeter: &Greeter,
-  width: u16,
-  label: &Option<String>,
-) -> u16 {
-  let width = std::cmp::min(greeter.width(), width);
-
+pub fn get_input_width(width: u16, label: &Option<String>) -> u16 {
   let label_width = match label {
     None => 0,
-    Some(label) => label.chars().count(),
+    Some(label) => label.width(),
   };
 
-  width - label_width as u16 - 4 - 1
+  width.saturating_sub(label_width as u16 + 4 + 1)
 }
 
-pub fn get_cursor_offset(greeter: &mut Greeter, length: usize) -> i16 {
-  let mut offset = length as i16 + greeter.cursor_offset;
+// Clamps the cursor offset, counted in graphemes, to the provided value and
+// returns the column the cursor should be displayed at within it.
+pub fn get_cursor_offset(greeter: &mut Greeter, value: &str) -> u16 {
+  let length = value.graphemes(true).count() as i16;
+  let mut offset = length + greeter.cursor_offset;
 
   if offset < 0 {
     offset = 0;
-    greeter.cursor_offset = -(length as i16);
+    greeter.cursor_offset = -length;
   }
 
-  if offset > length as i16 {
-    offset = length as i16;
+  if offset > length {
+    offset = length;
     greeter.cursor_offset = 0;
   }
 
-  offset
+  value
+    .graphemes(true)
+    .take(offset as usize)
+    .map(UnicodeWidthStr::width)
+    .sum::<usize>() as u16
 }
 
+// Measures the greeting shown in a prompt container of the provided width,
+// unless the layout places it on its own.
 pub fn get_greeting_height(
   greeter: &Greeter,
+  width: u16,
   padding: u16,
   fallback: u16,
 ) -> (Option<Paragraph<'_>>, u16) {
-  if let Some(greeting) = &greeter.greeting {
-    let width = greeter.width();
-
+  if let Some(greeting) = &greeter.greeting
+    && !has_widget(greeter, LayoutWidget::Greeting)
+  {
     let text = match greeting.clone().trim().into_text() {
       Ok(text) => text,
       Err(_) => Text::raw(greeting),
     };
 
     let paragraph = Paragraph::new(text.clone()).wrap(Wrap { trim: false });
-    let height = paragraph.line_count(width - (2 * padding)) + 1;
+    let height = paragraph.line_count(width.saturating_sub(2 * padding)) + 1;
 
     (Some(paragraph), height as u16)
   } else {
@@ -159,16 +209,42 @@ pub fn get_greeting_height(
   }
 }
 
+// Returns the lines of the greeting without their colors and styles, for
+// terminals showing plain text.
+pub fn get_plain_greeting(greeter: &Greeter) -> Vec<String> {
+  let Some(greeting) = &greeter.greeting else {
+    return Vec::new();
+  };
+
+  let text = match greeting.trim().into_text() {
+    Ok(text) => text,
+    Err(_) => Text::raw(greeting.trim()),
+  };
+
+  text
+    .lines
+    .iter()
+    .map(|line| {
+      line
+        .spans
+        .iter()
+        .map(|span| span.content.as_ref())
+        .collect()
+    })
+    .collect()
+}
+
+// Measures the message shown under a prompt container of the provided width.
 pub fn get_message_height(
   greeter: &Greeter,
+  width: u16,
   padding: u16,
   fallback: u16,
 ) -> (Option<Paragraph<'_>>, u16) {
   if let Some(message) = &greeter.message {
-    let width = greeter.width();
     let paragraph =
       Paragraph::new(message.trim_end()).wrap(Wrap { trim: true });
-    let height = paragraph.line_count(width - 4);
+    let height = paragraph.line_count(width.saturating_sub(4));
 
     (Some(paragraph), height as u16 + padding)
   } else {
@@ -189,6 +265,7 @@ mod test {
   use crate::{
     Greeter,
     Mode,
+    config::{Config, ContainerAnchor},
     ui::util::{get_greeting_height, get_height},
   };
 
@@ -202,7 +279,7 @@ mod test {
       Greeter::options().parse(&["--container-padding", "0"]).ok();
     greeter.mode = Mode::Username;
 
-    assert_eq!(get_height(&greeter), 3);
+    assert_eq!(get_height(&greeter, greeter.width()), 3);
   }
 
   // +-----------+
@@ -217,7 +294,7 @@ mod test {
       Greeter::options().parse(&["--container-padding", "1"]).ok();
     greeter.mode = Mode::Username;
 
-    assert_eq!(get_height(&greeter), 5);
+    assert_eq!(get_height(&greeter, greeter.width()), 5);
   }
 
   // +-----------+
@@ -235,7 +312,7 @@ mod test {
     greeter.greeting = Some("Hello".into());
     greeter.mode = Mode::Username;
 
-    assert_eq!(get_height(&greeter), 7);
+    assert_eq!(get_height(&greeter, greeter.width()), 7);
   }
 
   // +-----------+
@@ -256,7 +333,7 @@ mod test {
     greeter.mode = Mode::Password;
     greeter.prompt = Some("Password:".into());
 
-    assert_eq!(get_height(&greeter), 9);
+    assert_eq!(get_height(&greeter, greeter.width()), 9);
   }
 
   // +-----------+
@@ -277,7 +354,7 @@ mod test {
     greeter.mode = Mode::Password;
     greeter.prompt = Some("Password:".into());
 
-    assert_eq!(get_height(&greeter), 8);
+    assert_eq!(get_height(&greeter, greeter.width()), 8);
   }
 
   #[test]
@@ -294,6 +371,31 @@ mod test {
     assert_eq!(height, 6);
   }
 
+  #[test]
+  fn test_rect_bounds_anchored() {
+    let mut greeter = Greeter::default();
+    greeter.config = Greeter::options().parse(&["--width", "50"]).ok();
+
+    let mut config = Config::default();
+    config.layout.anchor = ContainerAnchor::BottomRight;
+    config.layout.offset_x = -5;
+    config.layout.offset_y = 3;
+    greeter.loaded_config = Some(config);
+
+    let area = Rect::new(10, 2, 100, 100);
+
+    assert_eq!(get_rect_bounds(&greeter, area, 1), (55, 96, 50, 6));
+
+    let config = greeter.loaded_config.as_mut().unwrap();
+    config.layout.anchor = ContainerAnchor::Left;
+    config.layout.offset_x = -5;
+    config.layout.offset_y = 0;
+    config.layout.width_percent = Some(30);
+    config.layout.height_percent = Some(50);
+
+    assert_eq!(get_rect_bounds(&greeter, area, 1), (10, 27, 30, 50));
+  }
+
   // | Username: __________________________ |
   // <--------------------------------------> width 40 (padding 1)
   //   <-------> prompt width 9
@@ -305,7 +407,7 @@ mod test {
       .parse(&["--width", "40", "--container-padding", "1"])
       .ok();
 
-    let input_width = get_input_width(&greeter, 40, &Some("Username:".into()));
+    let input_width = get_input_width(40, &Some("Username:".into()));
 
     assert_eq!(input_width, 26);
   }
@@ -318,7 +420,7 @@ mod test {
       .ok();
     greeter.greeting = Some("Hello World".into());
 
-    let (_, height) = get_greeting_height(&greeter, 1, 0);
+    let (_, height) = get_greeting_height(&greeter, greeter.width(), 1, 0);
 
     assert_eq!(height, 2);
   }
@@ -331,7 +433,7 @@ mod test {
       .ok();
     greeter.greeting = Some("Hello World".into());
 
-    let (_, height) = get_greeting_height(&greeter, 1, 0);
+    let (_, height) = get_greeting_height(&greeter, greeter.width(), 1, 0);
 
     assert_eq!(height, 3);
   }
@@ -344,7 +446,7 @@ mod test {
       .ok();
     greeter.greeting = Some("\x1b[31mHello\x1b[0m World".into());
 
-    let (text, height) = get_greeting_height(&greeter, 1, 0);
+    let (text, height) = get_greeting_height(&greeter, greeter.width(), 1, 0);
 
     let expected = Paragraph::new(Text::from(vec![Line::from(vec![
       Span::styled("Hello", Style::default().fg(Color::Red)),
@@ -364,7 +466,7 @@ mod test {
       .ok();
     greeter.greeting = Some("\x1b[31mHello\x1b[0m World".into());
 
-    let (text, height) = get_greeting_height(&greeter, 1, 0);
+    let (text, height) = get_greeting_height(&greeter, greeter.width(), 1, 0);
 
     let expected = Paragraph::new(Text::from(vec![Line::from(vec![
       Span::styled("Hello", Style::default().fg(Color::Red)),
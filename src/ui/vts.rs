@@ -0,0 +1,55 @@
+use std::borrow::Cow;
+
+use crate::ui::common::menu::MenuItem;
+
+// What selecting an entry of the VT menu should do.
+#[derive(SmartDefault, Debug, Copy, Clone, PartialEq)]
+pub enum VtTarget {
+  // Carry on with authentication and start a new session.
+  #[default]
+  NewSession,
+  // Bring an existing session's virtual terminal to the foreground.
+  Switch(u32),
+  // Bring the typed user's existing session to the foreground, instead of
+  // starting a new one.
+  Resume(u32),
+}
+
+#[derive(SmartDefault, Clone)]
+pub struct Vt {
+  pub target: VtTarget,
+  pub label:  String,
+}
+
+impl Vt {
+  pub fn switch(vt: u32, username: &str) -> Vt {
+    Vt {
+      target: VtTarget::Switch(vt),
+      label:  fl!(
+        "vt_switch",
+        vt = vt.to_string(),
+        username = username.to_string()
+      ),
+    }
+  }
+
+  pub fn resume(vt: u32, username: &str) -> Vt {
+    Vt {
+      target: VtTarget::Resume(vt),
+      ..Vt::switch(vt, username)
+    }
+  }
+
+  pub fn new_session() -> Vt {
+    Vt {
+      target: VtTarget::NewSession,
+      label:  fl!("vt_new_session"),
+    }
+  }
+}
+
+impl MenuItem for Vt {
+  fn format(&self) -> Cow<'_, str> {
+    Cow::Borrowed(&self.label)
+  }
+}
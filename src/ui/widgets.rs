@@ -0,0 +1,245 @@
+use ansi_to_tui::IntoText;
+use chrono::Local;
+use tui::{
+  layout::{Alignment, Rect},
+  style::{Color, Style},
+  text::{Line, Text},
+  widgets::{Block, Paragraph, Wrap},
+};
+use unicode_width::UnicodeWidthStr;
+
+use super::common::{
+  font::{self, FONT_HEIGHT},
+  style::Themed,
+};
+use crate::{
+  GreetAlign,
+  Greeter,
+  IdleScreen,
+  battery::{BatteryStatus, PowerSupply},
+  info::get_system_info,
+  ui::Frame,
+};
+
+const BIG_CLOCK_FORMAT: &str = "%H:%M";
+
+// Draws the greeting at the top of its area, when placed out of the prompt
+// container by the layout.
+pub fn draw_greeting(greeter: &Greeter, f: &mut Frame, area: Rect) {
+  let Some(ref greeting) = greeter.greeting else {
+    return;
+  };
+
+  let alignment = match greeter.greet_align() {
+    GreetAlign::Center => Alignment::Center,
+    GreetAlign::Left => Alignment::Left,
+    GreetAlign::Right => Alignment::Right,
+  };
+
+  let paragraph = Paragraph::new(ansi_text(greeting.trim()))
+    .alignment(alignment)
+    .style(greeter.theme.of(&[Themed::Greet]))
+    .wrap(Wrap { trim: false });
+
+  f.render_widget(paragraph, area);
+}
+
+pub fn draw_system_info(greeter: &Greeter, f: &mut Frame, area: Rect) {
+  let paragraph = Paragraph::new(get_system_info())
+    .alignment(Alignment::Center)
+    .style(greeter.theme.of(&[Themed::Time]));
+
+  f.render_widget(paragraph, area);
+}
+
+// Draws the logo in the middle of its area, keeping its lines aligned with one
+// another.
+pub fn draw_logo(greeter: &Greeter, f: &mut Frame, area: Rect) {
+  let Some(ref logo) = greeter.logo else {
+    return;
+  };
+
+  let text = ansi_text(logo.trim_end());
+  let width = (text.width() as u16).min(area.width);
+  let height = (text.height() as u16).min(area.height);
+
+  let logo_area = Rect::new(
+    area.x + (area.width - width) / 2,
+    area.y + (area.height - height) / 2,
+    width,
+    height,
+  );
+
+  f.render_widget(Paragraph::new(text), logo_area);
+}
+
+// Draws the battery charge in the middle of its area, standing out when the
+// battery is low.
+pub fn draw_battery(greeter: &Greeter, f: &mut Frame, area: Rect) {
+  let Some(ref power) = greeter.power_supply else {
+    return;
+  };
+
+  let style = match power.is_low(greeter.battery_low) {
+    true => greeter.theme.of(&[Themed::Time, Themed::BatteryLow]),
+    false => greeter.theme.of(&[Themed::Time]),
+  };
+
+  let text =
+    format!("{} {}", battery_label(greeter, power), battery_text(power));
+  let paragraph = Paragraph::new(text)
+    .alignment(Alignment::Center)
+    .style(style);
+
+  f.render_widget(paragraph, area);
+}
+
+pub fn battery_label(greeter: &Greeter, power: &PowerSupply) -> String {
+  let charging = power.status == BatteryStatus::Charging;
+
+  format!(
+    "{}{}",
+    greeter.glyphs.battery(power.capacity, charging),
+    fl!("status_battery")
+  )
+}
+
+// Charge of the batteries, followed by whether they are charging, full or
+// powered by an AC adapter.
+pub fn battery_text(power: &PowerSupply) -> String {
+  let state = match power.status {
+    BatteryStatus::Charging => Some(fl!("battery_charging")),
+    BatteryStatus::Full => Some(fl!("battery_full")),
+    _ if power.ac_online => Some(fl!("battery_ac")),
+    _ => None,
+  };
+
+  match state {
+    Some(state) => format!("{}% {state}", power.capacity),
+    None => format!("{}%", power.capacity),
+  }
+}
+
+// Draws every network interface with its state and addresses, and a warning
+// when there is no route out of the local networks.
+pub fn draw_network(greeter: &Greeter, f: &mut Frame, area: Rect) {
+  let network = &greeter.network;
+
+  let mut lines: Vec<Line> = network
+    .interfaces
+    .iter()
+    .map(|interface| {
+      let state = match interface.up {
+        true => fl!("network_up"),
+        false => fl!("network_down"),
+      };
+
+      let mut line = format!("{} {state}", interface.name);
+
+      for address in &interface.addresses {
+        line.push_str(&format!(" {address}"));
+      }
+
+      Line::from(line)
+    })
+    .collect();
+
+  if !network.default_route {
+    lines.push(Line::from(fl!("network_no_route")));
+  }
+
+  let paragraph = Paragraph::new(lines)
+    .alignment(Alignment::Center)
+    .style(greeter.theme.of(&[Themed::Time]))
+    .wrap(Wrap { trim: true });
+
+  f.render_widget(paragraph, area);
+}
+
+// Height of the big clock, with its date line and the blank line before it.
+pub fn big_clock_height(greeter: &Greeter) -> u16 {
+  match greeter.big_clock_date {
+    Some(_) => FONT_HEIGHT + 2,
+    None => FONT_HEIGHT,
+  }
+}
+
+// Draws the time with large digits in the middle of its area, followed by the
+// date if configured. The time is written with regular text instead if the
+// digits do not fit.
+pub fn draw_big_clock(greeter: &Greeter, f: &mut Frame, area: Rect) {
+  let now = Local::now();
+  let format = greeter
+    .big_clock_format
+    .as_deref()
+    .unwrap_or(BIG_CLOCK_FORMAT);
+  let time = now.format_localized(format, greeter.locale).to_string();
+
+  let digits = font::render(&time, greeter.glyphs.block());
+  let digits_width = digits.first().map_or(0, |line| line.width()) as u16;
+
+  let mut lines: Vec<Line> = match digits_width <= area.width {
+    true => digits.into_iter().map(Line::from).collect(),
+    false => vec![Line::from(time)],
+  };
+
+  if let Some(ref format) = greeter.big_clock_date {
+    lines.push(Line::default());
+    lines.push(Line::from(
+      now.format_localized(format, greeter.locale).to_string(),
+    ));
+  }
+
+  let height = (lines.len() as u16).min(area.height);
+  let clock_area = Rect::new(
+    area.x,
+    area.y + (area.height - height) / 2,
+    area.width,
+    height,
+  );
+
+  let paragraph = Paragraph::new(lines)
+    .alignment(Alignment::Center)
+    .style(greeter.theme.of(&[Themed::Time]));
+
+  f.render_widget(paragraph, clock_area);
+}
+
+// Draws the blanked screen, showing the time when asked to. The time is moved
+// every minute, so it does not burn into the screen either.
+pub fn draw_idle(greeter: &Greeter, f: &mut Frame) {
+  let area = f.area();
+  let black = Style::default().bg(Color::Black);
+
+  f.render_widget(Block::default().style(black), area);
+
+  if greeter.idle_screen != IdleScreen::Clock {
+    return;
+  }
+
+  let now = Local::now();
+  let time = now
+    .format_localized(BIG_CLOCK_FORMAT, greeter.locale)
+    .to_string();
+  let width = (time.width() as u16).min(area.width);
+
+  let minutes = (now.timestamp() / 60) as u64;
+  let x = (minutes * 37) % u64::from(area.width - width + 1);
+  let y = (minutes * 13) % u64::from(area.height.max(1));
+
+  let paragraph =
+    Paragraph::new(time).style(greeter.theme.of(&[Themed::Time]).patch(black));
+
+  f.render_widget(
+    paragraph,
+    Rect::new(area.x + x as u16, area.y + y as u16, width, 1)
+      .intersection(area),
+  );
+}
+
+fn ansi_text(text: &str) -> Text<'static> {
+  match text.into_text() {
+    Ok(text) => text,
+    Err(_) => Text::raw(text.to_string()),
+  }
+}
@@ -0,0 +1,194 @@
+use std::{
+  collections::HashMap,
+  error::Error,
+  fmt::{self, Display},
+};
+
+use crate::config::UsernameConfig;
+
+// Characters that cannot appear in a login, whatever the user database.
+const FORBIDDEN_CHARACTERS: &[char] = &[':', '/'];
+
+#[derive(Debug, PartialEq)]
+pub enum UsernameError {
+  Empty,
+  Forbidden(char),
+}
+
+impl Display for UsernameError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      UsernameError::Empty => write!(f, "{}", fl!("username_empty")),
+      UsernameError::Forbidden(c) => {
+        write!(
+          f,
+          "{}",
+          fl!(
+            "username_forbidden",
+            character = c.escape_default().to_string()
+          )
+        )
+      },
+    }
+  }
+}
+
+impl Error for UsernameError {}
+
+// Rules turning what was typed in the username field into the login to
+// authenticate.
+#[derive(SmartDefault)]
+pub struct UsernameRules {
+  #[default(true)]
+  pub trim:        bool,
+  pub lowercase:   bool,
+  pub strip_realm: bool,
+  pub map_domain:  bool,
+  // Lowercased alternative identifiers, and the login they stand for.
+  pub aliases:     HashMap<String, String>,
+}
+
+impl From<&UsernameConfig> for UsernameRules {
+  fn from(config: &UsernameConfig) -> UsernameRules {
+    UsernameRules {
+      trim:        config.trim,
+      lowercase:   config.lowercase,
+      strip_realm: config.strip_realm,
+      map_domain:  config.map_domain,
+      aliases:     config
+        .aliases
+        .iter()
+        .map(|(alias, login)| (alias.to_lowercase(), login.clone()))
+        .collect(),
+    }
+  }
+}
+
+impl UsernameRules {
+  // Applies the configured rules to the typed username. Aliases are matched
+  // before anything but trimming, and their target is used as is.
+  pub fn normalize(&self, input: &str) -> Result<String, UsernameError> {
+    let mut username = if self.trim { input.trim() } else { input }.to_string();
+
+    if let Some(login) = self.aliases.get(&username.to_lowercase()) {
+      username = login.clone();
+    } else {
+      if self.map_domain
+        && let Some((domain, user)) = username.split_once('\\')
+      {
+        username = format!("{user}@{domain}");
+      }
+
+      if self.strip_realm
+        && let Some((user, _)) = username.split_once('@')
+      {
+        username = user.to_string();
+      }
+
+      if self.lowercase {
+        username = username.to_lowercase();
+      }
+    }
+
+    validate(username)
+  }
+}
+
+fn validate(username: String) -> Result<String, UsernameError> {
+  if username.is_empty() {
+    return Err(UsernameError::Empty);
+  }
+
+  if username.starts_with('-') {
+    return Err(UsernameError::Forbidden('-'));
+  }
+
+  match username
+    .chars()
+    .find(|c| c.is_control() || FORBIDDEN_CHARACTERS.contains(c))
+  {
+    Some(c) => Err(UsernameError::Forbidden(c)),
+    None => Ok(username),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{UsernameError, UsernameRules};
+
+  #[test]
+  fn default_rules_only_trim() {
+    let rules = UsernameRules::default();
+
+    assert_eq!(rules.normalize("  Apognu "), Ok("Apognu".to_string()));
+    assert_eq!(
+      rules.normalize("apognu@example.com"),
+      Ok("apognu@example.com".to_string())
+    );
+  }
+
+  #[test]
+  fn realm_and_domain() {
+    let rules = UsernameRules {
+      lowercase: true,
+      map_domain: true,
+      ..Default::default()
+    };
+
+    assert_eq!(
+      rules.normalize("CORP\\Apognu"),
+      Ok("apognu@corp".to_string())
+    );
+
+    let rules = UsernameRules {
+      map_domain: true,
+      strip_realm: true,
+      ..Default::default()
+    };
+
+    assert_eq!(rules.normalize("CORP\\apognu"), Ok("apognu".to_string()));
+    assert_eq!(
+      rules.normalize("apognu@example.com"),
+      Ok("apognu".to_string())
+    );
+  }
+
+  #[test]
+  fn aliases() {
+    let rules = UsernameRules {
+      lowercase: true,
+      aliases: [("antoine@example.com".to_string(), "Apognu".to_string())]
+        .into(),
+      ..Default::default()
+    };
+
+    assert_eq!(
+      rules.normalize(" Antoine@Example.com"),
+      Ok("Apognu".to_string())
+    );
+    assert_eq!(rules.normalize("Bob"), Ok("bob".to_string()));
+  }
+
+  #[test]
+  fn invalid_usernames() {
+    let rules = UsernameRules {
+      strip_realm: true,
+      ..Default::default()
+    };
+
+    assert_eq!(rules.normalize("   "), Err(UsernameError::Empty));
+    assert_eq!(rules.normalize("@example.com"), Err(UsernameError::Empty));
+    assert_eq!(
+      rules.normalize("-apognu"),
+      Err(UsernameError::Forbidden('-'))
+    );
+    assert_eq!(
+      rules.normalize("apo:gnu"),
+      Err(UsernameError::Forbidden(':'))
+    );
+    assert_eq!(
+      rules.normalize("apo\tgnu"),
+      Err(UsernameError::Forbidden('\t'))
+    );
+  }
+}
@@ -0,0 +1,214 @@
+use std::{
+  error::Error,
+  fs::{File, OpenOptions},
+  io::Write,
+  os::fd::AsRawFd,
+  process::{Command, Output, Stdio},
+};
+
+use crate::ui::common::color::Palette;
+
+// Request codes from `<linux/vt.h>`.
+nix::ioctl_write_int_bad!(vt_activate, 0x5606);
+
+// Request codes from `<linux/kd.h>`.
+nix::ioctl_read_bad!(gio_cmap, 0x4B70, [u8; 48]);
+nix::ioctl_write_ptr_bad!(pio_cmap, 0x4B71, [u8; 48]);
+nix::ioctl_read_bad!(kd_getled, 0x4B31, u8);
+nix::ioctl_read_bad!(kd_gkbled, 0x4B64, u8);
+nix::ioctl_write_int_bad!(kd_skbled, 0x4B65);
+
+// Keyboard flags, as returned by `KDGKBLED` and `KDGETLED`.
+const LED_SCR: u8 = 0x01;
+const LED_NUM: u8 = 0x02;
+const LED_CAP: u8 = 0x04;
+// `KDGKBLED` returns the flags in the low bits, and their default value on
+// a console reset in the high bits.
+const LED_FLAGS_MASK: u8 = 0x77;
+
+// State of the lock keys of the console keyboard.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Locks {
+  pub caps:   bool,
+  pub num:    bool,
+  pub scroll: bool,
+}
+
+impl Locks {
+  fn from_flags(flags: u8) -> Locks {
+    Locks {
+      caps:   flags & LED_CAP != 0,
+      num:    flags & LED_NUM != 0,
+      scroll: flags & LED_SCR != 0,
+    }
+  }
+}
+
+// Brings the provided virtual terminal to the foreground.
+//
+// The request is issued on our controlling terminal, which `greetd` gives us
+// ownership of, and falls back to the console device otherwise. The switch is
+// only requested: waiting for it would hold the greeter, possibly forever if
+// the other VT never gives the console up.
+pub fn activate(vt: u32) -> Result<(), Box<dyn Error>> {
+  tracing::info!("activating VT {vt}");
+
+  let tty = console()?;
+
+  unsafe {
+    vt_activate(tty.as_raw_fd(), vt as i32)?;
+  }
+
+  Ok(())
+}
+
+// Reads the 16 colors palette of the console.
+pub fn get_palette() -> Result<Palette, Box<dyn Error>> {
+  let mut colors = [0u8; 48];
+
+  unsafe {
+    gio_cmap(console()?.as_raw_fd(), &mut colors)?;
+  }
+
+  let mut palette = Palette::default();
+
+  for (entry, rgb) in palette.iter_mut().zip(colors.chunks_exact(3)) {
+    *entry = (rgb[0], rgb[1], rgb[2]);
+  }
+
+  Ok(palette)
+}
+
+// Replaces the 16 colors palette of the console, which applies to the whole
+// screen at once.
+pub fn set_palette(palette: &Palette) -> Result<(), Box<dyn Error>> {
+  let mut colors = [0u8; 48];
+
+  for (rgb, entry) in colors.chunks_exact_mut(3).zip(palette) {
+    rgb.copy_from_slice(&[entry.0, entry.1, entry.2]);
+  }
+
+  unsafe {
+    pio_cmap(console()?.as_raw_fd(), &colors)?;
+  }
+
+  Ok(())
+}
+
+// Reads the state of the lock keys from the keyboard flags of the console, or
+// from its LEDs if the flags cannot be read.
+pub fn get_locks() -> Result<Locks, Box<dyn Error>> {
+  let tty = console()?;
+  let mut flags = 0u8;
+
+  unsafe {
+    if kd_gkbled(tty.as_raw_fd(), &mut flags).is_err() {
+      kd_getled(tty.as_raw_fd(), &mut flags)?;
+    }
+  }
+
+  Ok(Locks::from_flags(flags))
+}
+
+// Turns Num Lock on, leaving the other flags as they are.
+pub fn enable_numlock() -> Result<(), Box<dyn Error>> {
+  tracing::info!("enabling num lock");
+
+  let tty = console()?;
+  let mut flags = 0u8;
+
+  unsafe {
+    kd_gkbled(tty.as_raw_fd(), &mut flags)?;
+    kd_skbled(
+      tty.as_raw_fd(),
+      i32::from((flags & LED_FLAGS_MASK) | LED_NUM),
+    )?;
+  }
+
+  Ok(())
+}
+
+// Loads a console keymap, which applies to every virtual terminal.
+pub fn load_keymap(keymap: &str) -> Result<(), Box<dyn Error>> {
+  tracing::info!("loading keymap {keymap}");
+
+  let output = Command::new("loadkeys")
+    .args(["-q", "--", keymap])
+    .stdin(Stdio::null())
+    .output()?;
+
+  check_output("loadkeys", &output)
+}
+
+// Reads the keymap of the console, in the format `loadkeys` reads.
+pub fn dump_keymap() -> Result<String, Box<dyn Error>> {
+  let output = Command::new("dumpkeys").stdin(Stdio::null()).output()?;
+
+  check_output("dumpkeys", &output)?;
+
+  Ok(String::from_utf8(output.stdout)?)
+}
+
+// Loads back a keymap read with `dump_keymap`, which `loadkeys` reads from its
+// standard input.
+pub fn restore_keymap(keymap: &str) -> Result<(), Box<dyn Error>> {
+  tracing::info!("restoring the console keymap");
+
+  let mut child = Command::new("loadkeys")
+    .arg("-q")
+    .stdin(Stdio::piped())
+    .stdout(Stdio::null())
+    .stderr(Stdio::piped())
+    .spawn()?;
+
+  if let Some(mut stdin) = child.stdin.take() {
+    stdin.write_all(keymap.as_bytes())?;
+  }
+
+  let output = child.wait_with_output()?;
+
+  check_output("loadkeys", &output)
+}
+
+fn check_output(program: &str, output: &Output) -> Result<(), Box<dyn Error>> {
+  if !output.status.success() {
+    let error = String::from_utf8_lossy(&output.stderr);
+
+    return Err(match error.trim() {
+      "" => format!("{program} {}", output.status).into(),
+      error => error.into(),
+    });
+  }
+
+  Ok(())
+}
+
+fn console() -> Result<File, Box<dyn Error>> {
+  let tty = OpenOptions::new()
+    .read(true)
+    .write(true)
+    .open("/dev/tty")
+    .or_else(|_| OpenOptions::new().read(true).write(true).open("/dev/tty0"))?;
+
+  Ok(tty)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::Locks;
+
+  #[test]
+  fn locks_from_flags() {
+    assert_eq!(Locks::from_flags(0x00), Locks::default());
+    assert_eq!(Locks::from_flags(0x06), Locks {
+      caps:   true,
+      num:    true,
+      scroll: false,
+    });
+    assert_eq!(Locks::from_flags(0x21), Locks {
+      caps:   false,
+      num:    false,
+      scroll: true,
+    });
+  }
+}